@@ -4,7 +4,7 @@ pub mod bytecode;
 pub mod evmrunner;
 pub mod statetest;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(infer_subcommands = true)]
@@ -26,6 +26,16 @@ pub enum MainCmd {
     Btest(blockchaintest::Cmd),
 }
 
+/// Chain whose rules are used to execute the tests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Chain {
+    /// Ethereum mainnet.
+    #[default]
+    Eth,
+    /// OP Stack, with OP Stack fixtures and hardfork names.
+    Op,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
@@ -33,7 +33,7 @@ pub fn run(criterion: &mut Criterion) {
 
     let run_call_data = IBURNTPIX::runCall { seed, iterations }.abi_encode();
 
-    let db = init_db();
+    let db = init_db(false);
 
     let mut evm = Context::mainnet()
         .with_db(db)
@@ -56,6 +56,20 @@ pub fn run(criterion: &mut Criterion) {
         );
     });
 
+    let mut evm = Context::mainnet()
+        .with_db(init_db(true))
+        .modify_cfg_chained(|c| c.disable_nonce_check = true)
+        .build_mainnet();
+    evm.instruction.enable_fusion();
+
+    criterion.bench_function("burntpix-fused", |b| {
+        b.iter_batched(
+            || tx.clone(),
+            |input| evm.transact_one(input).unwrap(),
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
     //Collects the data and uses it to generate the svg after running the benchmark
     /*
     let tx_result = evm.replay().unwrap();
@@ -114,28 +128,49 @@ fn try_from_hex_to_u32(hex: &str) -> Result<u32, Box<dyn Error>> {
     u32::from_str_radix(trimmed, 16).map_err(|e| format!("Failed to parse hex: {e}").into())
 }
 
-fn insert_account_info(cache_db: &mut CacheDB<EmptyDB>, addr: Address, code: &str) {
+fn insert_account_info(cache_db: &mut CacheDB<EmptyDB>, addr: Address, code: &str, fused: bool) {
     let code = Bytes::from(hex::decode(code).unwrap());
     let code_hash = hex::encode(keccak256(&code));
+    let mut bytecode = Bytecode::new_raw(code);
+    if fused {
+        bytecode = bytecode.with_fusion();
+    }
     let account_info = AccountInfo::new(
         U256::from(0),
         0,
         B256::from_str(&code_hash).unwrap(),
-        Bytecode::new_raw(code),
+        bytecode,
     );
     cache_db.insert_account_info(addr, account_info);
 }
 
-fn init_db() -> CacheDB<EmptyDB> {
+/// Creates the database with the burntpix contracts, `fused` contracts are analyzed with fusion.
+fn init_db(fused: bool) -> CacheDB<EmptyDB> {
     let mut cache_db = CacheDB::new(EmptyDB::default());
 
-    insert_account_info(&mut cache_db, BURNTPIX_ADDRESS_ONE, BURNTPIX_BYTECODE_ONE);
-    insert_account_info(&mut cache_db, BURNTPIX_MAIN_ADDRESS, BURNTPIX_BYTECODE_TWO);
-    insert_account_info(&mut cache_db, BURNTPIX_ADDRESS_TWO, BURNTPIX_BYTECODE_THREE);
+    insert_account_info(
+        &mut cache_db,
+        BURNTPIX_ADDRESS_ONE,
+        BURNTPIX_BYTECODE_ONE,
+        fused,
+    );
+    insert_account_info(
+        &mut cache_db,
+        BURNTPIX_MAIN_ADDRESS,
+        BURNTPIX_BYTECODE_TWO,
+        fused,
+    );
+    insert_account_info(
+        &mut cache_db,
+        BURNTPIX_ADDRESS_TWO,
+        BURNTPIX_BYTECODE_THREE,
+        fused,
+    );
     insert_account_info(
         &mut cache_db,
         BURNTPIX_ADDRESS_THREE,
         BURNTPIX_BYTECODE_FOUR,
+        fused,
     );
 
     cache_db
@@ -41,6 +41,20 @@ pub fn run(criterion: &mut Criterion) {
             criterion::BatchSize::SmallInput,
         );
     });
+
+    let mut evm = Context::mainnet()
+        .with_db(BenchmarkDB::new_bytecode(bytecode.with_fusion()))
+        .modify_cfg_chained(|c| c.disable_nonce_check = true)
+        .build_mainnet();
+    evm.instruction.enable_fusion();
+
+    criterion.bench_function("snailtracer-fused", |b| {
+        b.iter_batched(
+            || tx.clone(),
+            |input| evm.transact_one(input).unwrap(),
+            criterion::BatchSize::SmallInput,
+        );
+    });
 }
 
 const BYTES: &str = include_str!("snailtracer.hex");
@@ -1,6 +1,8 @@
+mod op;
 pub mod post_block;
 pub mod pre_block;
 
+use super::Chain;
 use clap::Parser;
 
 use revm::{
@@ -15,8 +17,9 @@ use revm::{
     Context, Database, ExecuteCommitEvm, ExecuteEvm, InspectEvm, MainBuilder, MainContext,
 };
 use serde_json::json;
-use statetest_types::blockchain::{
-    Account, BlockchainTest, BlockchainTestCase, ForkSpec, Withdrawal,
+use statetest_types::{
+    blockchain::{Account, BlockchainTest, BlockchainTestCase, ForkSpec, Withdrawal},
+    op::OpBlockchainTest,
 };
 use std::{
     collections::BTreeMap,
@@ -50,6 +53,11 @@ pub struct Cmd {
     /// Output results in JSON format
     #[arg(long)]
     json: bool,
+    /// Chain of the tests
+    ///
+    /// OP Stack tests are built from payload attributes and keyed by the OP Stack hardfork names.
+    #[arg(long, value_enum, default_value_t = Chain::Eth)]
+    chain: Chain,
 }
 
 impl Cmd {
@@ -75,6 +83,7 @@ impl Cmd {
                 self.keep_going,
                 self.print_env_on_error,
                 self.json,
+                self.chain,
             )?;
         }
         Ok(())
@@ -104,6 +113,7 @@ fn run_tests(
     keep_going: bool,
     print_env_on_error: bool,
     json_output: bool,
+    chain: Chain,
 ) -> Result<(), Error> {
     let mut passed = 0;
     let mut failed = 0;
@@ -135,7 +145,7 @@ fn run_tests(
             continue;
         }
 
-        let result = run_test_file(&file_path, json_output, print_env_on_error);
+        let result = run_test_file(&file_path, json_output, print_env_on_error, chain);
 
         match result {
             Ok(test_count) => {
@@ -221,16 +231,39 @@ fn run_test_file(
     file_path: &Path,
     json_output: bool,
     print_env_on_error: bool,
+    chain: Chain,
 ) -> Result<usize, Error> {
     let content =
         fs::read_to_string(file_path).map_err(|e| Error::FileRead(file_path.to_path_buf(), e))?;
 
-    let blockchain_test: BlockchainTest = serde_json::from_str(&content)
-        .map_err(|e| Error::JsonDecode(file_path.to_path_buf(), e))?;
+    match chain {
+        Chain::Eth => {
+            let blockchain_test: BlockchainTest = serde_json::from_str(&content)
+                .map_err(|e| Error::JsonDecode(file_path.to_path_buf(), e))?;
+            run_test_cases(file_path, blockchain_test.0, json_output, |test_case| {
+                execute_blockchain_test(test_case, print_env_on_error, json_output)
+            })
+        }
+        Chain::Op => {
+            let blockchain_test: OpBlockchainTest = serde_json::from_str(&content)
+                .map_err(|e| Error::JsonDecode(file_path.to_path_buf(), e))?;
+            run_test_cases(file_path, blockchain_test.0, json_output, |test_case| {
+                op::execute_op_blockchain_test(test_case, print_env_on_error)
+            })
+        }
+    }
+}
 
+/// Run the test cases of a single file
+fn run_test_cases<T>(
+    file_path: &Path,
+    test_cases: BTreeMap<String, T>,
+    json_output: bool,
+    execute: impl Fn(&T) -> Result<(), TestExecutionError>,
+) -> Result<usize, Error> {
     let mut test_count = 0;
 
-    for (test_name, test_case) in blockchain_test.0 {
+    for (test_name, test_case) in test_cases {
         if json_output {
             // Output test start in JSON format
             let output = json!({
@@ -243,7 +276,7 @@ fn run_test_file(
             println!("  Running: {test_name}");
         }
         // Execute the blockchain test
-        let result = execute_blockchain_test(&test_case, print_env_on_error, json_output);
+        let result = execute(&test_case);
 
         match result {
             Ok(()) => {
@@ -1107,6 +1140,9 @@ pub enum TestExecutionError {
     #[error("Skipped fork: {0}")]
     SkippedFork(String),
 
+    #[error("Unknown fork: {0}")]
+    UnknownFork(String),
+
     #[error("Sender is required")]
     SenderRequired,
 
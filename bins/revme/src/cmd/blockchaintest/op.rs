@@ -0,0 +1,226 @@
+//! Execution of OP Stack blockchain tests
+//!
+//! Blocks are built from payload attributes and executed with
+//! [`OpBlockExecutor`], which applies the OP Stack block rules.
+
+use super::{print_error_with_state, validate_post_state, DebugInfo, TestExecutionError};
+use op_revm::{
+    block::{OpBlockError, OpBlockExecutor, OpParentBlock, OpPayloadAttributes},
+    estimate::estimation_envelope,
+    transaction::deposit::{DepositTransactionParts, DEPOSIT_TRANSACTION_TYPE},
+    DefaultOp, OpBuilder, OpSpecId, OpTransaction,
+};
+use revm::{
+    bytecode::Bytecode,
+    context::{cfg::CfgEnv, tx::TxEnv, ContextTr},
+    database::State,
+    handler::EvmTr,
+    primitives::HashMap,
+    state::AccountInfo,
+    Context,
+};
+use statetest_types::op::{OpBlock, OpBlockTransaction, OpBlockchainTestCase};
+
+/// Chain id of OP Mainnet.
+const OP_MAINNET_CHAIN_ID: u64 = 10;
+
+/// Execute a single OP Stack blockchain test case
+pub(super) fn execute_op_blockchain_test(
+    test_case: &OpBlockchainTestCase,
+    print_env_on_error: bool,
+) -> Result<(), TestExecutionError> {
+    let spec: OpSpecId = test_case
+        .network
+        .parse()
+        .map_err(|_| TestExecutionError::UnknownFork(test_case.network.clone()))?;
+
+    // Create database with initial state
+    let mut state = State::builder().with_bundle_update().build();
+
+    // Capture pre-state for debug info
+    let mut pre_state_debug = HashMap::default();
+
+    // Insert genesis state into database
+    for (address, account) in test_case.pre.clone().into_genesis_state() {
+        let account_info = AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: revm::primitives::keccak256(&account.code),
+            code: Some(Bytecode::new_raw(account.code.clone())),
+            account_id: None,
+        };
+
+        // Store for debug info
+        if print_env_on_error {
+            pre_state_debug.insert(address, (account_info.clone(), account.storage.clone()));
+        }
+
+        state.insert_account_with_storage(address, account_info, account.storage);
+    }
+
+    let genesis = &test_case.genesis_block_header;
+    let genesis_number = genesis.number.to::<u64>();
+    state.block_hashes.insert(genesis_number, genesis.hash);
+
+    let mut cfg = CfgEnv::new_with_spec(spec);
+    cfg.chain_id = test_case
+        .chain_id
+        .map(|chain_id| chain_id.to::<u64>())
+        .unwrap_or(OP_MAINNET_CHAIN_ID);
+    let mut evm = Context::op().with_cfg(cfg).with_db(state).build_op();
+
+    // Hardfork transitions are not supported, the parent is on the same spec.
+    let mut parent = OpParentBlock {
+        number: genesis_number,
+        hash: genesis.hash,
+        spec,
+    };
+
+    let debug_info = |block_idx: usize, tx_idx: usize, block_env| DebugInfo {
+        pre_state: pre_state_debug.clone(),
+        tx_env: None,
+        block_env,
+        cfg_env: CfgEnv::new_with_spec(spec.into_eth_spec()),
+        block_idx,
+        tx_idx,
+        withdrawals: None,
+    };
+
+    // Process each block in the test
+    for (block_idx, block) in test_case.blocks.iter().enumerate() {
+        println!("Run block {block_idx}/{}", test_case.blocks.len());
+
+        // Check if this block should fail
+        let should_fail = block.expect_exception.is_some();
+
+        let attributes = match payload_attributes(block) {
+            Ok(attributes) => attributes,
+            Err(_) if should_fail => continue,
+            Err((tx_idx, error)) => {
+                return Err(TestExecutionError::TransactionEnvCreation {
+                    block_idx,
+                    tx_idx,
+                    error,
+                });
+            }
+        };
+        let block_env = attributes.block_env(parent.number + 1, spec);
+
+        let result = OpBlockExecutor::new(&mut evm).execute_block(&parent, attributes);
+        let output = match result {
+            Ok(_) if should_fail => {
+                return Err(TestExecutionError::ExpectedFailure {
+                    block_idx,
+                    tx_idx: block.transactions.len(),
+                    message: block.expect_exception.clone().unwrap_or_default(),
+                });
+            }
+            Ok(output) => output,
+            // Changes of the failed block are not reverted, same as for Ethereum tests.
+            Err(_) if should_fail => continue,
+            Err(e) => {
+                let tx_idx = match &e {
+                    OpBlockError::DepositAfterUserTransaction { index }
+                    | OpBlockError::BlockGasLimitReached { index }
+                    | OpBlockError::DaFootprintLimitReached { index }
+                    | OpBlockError::Transaction { index, .. } => *index,
+                    _ => 0,
+                };
+                if print_env_on_error {
+                    print_error_with_state(
+                        &debug_info(block_idx, tx_idx, block_env),
+                        evm.ctx().db_ref(),
+                        test_case.post_state.as_ref(),
+                    );
+                }
+                return Err(TestExecutionError::UnexpectedFailure {
+                    block_idx,
+                    tx_idx,
+                    error: e.to_string(),
+                });
+            }
+        };
+
+        // insert present block hash.
+        let number = output.block.number.to::<u64>();
+        let hash = block.hash.unwrap_or_default();
+        evm.ctx().db_mut().block_hashes.insert(number, hash);
+        parent = OpParentBlock { number, hash, spec };
+    }
+
+    // Validate post state if present
+    if let Some(expected_post_state) = &test_case.post_state {
+        let block_env = evm.ctx().block().clone();
+        validate_post_state(
+            evm.ctx().db_mut(),
+            expected_post_state,
+            &debug_info(test_case.blocks.len(), 0, block_env),
+            print_env_on_error,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the block of the fixture into payload attributes.
+///
+/// Returns the index of the transaction that could not be converted on error.
+fn payload_attributes(block: &OpBlock) -> Result<OpPayloadAttributes, (usize, String)> {
+    let transactions = block
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(tx_idx, tx)| op_transaction(tx).map_err(|error| (tx_idx, error)))
+        .collect::<Result<_, _>>()?;
+
+    let attributes = &block.attributes;
+    Ok(OpPayloadAttributes {
+        timestamp: attributes.timestamp.to(),
+        prev_randao: attributes.prev_randao,
+        suggested_fee_recipient: attributes.suggested_fee_recipient,
+        parent_beacon_block_root: attributes.parent_beacon_block_root,
+        gas_limit: attributes.gas_limit.to(),
+        base_fee: attributes.base_fee_per_gas.to(),
+        transactions,
+    })
+}
+
+/// Converts the transaction of the fixture into an OP Stack transaction.
+fn op_transaction(tx: &OpBlockTransaction) -> Result<OpTransaction<TxEnv>, String> {
+    let mut op_tx = OpTransaction::new(tx.tx.to_tx_env()?);
+    if let Some(deposit) = &tx.deposit {
+        op_tx.base.tx_type = DEPOSIT_TRANSACTION_TYPE;
+        op_tx.deposit = DepositTransactionParts::new(
+            deposit.source_hash,
+            deposit.mint.map(|mint| mint.saturating_to()),
+            deposit.is_system_tx,
+        );
+    } else {
+        // Without the enveloped transaction the L1 fee is estimated.
+        op_tx.enveloped_tx = Some(
+            tx.enveloped_tx
+                .clone()
+                .unwrap_or_else(|| estimation_envelope(&op_tx.base)),
+        );
+    }
+    Ok(op_tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use statetest_types::op::OpBlockchainTest;
+
+    #[test]
+    fn isthmus_fees_fixture() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/op/blockchaintest/isthmus_fees.json"
+        );
+        let suite: OpBlockchainTest =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        for test_case in suite.0.values() {
+            execute_op_blockchain_test(test_case, false).unwrap();
+        }
+    }
+}
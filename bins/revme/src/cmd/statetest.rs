@@ -1,9 +1,11 @@
 pub mod merkle_trie;
+mod op;
 mod runner;
 pub mod utils;
 
 pub use runner::{TestError as Error, TestErrorKind};
 
+use super::Chain;
 use clap::Parser;
 use runner::{find_all_json_tests, run, TestError};
 use std::path::PathBuf;
@@ -36,6 +38,17 @@ pub struct Cmd {
     /// Keep going after a test failure
     #[arg(long, alias = "no-fail-fast")]
     keep_going: bool,
+    /// Chain of the tests
+    ///
+    /// OP Stack tests are keyed by the OP Stack hardfork names and can contain deposit fields
+    /// and L1 block info.
+    #[arg(long, value_enum, default_value_t = Chain::Eth)]
+    chain: Chain,
+    /// Execute bytecode one basic block at a time
+    ///
+    /// Static gas is charged once per basic block, results must be the same.
+    #[arg(long)]
+    basic_blocks: bool,
 }
 
 impl Cmd {
@@ -67,6 +80,8 @@ impl Cmd {
                 self.json,
                 self.json_outcome,
                 self.keep_going,
+                self.chain,
+                self.basic_blocks,
             )?
         }
         Ok(())
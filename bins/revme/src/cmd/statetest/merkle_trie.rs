@@ -1,11 +1,9 @@
-use std::convert::Infallible;
-
 use alloy_rlp::{RlpEncodable, RlpMaxEncodedLen};
 use hash_db::Hasher;
 use plain_hasher::PlainHasher;
 use revm::{
-    context::result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction},
-    database::{bal::EvmDatabaseError, EmptyDB, PlainAccount, State},
+    context::result::ExecutionResult,
+    database::{EmptyDB, PlainAccount, State},
     primitives::{keccak256, Address, Log, B256, U256},
 };
 use triehash::sec_trie_root;
@@ -15,11 +13,8 @@ pub struct TestValidationResult {
     pub state_root: B256,
 }
 
-pub fn compute_test_roots(
-    exec_result: &Result<
-        ExecutionResult<HaltReason>,
-        EVMError<EvmDatabaseError<Infallible>, InvalidTransaction>,
-    >,
+pub fn compute_test_roots<HaltReasonTr, E>(
+    exec_result: &Result<ExecutionResult<HaltReasonTr>, E>,
     db: &State<EmptyDB>,
 ) -> TestValidationResult {
     TestValidationResult {
@@ -0,0 +1,221 @@
+use super::runner::{check_evm_execution, TestError, TestErrorKind};
+use op_revm::{
+    estimate::estimation_envelope,
+    transaction::deposit::{DepositTransactionParts, DEPOSIT_TRANSACTION_TYPE},
+    DefaultOp, L1BlockInfo, OpBuilder, OpSpecId, OpTransaction,
+};
+use revm::{
+    context::{block::BlockEnv, cfg::CfgEnv, tx::TxEnv},
+    database,
+    inspector::{inspectors::TracerEip3155, InspectCommitEvm},
+    primitives::U256,
+    Context, ExecuteCommitEvm,
+};
+use statetest_types::{
+    op::{OpL1BlockInfo, OpTestSuite, OpTestUnit},
+    Test,
+};
+use std::{
+    io::stderr,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+struct OpTestExecutionContext<'a> {
+    name: &'a str,
+    unit: &'a OpTestUnit,
+    test: &'a Test,
+    cfg: &'a CfgEnv<OpSpecId>,
+    block: &'a BlockEnv,
+    tx: &'a OpTransaction<TxEnv>,
+    cache_state: &'a database::CacheState,
+    elapsed: &'a Arc<Mutex<Duration>>,
+    trace: bool,
+    print_json_outcome: bool,
+}
+
+/// Execute a single OP Stack test suite file containing multiple tests
+///
+/// Post states are keyed by the OP Stack hardfork names and transactions are executed with the
+/// OP Stack handler.
+///
+/// # Arguments
+/// * `path` - Path to the JSON test file
+/// * `elapsed` - Shared counter for total execution time
+/// * `trace` - Whether to enable EVM tracing
+/// * `print_json_outcome` - Whether to print JSON formatted results
+pub fn execute_op_test_suite(
+    path: &Path,
+    elapsed: &Arc<Mutex<Duration>>,
+    trace: bool,
+    print_json_outcome: bool,
+    basic_block_execution: bool,
+) -> Result<(), TestError> {
+    let s = std::fs::read_to_string(path).unwrap();
+    let path = path.to_string_lossy().into_owned();
+    let suite: OpTestSuite = serde_json::from_str(&s).map_err(|e| TestError {
+        name: "Unknown".to_string(),
+        path: path.clone(),
+        kind: e.into(),
+    })?;
+
+    for (name, op_unit) in &suite.0 {
+        let unit = &op_unit.unit;
+        let error = |kind| TestError {
+            name: name.clone(),
+            path: path.clone(),
+            kind,
+        };
+
+        // Prepare initial state
+        let cache_state = unit.state();
+
+        for (spec_name, tests) in &unit.post {
+            let spec: OpSpecId = spec_name
+                .parse()
+                .map_err(|_| error(TestErrorKind::UnknownHardfork(spec_name.clone())))?;
+
+            let mut cfg =
+                CfgEnv::new_with_spec(spec).with_basic_block_execution(basic_block_execution);
+            cfg.chain_id = unit
+                .env
+                .current_chain_id
+                .unwrap_or(U256::ONE)
+                .try_into()
+                .unwrap_or(1);
+
+            // Block environment follows the Ethereum spec of the OP Stack hardfork.
+            let block = unit.block_env(&mut CfgEnv::new_with_spec(spec.into_eth_spec()));
+
+            for test in tests {
+                // Setup transaction environment
+                let mut tx = match test.tx_env(unit) {
+                    Ok(tx) => OpTransaction::new(tx),
+                    Err(_) if test.expect_exception.is_some() => continue,
+                    Err(_) => {
+                        return Err(error(TestErrorKind::UnknownPrivateKey(
+                            unit.transaction.secret_key,
+                        )));
+                    }
+                };
+                if let Some(deposit) = &op_unit.deposit {
+                    tx.base.tx_type = DEPOSIT_TRANSACTION_TYPE;
+                    tx.deposit = DepositTransactionParts::new(
+                        deposit.source_hash,
+                        deposit.mint.map(|mint| mint.saturating_to()),
+                        deposit.is_system_tx,
+                    );
+                } else {
+                    // Without the signed transaction bytes the L1 fee is estimated.
+                    tx.enveloped_tx = Some(
+                        test.txbytes
+                            .clone()
+                            .unwrap_or_else(|| estimation_envelope(&tx.base)),
+                    );
+                }
+
+                execute_single_op_test(OpTestExecutionContext {
+                    name,
+                    unit: op_unit,
+                    test,
+                    cfg: &cfg,
+                    block: &block,
+                    tx: &tx,
+                    cache_state: &cache_state,
+                    elapsed,
+                    trace,
+                    print_json_outcome,
+                })
+                .map_err(error)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn execute_single_op_test(ctx: OpTestExecutionContext) -> Result<(), TestErrorKind> {
+    // Prepare state, all OP Stack hardforks clear empty accounts.
+    let mut cache = ctx.cache_state.clone();
+    cache.set_state_clear_flag(true);
+    let mut state = database::State::builder()
+        .with_cached_prestate(cache)
+        .with_bundle_update()
+        .build();
+
+    // L1 block info of the fixture is valid for the block, otherwise it is loaded from the
+    // L1 block contract.
+    let chain = ctx
+        .unit
+        .l1_block_info
+        .as_ref()
+        .map(|info| l1_block_info(info, ctx.block.number))
+        .unwrap_or_default();
+
+    let evm_context = Context::op()
+        .with_block(ctx.block.clone())
+        .with_tx(ctx.tx.clone())
+        .with_cfg(ctx.cfg.clone())
+        .with_chain(chain)
+        .with_db(&mut state);
+
+    // Execute
+    let timer = Instant::now();
+    let (db, exec_result) = if ctx.trace {
+        let mut evm = evm_context
+            .build_op_with_inspector(TracerEip3155::buffered(stderr()).without_summary());
+        let res = evm.inspect_tx_commit(ctx.tx.clone());
+        let db = evm.0.ctx.journaled_state.database;
+        (db, res)
+    } else {
+        let mut evm = evm_context.build_op();
+        let res = evm.transact_commit(ctx.tx.clone());
+        let db = evm.0.ctx.journaled_state.database;
+        (db, res)
+    };
+    *ctx.elapsed.lock().unwrap() += timer.elapsed();
+
+    // Check results
+    check_evm_execution(
+        ctx.test,
+        ctx.unit.unit.out.as_ref(),
+        ctx.name,
+        &exec_result,
+        db,
+        ctx.cfg.spec,
+        ctx.print_json_outcome,
+    )
+}
+
+/// Converts the L1 block info of the fixture into the L1 block info of the block.
+fn l1_block_info(info: &OpL1BlockInfo, l2_block: U256) -> L1BlockInfo {
+    L1BlockInfo {
+        l2_block: Some(l2_block),
+        l1_base_fee: info.l1_base_fee,
+        l1_fee_overhead: info.l1_fee_overhead,
+        l1_base_fee_scalar: info.l1_base_fee_scalar,
+        l1_blob_base_fee: info.l1_blob_base_fee,
+        l1_blob_base_fee_scalar: info.l1_blob_base_fee_scalar,
+        operator_fee_scalar: info.operator_fee_scalar,
+        operator_fee_constant: info.operator_fee_constant,
+        da_footprint_gas_scalar: info
+            .da_footprint_gas_scalar
+            .map(|scalar| scalar.saturating_to()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isthmus_fees_fixture() {
+        let path = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/op/statetest/isthmus_fees.json"
+        ));
+        let elapsed = Arc::new(Mutex::new(Duration::ZERO));
+        execute_op_test_suite(path, &elapsed, false, false, false).unwrap();
+    }
+}
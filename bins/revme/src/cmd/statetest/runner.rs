@@ -1,22 +1,23 @@
-use crate::cmd::statetest::merkle_trie::{compute_test_roots, TestValidationResult};
+use super::op::execute_op_test_suite;
+use crate::cmd::{
+    statetest::merkle_trie::{compute_test_roots, TestValidationResult},
+    Chain,
+};
 use indicatif::{ProgressBar, ProgressDrawTarget};
 use revm::{
     context::{block::BlockEnv, cfg::CfgEnv, tx::TxEnv},
-    context_interface::{
-        result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction},
-        Cfg,
-    },
-    database::{self, bal::EvmDatabaseError},
+    context_interface::{result::ExecutionResult, Cfg},
+    database,
     database_interface::EmptyDB,
     inspector::{inspectors::TracerEip3155, InspectCommitEvm},
     primitives::{hardfork::SpecId, Bytes, B256, U256},
     Context, ExecuteCommitEvm, MainBuilder, MainContext,
 };
+use serde::Serialize;
 use serde_json::json;
 use statetest_types::{SpecName, Test, TestSuite, TestUnit};
 use std::{
-    convert::Infallible,
-    fmt::Debug,
+    fmt::{Debug, Display},
     io::stderr,
     path::{Path, PathBuf},
     sync::{
@@ -44,6 +45,8 @@ pub enum TestErrorKind {
     LogsRootMismatch { got: B256, expected: B256 },
     #[error("state root mismatch: got {got}, expected {expected}")]
     StateRootMismatch { got: B256, expected: B256 },
+    #[error("unknown hardfork: {0}")]
+    UnknownHardfork(String),
     #[error("unknown private key: {0:?}")]
     UnknownPrivateKey(B256),
     #[error("unexpected exception: got {got_exception:?}, expected {expected_exception:?}")]
@@ -149,15 +152,12 @@ struct DebugContext<'a> {
     error: &'a TestErrorKind,
 }
 
-fn build_json_output(
+fn build_json_output<H: Debug, E: Display>(
     test: &Test,
     test_name: &str,
-    exec_result: &Result<
-        ExecutionResult<HaltReason>,
-        EVMError<EvmDatabaseError<Infallible>, InvalidTransaction>,
-    >,
+    exec_result: &Result<ExecutionResult<H>, E>,
     validation: &TestValidationResult,
-    spec: SpecId,
+    spec: impl Serialize,
     error: Option<String>,
 ) -> serde_json::Value {
     json!({
@@ -177,12 +177,7 @@ fn build_json_output(
     })
 }
 
-fn format_evm_result(
-    exec_result: &Result<
-        ExecutionResult<HaltReason>,
-        EVMError<EvmDatabaseError<Infallible>, InvalidTransaction>,
-    >,
-) -> String {
+fn format_evm_result<H: Debug, E: Display>(exec_result: &Result<ExecutionResult<H>, E>) -> String {
     match exec_result {
         Ok(r) => match r {
             ExecutionResult::Success { reason, .. } => format!("Success: {reason:?}"),
@@ -193,12 +188,9 @@ fn format_evm_result(
     }
 }
 
-fn validate_exception(
+fn validate_exception<H, E: Display>(
     test: &Test,
-    exec_result: &Result<
-        ExecutionResult<HaltReason>,
-        EVMError<EvmDatabaseError<Infallible>, InvalidTransaction>,
-    >,
+    exec_result: &Result<ExecutionResult<H>, E>,
 ) -> Result<bool, TestErrorKind> {
     match (&test.expect_exception, exec_result) {
         (None, Ok(_)) => Ok(false), // No exception expected, execution succeeded
@@ -210,9 +202,9 @@ fn validate_exception(
     }
 }
 
-fn validate_output(
+fn validate_output<H>(
     expected_output: Option<&Bytes>,
-    actual_result: &ExecutionResult<HaltReason>,
+    actual_result: &ExecutionResult<H>,
 ) -> Result<(), TestErrorKind> {
     if let Some((expected, actual)) = expected_output.zip(actual_result.output()) {
         if expected != actual {
@@ -225,16 +217,13 @@ fn validate_output(
     Ok(())
 }
 
-fn check_evm_execution(
+pub(super) fn check_evm_execution<H: Debug, E: Display>(
     test: &Test,
     expected_output: Option<&Bytes>,
     test_name: &str,
-    exec_result: &Result<
-        ExecutionResult<HaltReason>,
-        EVMError<EvmDatabaseError<Infallible>, InvalidTransaction>,
-    >,
+    exec_result: &Result<ExecutionResult<H>, E>,
     db: &mut database::State<EmptyDB>,
-    spec: SpecId,
+    spec: impl Serialize + Copy,
     print_json_outcome: bool,
 ) -> Result<(), TestErrorKind> {
     let validation = compute_test_roots(exec_result, db);
@@ -302,11 +291,13 @@ fn check_evm_execution(
 /// * `elapsed` - Shared counter for total execution time
 /// * `trace` - Whether to enable EVM tracing
 /// * `print_json_outcome` - Whether to print JSON formatted results
+/// * `basic_block_execution` - Whether to execute bytecode one basic block at a time
 pub fn execute_test_suite(
     path: &Path,
     elapsed: &Arc<Mutex<Duration>>,
     trace: bool,
     print_json_outcome: bool,
+    basic_block_execution: bool,
 ) -> Result<(), TestError> {
     if skip_test(path) {
         return Ok(());
@@ -325,7 +316,7 @@ pub fn execute_test_suite(
         let cache_state = unit.state();
 
         // Setup base configuration
-        let mut cfg = CfgEnv::default();
+        let mut cfg = CfgEnv::default().with_basic_block_execution(basic_block_execution);
         cfg.chain_id = unit
             .env
             .current_chain_id
@@ -505,10 +496,19 @@ struct TestRunnerConfig {
     trace: bool,
     print_outcome: bool,
     keep_going: bool,
+    chain: Chain,
+    basic_block_execution: bool,
 }
 
 impl TestRunnerConfig {
-    fn new(single_thread: bool, trace: bool, print_outcome: bool, keep_going: bool) -> Self {
+    fn new(
+        single_thread: bool,
+        trace: bool,
+        print_outcome: bool,
+        keep_going: bool,
+        chain: Chain,
+        basic_block_execution: bool,
+    ) -> Self {
         // Trace implies print_outcome
         let print_outcome = print_outcome || trace;
         // print_outcome or trace implies single_thread
@@ -519,6 +519,8 @@ impl TestRunnerConfig {
             trace,
             print_outcome,
             keep_going,
+            chain,
+            basic_block_execution,
         }
     }
 }
@@ -564,11 +566,16 @@ fn run_test_worker(state: TestRunnerState, config: TestRunnerConfig) -> Result<(
             return Ok(());
         };
 
-        let result = execute_test_suite(
+        let execute = match config.chain {
+            Chain::Eth => execute_test_suite,
+            Chain::Op => execute_op_test_suite,
+        };
+        let result = execute(
             &test_path,
             &state.elapsed,
             config.trace,
             config.print_outcome,
+            config.basic_block_execution,
         );
 
         state.console_bar.inc(1);
@@ -597,14 +604,25 @@ fn determine_thread_count(single_thread: bool, n_files: usize) -> usize {
 /// * `trace` - Enable EVM execution tracing
 /// * `print_outcome` - Print test outcomes in JSON format
 /// * `keep_going` - Continue running tests even if some fail
+/// * `chain` - Chain whose rules and fixture format are used
+/// * `basic_block_execution` - Execute bytecode one basic block at a time
 pub fn run(
     test_files: Vec<PathBuf>,
     single_thread: bool,
     trace: bool,
     print_outcome: bool,
     keep_going: bool,
+    chain: Chain,
+    basic_block_execution: bool,
 ) -> Result<(), TestError> {
-    let config = TestRunnerConfig::new(single_thread, trace, print_outcome, keep_going);
+    let config = TestRunnerConfig::new(
+        single_thread,
+        trace,
+        print_outcome,
+        keep_going,
+        chain,
+        basic_block_execution,
+    );
     let n_files = test_files.len();
     let state = TestRunnerState::new(test_files);
     let num_threads = determine_thread_count(config.single_thread, n_files);
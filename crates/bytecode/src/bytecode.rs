@@ -6,7 +6,7 @@
 
 use crate::{
     eip7702::{Eip7702Bytecode, EIP7702_MAGIC_BYTES},
-    BytecodeDecodeError, JumpTable, LegacyAnalyzedBytecode, LegacyRawBytecode,
+    BasicBlocks, BytecodeDecodeError, JumpTable, LegacyAnalyzedBytecode, LegacyRawBytecode,
 };
 use primitives::{
     alloy_primitives::Sealable, keccak256, Address, Bytes, OnceLock, B256, KECCAK_EMPTY,
@@ -56,6 +56,69 @@ impl Bytecode {
         }
     }
 
+    /// Returns basic blocks if bytecode is analyzed and they were computed.
+    #[inline]
+    pub fn legacy_basic_blocks(&self) -> Option<&Arc<BasicBlocks>> {
+        match &self {
+            Self::LegacyAnalyzed(analyzed) => analyzed.basic_blocks(),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytecode with [`BasicBlocks`] computed using the given static gas costs.
+    ///
+    /// EIP-7702 bytecode is returned unchanged.
+    ///
+    /// See [`LegacyAnalyzedBytecode::with_basic_blocks`] for more details.
+    #[inline]
+    pub fn with_basic_blocks(self, static_gas: &[u64; 256]) -> Self {
+        match self {
+            Self::LegacyAnalyzed(analyzed) => Self::LegacyAnalyzed(Arc::new(
+                Arc::unwrap_or_clone(analyzed).with_basic_blocks(static_gas),
+            )),
+            bytecode => bytecode,
+        }
+    }
+
+    /// Returns the bytecode with the given [`BasicBlocks`] computed for it.
+    ///
+    /// EIP-7702 bytecode is returned unchanged.
+    ///
+    /// See [`LegacyAnalyzedBytecode::with_computed_basic_blocks`] for more details.
+    #[inline]
+    pub fn with_computed_basic_blocks(self, basic_blocks: Arc<BasicBlocks>) -> Self {
+        match self {
+            Self::LegacyAnalyzed(analyzed) => Self::LegacyAnalyzed(Arc::new(
+                Arc::unwrap_or_clone(analyzed).with_computed_basic_blocks(basic_blocks),
+            )),
+            bytecode => bytecode,
+        }
+    }
+
+    /// Returns padded bytecode with fused opcodes if bytecode is analyzed and it was computed.
+    #[inline]
+    pub fn legacy_fused_bytecode(&self) -> Option<&Bytes> {
+        match &self {
+            Self::LegacyAnalyzed(analyzed) => analyzed.fused_bytecode(),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytecode with hot instruction sequences fused.
+    ///
+    /// EIP-7702 bytecode is returned unchanged.
+    ///
+    /// See [`LegacyAnalyzedBytecode::with_fusion`] for more details.
+    #[inline]
+    pub fn with_fusion(self) -> Self {
+        match self {
+            Self::LegacyAnalyzed(analyzed) => {
+                Self::LegacyAnalyzed(Arc::new(Arc::unwrap_or_clone(analyzed).with_fusion()))
+            }
+            bytecode => bytecode,
+        }
+    }
+
     /// Calculates hash of the bytecode.
     #[inline]
     pub fn hash_slow(&self) -> B256 {
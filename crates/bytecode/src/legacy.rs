@@ -1,9 +1,12 @@
 mod analysis;
 mod analyzed;
+mod basic_block;
+pub mod fusion;
 mod jump_map;
 mod raw;
 
 pub use analysis::analyze_legacy;
 pub use analyzed::LegacyAnalyzedBytecode;
+pub use basic_block::{ends_basic_block, BasicBlock, BasicBlocks};
 pub use jump_map::JumpTable;
 pub use raw::LegacyRawBytecode;
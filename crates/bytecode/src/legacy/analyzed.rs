@@ -1,5 +1,7 @@
-use super::JumpTable;
+use super::{BasicBlocks, JumpTable};
+use core::{cmp::Ordering, hash::Hash};
 use primitives::Bytes;
+use std::sync::Arc;
 
 /// Legacy analyzed bytecode represents the original bytecode format used in Ethereum.
 ///
@@ -25,7 +27,23 @@ use primitives::Bytes;
 /// analysis to generate its jump table. This analysis is O(n) on side of bytecode that is expensive,
 /// but the high gas cost required to store bytecode in the database is high enough to cover the
 /// expense of doing analysis and generate the jump table.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+///
+/// # Basic blocks
+///
+/// Bytecode can optionally contain [`BasicBlocks`] computed with
+/// [`with_basic_blocks`](Self::with_basic_blocks). They are used by the interpreter to charge the
+/// static gas once per block.
+///
+/// # Fusion
+///
+/// Bytecode can optionally contain a copy with hot instruction sequences rewritten into fused
+/// opcodes, computed with [`with_fusion`](Self::with_fusion). See
+/// [`fuse_legacy`](super::fusion::fuse_legacy) for more details. The original bytes are kept
+/// unchanged for `CODECOPY` and hashing.
+///
+/// Basic blocks and fused bytecode are not part of the equality, ordering or hash of the bytecode
+/// and are not serialized.
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LegacyAnalyzedBytecode {
     /// The potentially padded bytecode.
@@ -34,6 +52,46 @@ pub struct LegacyAnalyzedBytecode {
     original_len: usize,
     /// The jump table.
     jump_table: JumpTable,
+    /// Basic blocks of the bytecode.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    basic_blocks: Option<Arc<BasicBlocks>>,
+    /// Padded bytecode with fused opcodes.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fused_bytecode: Option<Bytes>,
+}
+
+impl PartialEq for LegacyAnalyzedBytecode {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytecode == other.bytecode
+            && self.original_len == other.original_len
+            && self.jump_table == other.jump_table
+    }
+}
+
+impl Eq for LegacyAnalyzedBytecode {}
+
+impl PartialOrd for LegacyAnalyzedBytecode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LegacyAnalyzedBytecode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.bytecode, self.original_len, &self.jump_table).cmp(&(
+            &other.bytecode,
+            other.original_len,
+            &other.jump_table,
+        ))
+    }
+}
+
+impl Hash for LegacyAnalyzedBytecode {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.bytecode.hash(state);
+        self.original_len.hash(state);
+        self.jump_table.hash(state);
+    }
 }
 
 impl Default for LegacyAnalyzedBytecode {
@@ -43,6 +101,8 @@ impl Default for LegacyAnalyzedBytecode {
             bytecode: Bytes::from_static(&[0]),
             original_len: 0,
             jump_table: JumpTable::default(),
+            basic_blocks: None,
+            fused_bytecode: None,
         }
     }
 }
@@ -80,9 +140,36 @@ impl LegacyAnalyzedBytecode {
             bytecode,
             original_len,
             jump_table,
+            basic_blocks: None,
+            fused_bytecode: None,
         }
     }
 
+    /// Splits the bytecode into [`BasicBlocks`] using the given static gas costs per opcode.
+    ///
+    /// Blocks are only valid for execution with the same static gas costs.
+    pub fn with_basic_blocks(mut self, static_gas: &[u64; 256]) -> Self {
+        self.basic_blocks = Some(Arc::new(BasicBlocks::analyze(
+            self.original_byte_slice(),
+            static_gas,
+        )));
+        self
+    }
+
+    /// Sets [`BasicBlocks`] computed for this bytecode, like blocks cached for the same code hash.
+    pub fn with_computed_basic_blocks(mut self, basic_blocks: Arc<BasicBlocks>) -> Self {
+        self.basic_blocks = Some(basic_blocks);
+        self
+    }
+
+    /// Computes the fused copy of the bytecode.
+    ///
+    /// See [`fuse_legacy`](super::fusion::fuse_legacy) for more details.
+    pub fn with_fusion(mut self) -> Self {
+        self.fused_bytecode = super::fusion::fuse_legacy(&self.bytecode);
+        self
+    }
+
     /// Returns a reference to the bytecode.
     ///
     /// The bytecode is padded with 32 zero bytes.
@@ -109,6 +196,16 @@ impl LegacyAnalyzedBytecode {
     pub fn jump_table(&self) -> &JumpTable {
         &self.jump_table
     }
+
+    /// Returns the padded bytecode with fused opcodes if it was computed and anything was fused.
+    pub fn fused_bytecode(&self) -> Option<&Bytes> {
+        self.fused_bytecode.as_ref()
+    }
+
+    /// Returns [BasicBlocks] of analyzed bytes if they were computed.
+    pub fn basic_blocks(&self) -> Option<&Arc<BasicBlocks>> {
+        self.basic_blocks.as_ref()
+    }
 }
 
 #[cfg(test)]
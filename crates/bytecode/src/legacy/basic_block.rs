@@ -0,0 +1,207 @@
+use crate::opcode::{self, OpCode};
+use std::vec::Vec;
+
+/// Straight-line sequence of instructions with a single entry and a single exit.
+///
+/// A block starts at the beginning of the bytecode, at a `JUMPDEST` or after an instruction that
+/// ends a block. See [`ends_basic_block`] for the instructions that end a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BasicBlock {
+    /// Program counter of the first instruction.
+    pub start: u32,
+    /// Number of instructions in the block.
+    pub instructions: u32,
+    /// Sum of the static gas of all instructions in the block.
+    pub static_gas: u64,
+    /// Minimum stack height on block entry needed so no instruction underflows.
+    pub min_stack_height: u16,
+    /// Maximum stack height reached inside the block, relative to the height on entry.
+    pub max_stack_growth: u16,
+}
+
+/// Basic blocks of a legacy bytecode, sorted by their start.
+///
+/// Static gas of the blocks depends on the instruction table used for the analysis so it is only
+/// valid for execution with the same static gas costs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BasicBlocks {
+    blocks: Vec<BasicBlock>,
+}
+
+impl BasicBlocks {
+    /// Splits the bytecode into basic blocks.
+    ///
+    /// `static_gas` contains the static gas cost of each opcode.
+    pub fn analyze(bytecode: &[u8], static_gas: &[u64; 256]) -> Self {
+        let mut blocks = Vec::new();
+        let mut block = BasicBlock::default();
+        // Stack height relative to the block entry.
+        let mut height: i32 = 0;
+
+        let mut pc = 0;
+        while pc < bytecode.len() {
+            let op = bytecode[pc];
+            if op == opcode::JUMPDEST && block.instructions != 0 {
+                blocks.push(block);
+                block = BasicBlock {
+                    start: pc as u32,
+                    ..Default::default()
+                };
+                height = 0;
+            }
+
+            let (inputs, outputs, immediate_size) = match OpCode::new(op) {
+                Some(op) => (
+                    op.inputs() as i32,
+                    op.outputs() as i32,
+                    op.info().immediate_size() as usize,
+                ),
+                None => (0, 0, 0),
+            };
+            block.instructions += 1;
+            block.static_gas += static_gas[op as usize];
+            block.min_stack_height = block.min_stack_height.max((inputs - height).max(0) as u16);
+            height += outputs - inputs;
+            block.max_stack_growth = block.max_stack_growth.max(height.max(0) as u16);
+
+            pc += 1 + immediate_size;
+            if ends_basic_block(op) {
+                blocks.push(block);
+                block = BasicBlock {
+                    start: pc as u32,
+                    ..Default::default()
+                };
+                height = 0;
+            }
+        }
+        if block.instructions != 0 {
+            blocks.push(block);
+        }
+        Self { blocks }
+    }
+
+    /// Returns the block that starts at `pc`.
+    #[inline]
+    pub fn block_at(&self, pc: usize) -> Option<&BasicBlock> {
+        self.blocks
+            .binary_search_by_key(&pc, |block| block.start as usize)
+            .ok()
+            .map(|index| &self.blocks[index])
+    }
+
+    /// Returns all blocks.
+    #[inline]
+    pub fn blocks(&self) -> &[BasicBlock] {
+        &self.blocks
+    }
+}
+
+/// Returns `true` if the instruction is the last one in its basic block.
+///
+/// These are instructions that change control flow or stop execution, and instructions with
+/// dynamic gas or that depend on the remaining gas. Ending the block on them guarantees that
+/// precharging the static gas of a block is not observable.
+#[inline]
+pub const fn ends_basic_block(op: u8) -> bool {
+    use opcode::*;
+    match OpCode::new(op) {
+        Some(code) if code.info().is_terminating() => true,
+        Some(_) => matches!(
+            op,
+            JUMP | JUMPI
+                | EXP
+                | KECCAK256
+                | BALANCE
+                | CALLDATACOPY
+                | CODECOPY
+                | EXTCODESIZE
+                | EXTCODECOPY
+                | RETURNDATACOPY
+                | EXTCODEHASH
+                | MLOAD
+                | MSTORE
+                | MSTORE8
+                | MCOPY
+                | SLOAD
+                | SSTORE
+                | GAS
+                | LOG0
+                | LOG1
+                | LOG2
+                | LOG3
+                | LOG4
+                | CREATE
+                | CREATE2
+                | CALL
+                | CALLCODE
+                | DELEGATECALL
+                | STATICCALL
+        ),
+        // Unknown opcodes halt execution.
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn static_gas() -> [u64; 256] {
+        let mut table = [3; 256];
+        table[opcode::STOP as usize] = 0;
+        table[opcode::JUMPDEST as usize] = 1;
+        table
+    }
+
+    #[test]
+    fn splits_on_jumps_and_jumpdests() {
+        let bytecode = [
+            opcode::PUSH1,
+            0x04,
+            opcode::JUMP,
+            opcode::STOP,
+            opcode::JUMPDEST,
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x02,
+            opcode::ADD,
+            opcode::STOP,
+        ];
+        let blocks = BasicBlocks::analyze(&bytecode, &static_gas());
+        let starts: Vec<_> = blocks.blocks().iter().map(|b| b.start).collect();
+        assert_eq!(starts, [0, 3, 4]);
+
+        let block = blocks.block_at(4).unwrap();
+        assert_eq!(block.instructions, 5);
+        assert_eq!(block.static_gas, 1 + 3 + 3 + 3);
+        assert_eq!(block.min_stack_height, 0);
+        assert_eq!(block.max_stack_growth, 2);
+        assert!(blocks.block_at(5).is_none());
+    }
+
+    #[test]
+    fn min_stack_height() {
+        let bytecode = [opcode::ADD, opcode::ADD, opcode::DUP1, opcode::STOP];
+        let blocks = BasicBlocks::analyze(&bytecode, &static_gas());
+        let block = blocks.block_at(0).unwrap();
+        assert_eq!(block.min_stack_height, 3);
+        assert_eq!(block.max_stack_growth, 0);
+    }
+
+    #[test]
+    fn ends_on_dynamic_gas() {
+        let bytecode = [
+            opcode::PUSH0,
+            opcode::MLOAD,
+            opcode::GAS,
+            opcode::POP,
+            opcode::STOP,
+        ];
+        let blocks = BasicBlocks::analyze(&bytecode, &static_gas());
+        let starts: Vec<_> = blocks.blocks().iter().map(|b| b.start).collect();
+        assert_eq!(starts, [0, 2, 3]);
+    }
+}
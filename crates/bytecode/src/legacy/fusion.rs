@@ -0,0 +1,130 @@
+use crate::opcode::{self, OpCode};
+use primitives::Bytes;
+
+/// Fused `PUSH1 x JUMP`.
+pub const FUSED_PUSH1_JUMP: u8 = 0xB0;
+/// Fused `PUSH2 x JUMP`.
+pub const FUSED_PUSH2_JUMP: u8 = 0xB1;
+/// Fused `PUSH1 x JUMPI`.
+pub const FUSED_PUSH1_JUMPI: u8 = 0xB2;
+/// Fused `PUSH2 x JUMPI`.
+pub const FUSED_PUSH2_JUMPI: u8 = 0xB3;
+/// Fused `SWAP1 POP`.
+pub const FUSED_SWAP1_POP: u8 = 0xB4;
+/// Fused function dispatch `DUP1 PUSH4 selector EQ PUSH2 target JUMPI`.
+pub const FUSED_DISPATCH: u8 = 0xB5;
+
+/// Returns `true` if the opcode is an internal fused opcode.
+#[inline]
+pub const fn is_fused_opcode(op: u8) -> bool {
+    matches!(op, FUSED_PUSH1_JUMP..=FUSED_DISPATCH)
+}
+
+/// Rewrites hot instruction sequences of the padded bytecode into fused opcodes.
+///
+/// Only the first opcode of a sequence is replaced, the immediates and the following opcodes
+/// stay in place, so program counters and the jump table remain valid. The fused opcode handler
+/// executes the whole sequence.
+///
+/// Returns `None` if nothing was fused or if the bytecode contains an instruction that collides
+/// with a fused opcode, as executing it with fused handlers would change its meaning.
+pub fn fuse_legacy(bytecode: &[u8]) -> Option<Bytes> {
+    let at = |i: usize| bytecode.get(i).copied();
+    let mut fused = bytecode.to_vec();
+    let mut is_fused = false;
+
+    let mut i = 0;
+    while i < bytecode.len() {
+        let op = bytecode[i];
+        if is_fused_opcode(op) {
+            return None;
+        }
+
+        let sequence = match op {
+            opcode::PUSH1 if at(i + 2) == Some(opcode::JUMP) => Some((FUSED_PUSH1_JUMP, 3)),
+            opcode::PUSH1 if at(i + 2) == Some(opcode::JUMPI) => Some((FUSED_PUSH1_JUMPI, 3)),
+            opcode::PUSH2 if at(i + 3) == Some(opcode::JUMP) => Some((FUSED_PUSH2_JUMP, 4)),
+            opcode::PUSH2 if at(i + 3) == Some(opcode::JUMPI) => Some((FUSED_PUSH2_JUMPI, 4)),
+            opcode::SWAP1 if at(i + 1) == Some(opcode::POP) => Some((FUSED_SWAP1_POP, 2)),
+            opcode::DUP1
+                if at(i + 1) == Some(opcode::PUSH4)
+                    && at(i + 6) == Some(opcode::EQ)
+                    && at(i + 7) == Some(opcode::PUSH2)
+                    && at(i + 10) == Some(opcode::JUMPI) =>
+            {
+                Some((FUSED_DISPATCH, 11))
+            }
+            _ => None,
+        };
+
+        match sequence {
+            Some((fused_op, len)) => {
+                fused[i] = fused_op;
+                is_fused = true;
+                i += len;
+            }
+            None => {
+                let immediate_size = OpCode::new(op)
+                    .map(|op| op.info().immediate_size())
+                    .unwrap_or_default();
+                i += 1 + immediate_size as usize;
+            }
+        }
+    }
+
+    is_fused.then(|| fused.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuses_sequences() {
+        let bytecode = [
+            opcode::DUP1,
+            opcode::PUSH4,
+            0x01,
+            0x02,
+            0x03,
+            0x04,
+            opcode::EQ,
+            opcode::PUSH2,
+            0x00,
+            0x10,
+            opcode::JUMPI,
+            opcode::PUSH1,
+            0x10,
+            opcode::JUMP,
+            opcode::SWAP1,
+            opcode::POP,
+            opcode::JUMPDEST,
+            opcode::STOP,
+        ];
+        let fused = fuse_legacy(&bytecode).unwrap();
+        assert_eq!(fused.len(), bytecode.len());
+        assert_eq!(fused[0], FUSED_DISPATCH);
+        assert_eq!(fused[11], FUSED_PUSH1_JUMP);
+        assert_eq!(fused[14], FUSED_SWAP1_POP);
+        // Everything except the first opcode of a sequence is unchanged.
+        assert_eq!(fused[1..11], bytecode[1..11]);
+        assert_eq!(fused[15..], bytecode[15..]);
+    }
+
+    #[test]
+    fn skips_push_data() {
+        // `0x90 0x50` (SWAP1 POP) is push data.
+        let bytecode = [opcode::PUSH2, opcode::SWAP1, opcode::POP, opcode::STOP];
+        assert_eq!(fuse_legacy(&bytecode), None);
+    }
+
+    #[test]
+    fn rejects_colliding_opcodes() {
+        let bytecode = [opcode::SWAP1, opcode::POP, FUSED_SWAP1_POP, opcode::STOP];
+        assert_eq!(fuse_legacy(&bytecode), None);
+
+        // Collision inside push data is fine.
+        let bytecode = [opcode::SWAP1, opcode::POP, opcode::PUSH1, FUSED_SWAP1_POP];
+        assert!(fuse_legacy(&bytecode).is_some());
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! [`Block`] trait is used to retrieve block information required for execution.
 pub mod blob;
+pub mod header;
 
-pub use blob::{calc_blob_gasprice, BlobExcessGasAndPrice};
+pub use blob::{calc_blob_gasprice, calc_excess_blob_gas, BlobExcessGasAndPrice, BlobParams};
+pub use header::{calc_next_block_base_fee, BlockHeader};
 
 use auto_impl::auto_impl;
 use primitives::{Address, B256, U256};
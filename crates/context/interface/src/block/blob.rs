@@ -7,9 +7,14 @@
 //! [`BlobExcessGasAndPrice`] is used to store the blob gas price and excess blob gas.s
 use primitives::{
     eip4844::{
-        BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN, BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE,
-        MIN_BLOB_GASPRICE,
+        BLOB_BASE_FEE_UPDATE_FRACTION_BPO1, BLOB_BASE_FEE_UPDATE_FRACTION_BPO2,
+        BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN, BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, GAS_PER_BLOB,
+        MAX_BLOB_GAS_PER_BLOCK_CANCUN, MAX_BLOB_GAS_PER_BLOCK_PRAGUE,
+        MAX_BLOB_NUMBER_PER_BLOCK_BPO1, MAX_BLOB_NUMBER_PER_BLOCK_BPO2, MIN_BLOB_GASPRICE,
+        TARGET_BLOB_GAS_PER_BLOCK_CANCUN, TARGET_BLOB_GAS_PER_BLOCK_PRAGUE,
+        TARGET_BLOB_NUMBER_PER_BLOCK_BPO1, TARGET_BLOB_NUMBER_PER_BLOCK_BPO2,
     },
+    eip7918::BLOB_BASE_COST,
     hardfork::SpecId,
 };
 
@@ -32,7 +37,7 @@ pub struct BlobExcessGasAndPrice {
 impl BlobExcessGasAndPrice {
     /// Creates a new instance by calculating the blob gas price with [`calc_blob_gasprice`].
     ///
-    /// `excess_blob_gas` is the excess blob gas of the block, it can be calculated with `calc_excess_blob_gas` function from alloy-eips.
+    /// `excess_blob_gas` is the excess blob gas of the block, it can be calculated with [`calc_excess_blob_gas`].
     pub fn new(excess_blob_gas: u64, blob_base_fee_update_fraction: u64) -> Self {
         let blob_gasprice = calc_blob_gasprice(excess_blob_gas, blob_base_fee_update_fraction);
         Self {
@@ -54,6 +59,129 @@ impl BlobExcessGasAndPrice {
     }
 }
 
+/// Blob gas parameters of a fork.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobParams {
+    /// Target blob gas per block.
+    pub target_blob_gas_per_block: u64,
+    /// Maximum blob gas per block.
+    pub max_blob_gas_per_block: u64,
+    /// Blob base fee update fraction.
+    pub blob_base_fee_update_fraction: u64,
+}
+
+impl BlobParams {
+    /// Blob parameters introduced in Cancun.
+    pub const fn cancun() -> Self {
+        Self {
+            target_blob_gas_per_block: TARGET_BLOB_GAS_PER_BLOCK_CANCUN,
+            max_blob_gas_per_block: MAX_BLOB_GAS_PER_BLOCK_CANCUN,
+            blob_base_fee_update_fraction: BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN,
+        }
+    }
+
+    /// Blob parameters introduced in Prague.
+    pub const fn prague() -> Self {
+        Self {
+            target_blob_gas_per_block: TARGET_BLOB_GAS_PER_BLOCK_PRAGUE,
+            max_blob_gas_per_block: MAX_BLOB_GAS_PER_BLOCK_PRAGUE,
+            blob_base_fee_update_fraction: BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE,
+        }
+    }
+
+    /// Blob parameters of Osaka, unchanged from Prague.
+    pub const fn osaka() -> Self {
+        Self::prague()
+    }
+
+    /// Blob parameters of the first blob parameter only fork (BPO1) after Osaka.
+    pub const fn bpo1() -> Self {
+        Self {
+            target_blob_gas_per_block: TARGET_BLOB_NUMBER_PER_BLOCK_BPO1 * GAS_PER_BLOB,
+            max_blob_gas_per_block: MAX_BLOB_NUMBER_PER_BLOCK_BPO1 * GAS_PER_BLOB,
+            blob_base_fee_update_fraction: BLOB_BASE_FEE_UPDATE_FRACTION_BPO1,
+        }
+    }
+
+    /// Blob parameters of the second blob parameter only fork (BPO2) after Osaka.
+    pub const fn bpo2() -> Self {
+        Self {
+            target_blob_gas_per_block: TARGET_BLOB_NUMBER_PER_BLOCK_BPO2 * GAS_PER_BLOB,
+            max_blob_gas_per_block: MAX_BLOB_NUMBER_PER_BLOCK_BPO2 * GAS_PER_BLOB,
+            blob_base_fee_update_fraction: BLOB_BASE_FEE_UPDATE_FRACTION_BPO2,
+        }
+    }
+
+    /// Returns the blob parameters of the spec.
+    ///
+    /// Specs before Cancun return the Cancun parameters. Blob parameter only forks are not
+    /// specs, their parameters have to be passed explicitly, see [`BlobParams::bpo1`] and
+    /// [`BlobParams::bpo2`].
+    pub const fn from_spec(spec: SpecId) -> Self {
+        if spec.is_enabled_in(SpecId::OSAKA) {
+            Self::osaka()
+        } else if spec.is_enabled_in(SpecId::PRAGUE) {
+            Self::prague()
+        } else {
+            Self::cancun()
+        }
+    }
+}
+
+/// Calculates the excess blob gas of the block from the parent block header fields.
+///
+/// See also [the EIP-4844 helpers](https://eips.ethereum.org/EIPS/eip-4844#helpers)
+/// (`calc_excess_blob_gas`).
+#[inline]
+pub fn calc_excess_blob_gas(
+    parent_excess_blob_gas: u64,
+    parent_blob_gas_used: u64,
+    target_blob_gas_per_block: u64,
+) -> u64 {
+    parent_excess_blob_gas
+        .saturating_add(parent_blob_gas_used)
+        .saturating_sub(target_blob_gas_per_block)
+}
+
+/// Calculates the excess blob gas of the block with the reserve price introduced in Osaka.
+///
+/// If the execution cost of the parent block dominates the blob fee, excess blob gas only
+/// increases and the blob base fee converges upwards.
+///
+/// Target and maximum blob gas are the ones of the block being calculated while
+/// `parent_blob_base_fee_update_fraction` is the one active at the parent, used to price the
+/// blob fee of the parent.
+///
+/// See also [EIP-7918](https://eips.ethereum.org/EIPS/eip-7918).
+#[inline]
+pub fn calc_excess_blob_gas_osaka(
+    parent_excess_blob_gas: u64,
+    parent_blob_gas_used: u64,
+    parent_base_fee_per_gas: u64,
+    target_blob_gas_per_block: u64,
+    max_blob_gas_per_block: u64,
+    parent_blob_base_fee_update_fraction: u64,
+) -> u64 {
+    let total_blob_gas = parent_excess_blob_gas.saturating_add(parent_blob_gas_used);
+    if total_blob_gas < target_blob_gas_per_block {
+        return 0;
+    }
+
+    let execution_cost = BLOB_BASE_COST as u128 * parent_base_fee_per_gas as u128;
+    let blob_cost = GAS_PER_BLOB as u128
+        * calc_blob_gasprice(parent_excess_blob_gas, parent_blob_base_fee_update_fraction);
+    if execution_cost > blob_cost {
+        let increase = (parent_blob_gas_used as u128
+            * max_blob_gas_per_block.saturating_sub(target_blob_gas_per_block) as u128)
+            .checked_div(max_blob_gas_per_block as u128)
+            .unwrap_or_default();
+        parent_excess_blob_gas.saturating_add(increase.min(u64::MAX as u128) as u64)
+    } else {
+        total_blob_gas - target_blob_gas_per_block
+    }
+}
+
 /// Calculates the blob gas price from the header's excess blob gas field.
 ///
 /// See also [the EIP-4844 helpers](https://eips.ethereum.org/EIPS/eip-4844#helpers)
@@ -102,7 +230,78 @@ pub fn fake_exponential(factor: u64, numerator: u64, denominator: u64) -> u128 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN;
+    use primitives::eip4844::{
+        BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN, BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE,
+        MAX_BLOB_GAS_PER_BLOCK_PRAGUE, TARGET_BLOB_GAS_PER_BLOCK_CANCUN,
+        TARGET_BLOB_GAS_PER_BLOCK_PRAGUE,
+    };
+
+    // https://github.com/ethereum/go-ethereum/blob/28857080d732857030eda80c69b9ba2c8926f221/consensus/misc/eip4844/eip4844_test.go#L27
+    #[test]
+    fn excess_blob_gas() {
+        let target = TARGET_BLOB_GAS_PER_BLOCK_CANCUN;
+        for t @ &(excess, used, expected) in &[
+            // Blob gas usage below target does not accumulate.
+            (0, 0, 0),
+            (0, GAS_PER_BLOB, 0),
+            (0, target, 0),
+            // Blob gas usage above target accumulates.
+            (0, target + GAS_PER_BLOB, GAS_PER_BLOB),
+            (GAS_PER_BLOB, target + GAS_PER_BLOB, 2 * GAS_PER_BLOB),
+            // Excess below target is drained.
+            (target - 1, 0, 0),
+        ] {
+            assert_eq!(
+                calc_excess_blob_gas(excess, used, target),
+                expected,
+                "test: {t:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn excess_blob_gas_osaka_reserve_price() {
+        let target = TARGET_BLOB_GAS_PER_BLOCK_PRAGUE;
+        let max = MAX_BLOB_GAS_PER_BLOCK_PRAGUE;
+        let fraction = BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE;
+
+        // Cheap execution, regular update rule applies.
+        assert_eq!(
+            calc_excess_blob_gas_osaka(target, target, 1, target, max, fraction),
+            calc_excess_blob_gas(target, target, target)
+        );
+        // Expensive execution, excess blob gas only grows.
+        assert_eq!(
+            calc_excess_blob_gas_osaka(target, target, 1_000_000_000, target, max, fraction),
+            target + target * (max - target) / max
+        );
+        // Below target resets to zero.
+        assert_eq!(
+            calc_excess_blob_gas_osaka(0, target - 1, 1_000_000_000, target, max, fraction),
+            0
+        );
+    }
+
+    #[test]
+    fn excess_blob_gas_saturates() {
+        let target = TARGET_BLOB_GAS_PER_BLOCK_PRAGUE;
+        let max = MAX_BLOB_GAS_PER_BLOCK_PRAGUE;
+        let fraction = BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE;
+
+        assert_eq!(
+            calc_excess_blob_gas(u64::MAX, u64::MAX, target),
+            u64::MAX - target
+        );
+        assert_eq!(
+            calc_excess_blob_gas_osaka(target, u64::MAX, 1, target, max, fraction),
+            u64::MAX - target
+        );
+        // Expensive execution, the increase is computed without overflowing.
+        assert_eq!(
+            calc_excess_blob_gas_osaka(target, u64::MAX, u64::MAX, target, max, fraction),
+            target + u64::MAX / 3
+        );
+    }
 
     // https://github.com/ethereum/go-ethereum/blob/28857080d732857030eda80c69b9ba2c8926f221/consensus/misc/eip4844/eip4844_test.go#L78
     #[test]
@@ -129,4 +328,24 @@ mod tests {
             assert_eq!(actual, expected, "test: {t:?}");
         }
     }
+
+    #[test]
+    fn blob_params_from_spec() {
+        assert_eq!(
+            BlobParams::from_spec(SpecId::SHANGHAI),
+            BlobParams::cancun()
+        );
+        assert_eq!(BlobParams::from_spec(SpecId::CANCUN), BlobParams::cancun());
+        assert_eq!(BlobParams::from_spec(SpecId::PRAGUE), BlobParams::prague());
+        assert_eq!(BlobParams::from_spec(SpecId::OSAKA), BlobParams::osaka());
+        assert_eq!(
+            BlobParams::from_spec(SpecId::AMSTERDAM),
+            BlobParams::osaka()
+        );
+        assert_eq!(BlobParams::bpo1().max_blob_gas_per_block, 15 * GAS_PER_BLOB);
+        assert_eq!(
+            BlobParams::bpo2().target_blob_gas_per_block,
+            14 * GAS_PER_BLOB
+        );
+    }
 }
@@ -0,0 +1,422 @@
+//! Block header fields needed to validate a block against its parent.
+//!
+//! [`BlockHeader`] contains the consensus fields of a header that are relevant for execution. It
+//! is used to check a block against its parent and to calculate the base fee and excess blob gas
+//! of the next block.
+use super::blob::{calc_excess_blob_gas, calc_excess_blob_gas_osaka, BlobParams};
+use crate::result::InvalidHeader;
+use primitives::{
+    eip1559::{
+        BASE_FEE_MAX_CHANGE_DENOMINATOR, ELASTICITY_MULTIPLIER, GAS_LIMIT_BOUND_DIVISOR,
+        INITIAL_BASE_FEE, MIN_GAS_LIMIT,
+    },
+    hardfork::SpecId,
+    B256,
+};
+
+/// Consensus header fields that are relevant for execution.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockHeader {
+    /// Block number.
+    pub number: u64,
+    /// Block timestamp in seconds since the UNIX epoch.
+    pub timestamp: u64,
+    /// Gas limit of the block.
+    pub gas_limit: u64,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Base fee per gas, added in London.
+    pub base_fee_per_gas: Option<u64>,
+    /// Blob gas used by the block, added in Cancun.
+    pub blob_gas_used: Option<u64>,
+    /// Excess blob gas of the block, added in Cancun.
+    pub excess_blob_gas: Option<u64>,
+    /// Root of the withdrawals trie, added in Shanghai.
+    pub withdrawals_root: Option<B256>,
+    /// Hash of the execution layer requests, added in Prague.
+    pub requests_hash: Option<B256>,
+}
+
+impl BlockHeader {
+    /// Returns the base fee of the next block.
+    ///
+    /// Returns `None` if London is not enabled in `spec`. If this block is before London the next
+    /// block is the fork block and [`INITIAL_BASE_FEE`] is returned.
+    pub fn next_block_base_fee(&self, spec: SpecId) -> Option<u64> {
+        if !spec.is_enabled_in(SpecId::LONDON) {
+            return None;
+        }
+        Some(match self.base_fee_per_gas {
+            Some(base_fee) => calc_next_block_base_fee(self.gas_used, self.gas_limit, base_fee),
+            None => INITIAL_BASE_FEE,
+        })
+    }
+
+    /// Returns the number of the next block.
+    pub fn next_block_number(&self) -> Result<u64, InvalidHeader> {
+        self.number
+            .checked_add(1)
+            .ok_or(InvalidHeader::NumberOverflow)
+    }
+
+    /// Returns the gas limit that bounds the gas limit of the next block.
+    ///
+    /// Gas target of the London fork block is doubled so the bound is the gas limit of this block
+    /// times the elasticity multiplier.
+    pub fn next_block_parent_gas_limit(&self, spec: SpecId) -> Result<u64, InvalidHeader> {
+        if spec.is_enabled_in(SpecId::LONDON) && self.base_fee_per_gas.is_none() {
+            self.gas_limit.checked_mul(ELASTICITY_MULTIPLIER).ok_or(
+                InvalidHeader::GasLimitOverflow {
+                    parent: self.gas_limit,
+                },
+            )
+        } else {
+            Ok(self.gas_limit)
+        }
+    }
+
+    /// Returns the excess blob gas of the next block.
+    ///
+    /// `spec` and `blob_params` are the ones active at the next block, `parent_blob_params` the
+    /// ones active at this block. From Osaka the blob base fee of this block is compared against
+    /// the reserve price, it is calculated with the update fraction of this block so the result
+    /// is correct across a blob parameter only fork.
+    ///
+    /// Returns `None` if Cancun is not enabled in `spec`. If this block is before Cancun the next
+    /// block is the fork block and zero is returned.
+    pub fn next_block_excess_blob_gas(
+        &self,
+        spec: SpecId,
+        blob_params: BlobParams,
+        parent_blob_params: BlobParams,
+    ) -> Option<u64> {
+        if !spec.is_enabled_in(SpecId::CANCUN) {
+            return None;
+        }
+        let (Some(excess_blob_gas), Some(blob_gas_used)) =
+            (self.excess_blob_gas, self.blob_gas_used)
+        else {
+            return Some(0);
+        };
+        Some(if spec.is_enabled_in(SpecId::OSAKA) {
+            calc_excess_blob_gas_osaka(
+                excess_blob_gas,
+                blob_gas_used,
+                self.base_fee_per_gas.unwrap_or_default(),
+                blob_params.target_blob_gas_per_block,
+                blob_params.max_blob_gas_per_block,
+                parent_blob_params.blob_base_fee_update_fraction,
+            )
+        } else {
+            calc_excess_blob_gas(
+                excess_blob_gas,
+                blob_gas_used,
+                blob_params.target_blob_gas_per_block,
+            )
+        })
+    }
+
+    /// Validates the header against its parent.
+    ///
+    /// `spec` and `blob_params` are the ones active at this block, `parent_blob_params` the ones
+    /// active at the parent. Checks the block number, timestamp, gas limit and gas used, base
+    /// fee, excess blob gas and the presence of the withdrawals root and requests hash.
+    pub fn validate_against_parent(
+        &self,
+        parent: &BlockHeader,
+        spec: SpecId,
+        blob_params: BlobParams,
+        parent_blob_params: BlobParams,
+    ) -> Result<(), InvalidHeader> {
+        if self.number != parent.next_block_number()? {
+            return Err(InvalidHeader::NumberNotSequential {
+                parent: parent.number,
+                number: self.number,
+            });
+        }
+        if self.timestamp <= parent.timestamp {
+            return Err(InvalidHeader::TimestampNotIncreasing {
+                parent: parent.timestamp,
+                timestamp: self.timestamp,
+            });
+        }
+        if self.gas_used > self.gas_limit {
+            return Err(InvalidHeader::GasUsedExceedsGasLimit {
+                gas_used: self.gas_used,
+                gas_limit: self.gas_limit,
+            });
+        }
+
+        let parent_gas_limit = parent.next_block_parent_gas_limit(spec)?;
+        if !is_gas_limit_valid(parent_gas_limit, self.gas_limit) {
+            return Err(InvalidHeader::InvalidGasLimit {
+                parent: parent_gas_limit,
+                gas_limit: self.gas_limit,
+            });
+        }
+
+        if let Some(expected) = parent.next_block_base_fee(spec) {
+            let got = self.base_fee_per_gas.ok_or(InvalidHeader::BaseFeeNotSet)?;
+            if got != expected {
+                return Err(InvalidHeader::BaseFeeMismatch { expected, got });
+            }
+        }
+
+        if let Some(expected) =
+            parent.next_block_excess_blob_gas(spec, blob_params, parent_blob_params)
+        {
+            if self.blob_gas_used.is_none() {
+                return Err(InvalidHeader::BlobGasUsedNotSet);
+            }
+            let got = self
+                .excess_blob_gas
+                .ok_or(InvalidHeader::ExcessBlobGasNotSet)?;
+            if got != expected {
+                return Err(InvalidHeader::ExcessBlobGasMismatch { expected, got });
+            }
+        }
+
+        if spec.is_enabled_in(SpecId::SHANGHAI) && self.withdrawals_root.is_none() {
+            return Err(InvalidHeader::WithdrawalsRootNotSet);
+        }
+        if spec.is_enabled_in(SpecId::PRAGUE) && self.requests_hash.is_none() {
+            return Err(InvalidHeader::RequestsHashNotSet);
+        }
+        Ok(())
+    }
+
+    /// Validates the withdrawals root against the root calculated from the block body.
+    pub fn validate_withdrawals_root(&self, expected: B256) -> Result<(), InvalidHeader> {
+        let got = self
+            .withdrawals_root
+            .ok_or(InvalidHeader::WithdrawalsRootNotSet)?;
+        if got != expected {
+            return Err(InvalidHeader::WithdrawalsRootMismatch { expected, got });
+        }
+        Ok(())
+    }
+
+    /// Validates the requests hash against the hash of the requests produced by the block.
+    pub fn validate_requests_hash(&self, expected: B256) -> Result<(), InvalidHeader> {
+        let got = self
+            .requests_hash
+            .ok_or(InvalidHeader::RequestsHashNotSet)?;
+        if got != expected {
+            return Err(InvalidHeader::RequestsHashMismatch { expected, got });
+        }
+        Ok(())
+    }
+}
+
+/// Calculates the base fee of the next block.
+///
+/// See also [EIP-1559](https://eips.ethereum.org/EIPS/eip-1559).
+pub fn calc_next_block_base_fee(gas_used: u64, gas_limit: u64, base_fee: u64) -> u64 {
+    let gas_target = gas_limit / ELASTICITY_MULTIPLIER;
+    if gas_target == 0 || gas_used == gas_target {
+        return base_fee;
+    }
+
+    let base_fee = base_fee as u128;
+    let gas_target = gas_target as u128;
+    let denominator = BASE_FEE_MAX_CHANGE_DENOMINATOR as u128;
+    if gas_used as u128 > gas_target {
+        let gas_used_delta = gas_used as u128 - gas_target;
+        let base_fee_delta = (base_fee * gas_used_delta / gas_target / denominator).max(1);
+        (base_fee + base_fee_delta).min(u64::MAX as u128) as u64
+    } else {
+        let gas_used_delta = gas_target - gas_used as u128;
+        let base_fee_delta = base_fee * gas_used_delta / gas_target / denominator;
+        (base_fee - base_fee_delta) as u64
+    }
+}
+
+/// Returns `true` if the gas limit is within the bounds allowed by the parent gas limit.
+///
+/// The gas limit can change by less than `parent_gas_limit / 1024` and can't be below
+/// [`MIN_GAS_LIMIT`].
+pub fn is_gas_limit_valid(parent_gas_limit: u64, gas_limit: u64) -> bool {
+    gas_limit >= MIN_GAS_LIMIT
+        && gas_limit.abs_diff(parent_gas_limit) < parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent() -> BlockHeader {
+        BlockHeader {
+            number: 10,
+            timestamp: 120,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            withdrawals_root: Some(B256::ZERO),
+            requests_hash: Some(B256::ZERO),
+        }
+    }
+
+    fn child() -> BlockHeader {
+        BlockHeader {
+            number: 11,
+            timestamp: 132,
+            gas_used: 0,
+            ..parent()
+        }
+    }
+
+    #[test]
+    fn next_block_base_fee() {
+        // At target base fee stays the same.
+        assert_eq!(
+            calc_next_block_base_fee(15_000_000, 30_000_000, 1_000),
+            1_000
+        );
+        // Full block increases base fee by 12.5%.
+        assert_eq!(
+            calc_next_block_base_fee(30_000_000, 30_000_000, 1_000),
+            1_125
+        );
+        // Empty block decreases base fee by 12.5%.
+        assert_eq!(calc_next_block_base_fee(0, 30_000_000, 1_000), 875);
+        // Increase is at least one.
+        assert_eq!(calc_next_block_base_fee(15_000_001, 30_000_000, 7), 8);
+
+        let pre_london = BlockHeader {
+            base_fee_per_gas: None,
+            ..parent()
+        };
+        assert_eq!(pre_london.next_block_base_fee(SpecId::BERLIN), None);
+        assert_eq!(
+            pre_london.next_block_base_fee(SpecId::LONDON),
+            Some(INITIAL_BASE_FEE)
+        );
+    }
+
+    #[test]
+    fn gas_limit_bounds() {
+        let parent = 30_000_000;
+        let max_change = parent / GAS_LIMIT_BOUND_DIVISOR;
+        assert!(is_gas_limit_valid(parent, parent + max_change - 1));
+        assert!(!is_gas_limit_valid(parent, parent + max_change));
+        assert!(is_gas_limit_valid(parent, parent - max_change + 1));
+        assert!(!is_gas_limit_valid(parent, parent - max_change));
+        assert!(!is_gas_limit_valid(MIN_GAS_LIMIT, MIN_GAS_LIMIT - 1));
+    }
+
+    #[test]
+    fn validate_against_parent() {
+        let spec = SpecId::PRAGUE;
+        let blob_params = BlobParams::from_spec(spec);
+        assert_eq!(
+            child().validate_against_parent(&parent(), spec, blob_params, blob_params),
+            Ok(())
+        );
+
+        let header = BlockHeader {
+            timestamp: 120,
+            ..child()
+        };
+        assert_eq!(
+            header.validate_against_parent(&parent(), spec, blob_params, blob_params),
+            Err(InvalidHeader::TimestampNotIncreasing {
+                parent: 120,
+                timestamp: 120
+            })
+        );
+
+        let header = BlockHeader {
+            base_fee_per_gas: Some(1),
+            ..child()
+        };
+        assert_eq!(
+            header.validate_against_parent(&parent(), spec, blob_params, blob_params),
+            Err(InvalidHeader::BaseFeeMismatch {
+                expected: 1_000_000_000,
+                got: 1
+            })
+        );
+
+        let header = BlockHeader {
+            excess_blob_gas: Some(1),
+            ..child()
+        };
+        assert_eq!(
+            header.validate_against_parent(&parent(), spec, blob_params, blob_params),
+            Err(InvalidHeader::ExcessBlobGasMismatch {
+                expected: 0,
+                got: 1
+            })
+        );
+
+        let header = BlockHeader {
+            requests_hash: None,
+            ..child()
+        };
+        assert_eq!(
+            header.validate_against_parent(&parent(), spec, blob_params, blob_params),
+            Err(InvalidHeader::RequestsHashNotSet)
+        );
+    }
+
+    #[test]
+    fn validate_against_parent_overflow() {
+        let spec = SpecId::PRAGUE;
+        let blob_params = BlobParams::from_spec(spec);
+        let parent = BlockHeader {
+            number: u64::MAX,
+            ..parent()
+        };
+        assert_eq!(
+            child().validate_against_parent(&parent, spec, blob_params, blob_params),
+            Err(InvalidHeader::NumberOverflow)
+        );
+
+        let parent = BlockHeader {
+            gas_limit: u64::MAX,
+            base_fee_per_gas: None,
+            ..parent()
+        };
+        assert_eq!(
+            parent.next_block_parent_gas_limit(SpecId::LONDON),
+            Err(InvalidHeader::GasLimitOverflow { parent: u64::MAX })
+        );
+        assert_eq!(
+            child().validate_against_parent(&parent, spec, blob_params, blob_params),
+            Err(InvalidHeader::GasLimitOverflow { parent: u64::MAX })
+        );
+    }
+
+    #[test]
+    fn next_block_excess_blob_gas_across_bpo() {
+        let excess_blob_gas = 15_000_000;
+        let parent = BlockHeader {
+            base_fee_per_gas: Some(200),
+            blob_gas_used: Some(BlobParams::bpo1().target_blob_gas_per_block),
+            excess_blob_gas: Some(excess_blob_gas),
+            ..parent()
+        };
+        // Priced with the Osaka update fraction the parent blob fee is above the reserve price,
+        // so excess blob gas changes by the difference of blob gas used and the target.
+        assert_eq!(
+            parent.next_block_excess_blob_gas(
+                SpecId::OSAKA,
+                BlobParams::bpo1(),
+                BlobParams::osaka()
+            ),
+            Some(excess_blob_gas)
+        );
+        // Priced with the BPO1 update fraction it would be below the reserve price.
+        assert_ne!(
+            parent.next_block_excess_blob_gas(
+                SpecId::OSAKA,
+                BlobParams::bpo1(),
+                BlobParams::bpo1()
+            ),
+            Some(excess_blob_gas)
+        );
+    }
+}
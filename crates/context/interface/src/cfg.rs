@@ -1,4 +1,5 @@
 //! Configuration for the EVM. Containing [`SpecId`].
+use crate::fee::FeePolicy;
 use auto_impl::auto_impl;
 use core::{fmt::Debug, hash::Hash};
 use primitives::{hardfork::SpecId, Address, TxKind, U256};
@@ -66,6 +67,86 @@ pub trait Cfg {
 
     /// Returns the limit in bytes for the memory buffer.
     fn memory_limit(&self) -> u64;
+
+    /// Returns the maximum number of items on the interpreter stack.
+    ///
+    /// Defaults to the mainnet value [`STACK_LIMIT`](primitives::constants::STACK_LIMIT).
+    fn max_stack_size(&self) -> usize {
+        primitives::constants::STACK_LIMIT
+    }
+
+    /// Returns the maximum depth of calls and creates.
+    ///
+    /// Defaults to the mainnet value [`CALL_STACK_LIMIT`](primitives::constants::CALL_STACK_LIMIT).
+    fn max_call_depth(&self) -> u64 {
+        primitives::constants::CALL_STACK_LIMIT
+    }
+
+    /// Returns the gas metering mode of the interpreter.
+    ///
+    /// Defaults to [`GasMode::Metered`].
+    fn gas_mode(&self) -> GasMode {
+        GasMode::Metered
+    }
+
+    /// Returns whether bytecode is executed one basic block at a time.
+    ///
+    /// Static gas of a basic block is charged and the stack height checked once on block entry.
+    /// Results are the same as with per instruction charging. Only used with
+    /// [`GasMode::Metered`], without an inspector and for bytecode that is not fused.
+    fn is_basic_block_execution_enabled(&self) -> bool {
+        false
+    }
+
+    /// Returns the policy that distributes the fees of a transaction.
+    ///
+    /// If None, the fees are distributed as on the chain: [`FeePolicy::mainnet`] on mainnet, the
+    /// fee vaults on rollups. Defaults to None.
+    fn fee_policy(&self) -> Option<&FeePolicy> {
+        None
+    }
+}
+
+/// Gas metering mode of the interpreter.
+///
+/// Modes other than [`GasMode::Metered`] only change how frames are executed, transaction
+/// validation and fee payment are unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GasMode {
+    /// Gas is charged and execution halts when it runs out.
+    #[default]
+    Metered,
+    /// Gas is charged but execution never runs out of it.
+    ///
+    /// Gas spent above the limit is tracked separately as an overrun. Fees and refunds are
+    /// computed from the gas used within the limit, so the caller never pays more than the gas
+    /// limit, while the gas used reported by the result includes the overrun to show how much
+    /// gas the execution would need.
+    ///
+    /// Child calls and creates are sized from the remaining gas, as in metered execution, and
+    /// receive no gas once it is exhausted. Gas they spend above their limit is tracked by the
+    /// parent, so the gas used stays exact, but the `GAS` opcode and gas dependent code observe
+    /// the smaller limits rather than the gas an execution with a sufficient limit would have.
+    Tracked,
+    /// Gas is not tracked and execution never runs out of it.
+    ///
+    /// Static gas of instructions is not charged, reported gas usage is not meaningful.
+    Unmetered,
+}
+
+impl GasMode {
+    /// Returns `true` if execution halts when it runs out of gas.
+    #[inline]
+    pub const fn is_metered(self) -> bool {
+        matches!(self, Self::Metered)
+    }
+
+    /// Returns `true` if gas is not tracked.
+    #[inline]
+    pub const fn is_unmetered(self) -> bool {
+        matches!(self, Self::Unmetered)
+    }
 }
 
 /// What bytecode analysis to perform
@@ -0,0 +1,433 @@
+//! Distribution of the fees paid by a transaction.
+//!
+//! A transaction pays the base fee, the priority fee and the blob fee of its gas, rollups add the
+//! L1 data fee and the operator fee. A [`FeePolicy`] sends every fee to the beneficiary or to an
+//! address, burns it, or splits it between them by percentage. The payments are recorded as
+//! [`FeeTransfer`]s in the [local context][crate::LocalContextTr::fee_transfers] of the
+//! transaction.
+use core::fmt;
+use primitives::{Address, U256};
+use std::vec::Vec;
+
+/// Fee paid by a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeeKind {
+    /// Base fee of the used gas.
+    BaseFee,
+    /// Priority fee of the used gas, the part of the gas price above the base fee.
+    PriorityFee,
+    /// Fee of the blob gas.
+    BlobFee,
+    /// L1 data fee of a rollup transaction.
+    L1DataFee,
+    /// Operator fee of a rollup transaction.
+    OperatorFee,
+}
+
+impl FeeKind {
+    /// All fee kinds, in the order they are distributed.
+    pub const ALL: [FeeKind; 5] = [
+        Self::BaseFee,
+        Self::PriorityFee,
+        Self::BlobFee,
+        Self::L1DataFee,
+        Self::OperatorFee,
+    ];
+}
+
+impl fmt::Display for FeeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BaseFee => f.write_str("base fee"),
+            Self::PriorityFee => f.write_str("priority fee"),
+            Self::BlobFee => f.write_str("blob fee"),
+            Self::L1DataFee => f.write_str("L1 data fee"),
+            Self::OperatorFee => f.write_str("operator fee"),
+        }
+    }
+}
+
+/// Fees paid by a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxFees {
+    /// Base fee of the used gas.
+    pub base_fee: U256,
+    /// Priority fee of the used gas.
+    pub priority_fee: U256,
+    /// Fee of the blob gas.
+    pub blob_fee: U256,
+    /// L1 data fee.
+    pub l1_data_fee: U256,
+    /// Operator fee.
+    pub operator_fee: U256,
+}
+
+impl TxFees {
+    /// Returns the fee of the given kind.
+    pub const fn get(&self, kind: FeeKind) -> U256 {
+        match kind {
+            FeeKind::BaseFee => self.base_fee,
+            FeeKind::PriorityFee => self.priority_fee,
+            FeeKind::BlobFee => self.blob_fee,
+            FeeKind::L1DataFee => self.l1_data_fee,
+            FeeKind::OperatorFee => self.operator_fee,
+        }
+    }
+}
+
+/// Recipient of a share of a fee.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeeRecipient {
+    /// Beneficiary of the block.
+    Beneficiary,
+    /// Given address.
+    Address(Address),
+    /// Fee is burned.
+    Burn,
+}
+
+/// Share of a fee sent to a recipient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeShare {
+    /// Recipient of the share.
+    pub recipient: FeeRecipient,
+    /// Percent of the fee.
+    pub percent: u8,
+}
+
+/// Split of a fee between recipients.
+///
+/// Shares sum to at most 100 percent and every share is rounded down. The part of the fee that is
+/// not covered by the shares, including the rounding dust of the shares, is burned.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "Vec<FeeShare>", into = "Vec<FeeShare>")
+)]
+pub struct FeeSplit {
+    shares: Vec<FeeShare>,
+}
+
+impl FeeSplit {
+    /// Creates a split that burns the whole fee.
+    pub const fn burn() -> Self {
+        Self { shares: Vec::new() }
+    }
+
+    /// Creates a split that sends the whole fee to the recipient.
+    pub fn to(recipient: FeeRecipient) -> Self {
+        Self {
+            shares: Vec::from([FeeShare {
+                recipient,
+                percent: 100,
+            }]),
+        }
+    }
+
+    /// Adds a share of the fee for the recipient.
+    ///
+    /// Returns an error if the shares would sum to more than 100 percent.
+    pub fn with_share(
+        mut self,
+        recipient: FeeRecipient,
+        percent: u8,
+    ) -> Result<Self, FeeSplitError> {
+        let total = self.total_percent() + percent as u16;
+        if total > 100 {
+            return Err(FeeSplitError { total });
+        }
+        self.shares.push(FeeShare { recipient, percent });
+        Ok(self)
+    }
+
+    /// Returns the shares of the split.
+    pub fn shares(&self) -> &[FeeShare] {
+        &self.shares
+    }
+
+    /// Returns the sum of the percents of the shares.
+    pub fn total_percent(&self) -> u16 {
+        self.shares.iter().map(|share| share.percent as u16).sum()
+    }
+
+    /// Splits the fee into transfers, the burned part has no recipient.
+    ///
+    /// Every share has a transfer, even of a zero amount. The burned remainder has a transfer if
+    /// it is not zero.
+    pub fn transfers(&self, kind: FeeKind, amount: U256, beneficiary: Address) -> Vec<FeeTransfer> {
+        let mut transfers = Vec::with_capacity(self.shares.len() + 1);
+        let mut left = amount;
+        for share in &self.shares {
+            let share_amount = amount.saturating_mul(U256::from(share.percent)) / U256::from(100);
+            left -= share_amount;
+            let to = match share.recipient {
+                FeeRecipient::Beneficiary => Some(beneficiary),
+                FeeRecipient::Address(address) => Some(address),
+                FeeRecipient::Burn => None,
+            };
+            transfers.push(FeeTransfer {
+                kind,
+                to,
+                amount: share_amount,
+            });
+        }
+        if !left.is_zero() {
+            transfers.push(FeeTransfer {
+                kind,
+                to: None,
+                amount: left,
+            });
+        }
+        transfers
+    }
+}
+
+impl TryFrom<Vec<FeeShare>> for FeeSplit {
+    type Error = FeeSplitError;
+
+    fn try_from(shares: Vec<FeeShare>) -> Result<Self, Self::Error> {
+        shares.into_iter().try_fold(Self::burn(), |split, share| {
+            split.with_share(share.recipient, share.percent)
+        })
+    }
+}
+
+impl From<FeeSplit> for Vec<FeeShare> {
+    fn from(split: FeeSplit) -> Self {
+        split.shares
+    }
+}
+
+/// Error of a [`FeeSplit`] whose shares sum to more than 100 percent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FeeSplitError {
+    /// Sum of the percents of the shares.
+    pub total: u16,
+}
+
+impl fmt::Display for FeeSplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "fee shares sum to {}% which is more than 100%",
+            self.total
+        )
+    }
+}
+
+impl core::error::Error for FeeSplitError {}
+
+/// Policy that decides where the fees of a transaction go.
+///
+/// Defaults to the mainnet policy, see [`FeePolicy::mainnet`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeePolicy {
+    /// Split of the base fee.
+    pub base_fee: FeeSplit,
+    /// Split of the priority fee.
+    pub priority_fee: FeeSplit,
+    /// Split of the blob fee.
+    pub blob_fee: FeeSplit,
+    /// Split of the L1 data fee.
+    pub l1_data_fee: FeeSplit,
+    /// Split of the operator fee.
+    pub operator_fee: FeeSplit,
+}
+
+impl Default for FeePolicy {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+impl FeePolicy {
+    /// Mainnet policy, the priority fee goes to the beneficiary and the other fees are burned.
+    pub fn mainnet() -> Self {
+        Self::burn_all().with_split(
+            FeeKind::PriorityFee,
+            FeeSplit::to(FeeRecipient::Beneficiary),
+        )
+    }
+
+    /// Policy that burns all fees.
+    pub const fn burn_all() -> Self {
+        Self {
+            base_fee: FeeSplit::burn(),
+            priority_fee: FeeSplit::burn(),
+            blob_fee: FeeSplit::burn(),
+            l1_data_fee: FeeSplit::burn(),
+            operator_fee: FeeSplit::burn(),
+        }
+    }
+
+    /// Sets the split of the fee.
+    pub fn with_split(mut self, kind: FeeKind, split: FeeSplit) -> Self {
+        *self.split_mut(kind) = split;
+        self
+    }
+
+    /// Returns the split of the fee.
+    pub const fn split(&self, kind: FeeKind) -> &FeeSplit {
+        match kind {
+            FeeKind::BaseFee => &self.base_fee,
+            FeeKind::PriorityFee => &self.priority_fee,
+            FeeKind::BlobFee => &self.blob_fee,
+            FeeKind::L1DataFee => &self.l1_data_fee,
+            FeeKind::OperatorFee => &self.operator_fee,
+        }
+    }
+
+    /// Returns the mutable split of the fee.
+    pub fn split_mut(&mut self, kind: FeeKind) -> &mut FeeSplit {
+        match kind {
+            FeeKind::BaseFee => &mut self.base_fee,
+            FeeKind::PriorityFee => &mut self.priority_fee,
+            FeeKind::BlobFee => &mut self.blob_fee,
+            FeeKind::L1DataFee => &mut self.l1_data_fee,
+            FeeKind::OperatorFee => &mut self.operator_fee,
+        }
+    }
+
+    /// Returns the transfers of the fees of a transaction.
+    pub fn transfers(&self, fees: &TxFees, beneficiary: Address) -> Vec<FeeTransfer> {
+        FeeKind::ALL
+            .into_iter()
+            .flat_map(|kind| {
+                self.split(kind)
+                    .transfers(kind, fees.get(kind), beneficiary)
+            })
+            .collect()
+    }
+}
+
+/// Transfer of a part of a fee, recorded in the local context of the transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeTransfer {
+    /// Transferred fee.
+    pub kind: FeeKind,
+    /// Recipient of the transfer, `None` if the amount is burned.
+    pub to: Option<Address>,
+    /// Transferred amount.
+    pub amount: U256,
+}
+
+impl FeeTransfer {
+    /// Returns `true` if the amount is burned.
+    pub const fn is_burn(&self) -> bool {
+        self.to.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::address;
+
+    #[test]
+    fn test_fee_policy_transfers() {
+        let beneficiary = address!("0x0000000000000000000000000000000000000001");
+        let treasury = address!("0x0000000000000000000000000000000000000002");
+        let policy = FeePolicy::mainnet().with_split(
+            FeeKind::BaseFee,
+            FeeSplit::burn()
+                .with_share(FeeRecipient::Address(treasury), 30)
+                .and_then(|split| split.with_share(FeeRecipient::Beneficiary, 20))
+                .unwrap(),
+        );
+        let fees = TxFees {
+            base_fee: U256::from(1001),
+            priority_fee: U256::from(50),
+            blob_fee: U256::from(7),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            policy.transfers(&fees, beneficiary),
+            vec![
+                FeeTransfer {
+                    kind: FeeKind::BaseFee,
+                    to: Some(treasury),
+                    amount: U256::from(300),
+                },
+                FeeTransfer {
+                    kind: FeeKind::BaseFee,
+                    to: Some(beneficiary),
+                    amount: U256::from(200),
+                },
+                FeeTransfer {
+                    kind: FeeKind::BaseFee,
+                    to: None,
+                    amount: U256::from(501),
+                },
+                FeeTransfer {
+                    kind: FeeKind::PriorityFee,
+                    to: Some(beneficiary),
+                    amount: U256::from(50),
+                },
+                FeeTransfer {
+                    kind: FeeKind::BlobFee,
+                    to: None,
+                    amount: U256::from(7),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fee_split_over_hundred_percent() {
+        let split = FeeSplit::burn().with_share(FeeRecipient::Burn, 60).unwrap();
+
+        assert_eq!(
+            split.clone().with_share(FeeRecipient::Beneficiary, 41),
+            Err(FeeSplitError { total: 101 })
+        );
+        assert_eq!(
+            FeeSplit::to(FeeRecipient::Beneficiary).with_share(FeeRecipient::Burn, 1),
+            Err(FeeSplitError { total: 101 })
+        );
+        assert_eq!(
+            split
+                .with_share(FeeRecipient::Beneficiary, 40)
+                .map(|split| split.total_percent()),
+            Ok(100)
+        );
+    }
+
+    #[test]
+    fn test_fee_split_dust_is_burned() {
+        let beneficiary = address!("0x0000000000000000000000000000000000000001");
+        let treasury = address!("0x0000000000000000000000000000000000000002");
+        let split = FeeSplit::burn()
+            .with_share(FeeRecipient::Address(treasury), 50)
+            .and_then(|split| split.with_share(FeeRecipient::Beneficiary, 50))
+            .unwrap();
+
+        assert_eq!(
+            split.transfers(FeeKind::BaseFee, U256::from(9), beneficiary),
+            vec![
+                FeeTransfer {
+                    kind: FeeKind::BaseFee,
+                    to: Some(treasury),
+                    amount: U256::from(4),
+                },
+                FeeTransfer {
+                    kind: FeeKind::BaseFee,
+                    to: Some(beneficiary),
+                    amount: U256::from(4),
+                },
+                FeeTransfer {
+                    kind: FeeKind::BaseFee,
+                    to: None,
+                    amount: U256::from(1),
+                },
+            ]
+        );
+    }
+}
@@ -8,6 +8,7 @@ extern crate alloc as std;
 pub mod block;
 pub mod cfg;
 pub mod context;
+pub mod fee;
 pub mod host;
 pub mod journaled_state;
 pub mod local;
@@ -15,10 +16,11 @@ pub mod result;
 pub mod transaction;
 
 pub use block::Block;
-pub use cfg::{Cfg, CreateScheme, TransactTo};
+pub use cfg::{Cfg, CreateScheme, GasMode, TransactTo};
 pub use context::{ContextError, ContextSetters, ContextTr};
 pub use database_interface::{erased_error::ErasedError, DBErrorMarker, Database};
 pub use either;
+pub use fee::{FeeKind, FeePolicy, FeeRecipient, FeeSplit, FeeSplitError, FeeTransfer, TxFees};
 pub use host::{DummyHost, Host};
 pub use journaled_state::JournalTr;
 pub use local::{FrameStack, FrameToken, LocalContextTr, OutFrame};
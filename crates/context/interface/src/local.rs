@@ -1,4 +1,5 @@
 //! Local context trait [`LocalContextTr`] and related types.
+use crate::fee::FeeTransfer;
 use core::{
     cell::{Ref, RefCell},
     ops::Range,
@@ -64,12 +65,33 @@ impl<T> FrameStack<T> {
         self.index = Some(0);
     }
 
+    /// Creates a stack from the active frames, the last one being the current frame.
+    pub fn from_frames(frames: Vec<T>) -> Self {
+        let index = frames.len().checked_sub(1);
+        Self {
+            stack: frames,
+            index,
+        }
+    }
+
     /// Returns the current index of the stack.
     #[inline]
     pub fn index(&self) -> Option<usize> {
         self.index
     }
 
+    /// Returns the active frames, the last one being the current frame.
+    #[inline]
+    pub fn frames(&self) -> &[T] {
+        &self.stack[..self.index.map_or(0, |index| index + 1)]
+    }
+
+    /// Returns the active frames as mutable, the last one being the current frame.
+    #[inline]
+    pub fn frames_mut(&mut self) -> &mut [T] {
+        &mut self.stack[..self.index.map_or(0, |index| index + 1)]
+    }
+
     /// Increments the index.
     ///
     /// # Safety
@@ -132,6 +154,27 @@ impl<T> FrameStack<T> {
     }
 }
 
+/// Only the active frames are serialized, the pooled ones are dropped.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for FrameStack<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.frames().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for FrameStack<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(Self::from_frames)
+    }
+}
+
 /// A potentially initialized frame. Used when initializing a new frame in the main loop.
 #[allow(missing_debug_implementations)]
 pub struct OutFrame<'a, T> {
@@ -238,6 +281,34 @@ pub trait LocalContextTr {
     ///
     /// Returns `Some(String)` if a precompile error message was recorded.
     fn take_precompile_error_context(&mut self) -> Option<String>;
+
+    /// Records a fee transfer of the transaction.
+    ///
+    /// Transfers are recorded when the fees are distributed. They are not cleared with the rest of
+    /// the local context, so they can be read after the transaction, and are discarded when the
+    /// next transaction starts.
+    ///
+    /// Does nothing by default.
+    fn record_fee_transfer(&mut self, _transfer: FeeTransfer) {}
+
+    /// Returns the fee transfers of the last transaction.
+    ///
+    /// Returns no transfers by default.
+    fn fee_transfers(&self) -> &[FeeTransfer] {
+        &[]
+    }
+
+    /// Take and clear the recorded fee transfers.
+    ///
+    /// Returns no transfers by default.
+    fn take_fee_transfers(&mut self) -> Vec<FeeTransfer> {
+        Vec::new()
+    }
+
+    /// Discards the recorded fee transfers.
+    fn clear_fee_transfers(&mut self) {
+        self.take_fee_transfers();
+    }
 }
 
 #[cfg(test)]
@@ -282,4 +353,20 @@ mod tests {
         assert!(b.init);
         assert_eq!(unsafe { b.get_unchecked() }, &mut 2);
     }
+
+    #[test]
+    fn frame_stack_from_frames() {
+        let mut stack = FrameStack::from_frames(vec![1, 2]);
+        assert_eq!(stack.index(), Some(1));
+        assert_eq!(stack.get(), &mut 2);
+
+        stack.pop();
+        assert_eq!(stack.frames(), &[1]);
+        let mut next = stack.get_next();
+        assert_eq!(unsafe { next.get_unchecked() }, &mut 2);
+
+        stack.pop();
+        assert!(stack.frames().is_empty());
+        assert_eq!(FrameStack::<u8>::from_frames(vec![]).index(), None);
+    }
 }
@@ -10,7 +10,7 @@
 use crate::{context::ContextError, transaction::TransactionError};
 use core::fmt::{self, Debug};
 use database_interface::DBErrorMarker;
-use primitives::{Address, Bytes, Log, U256};
+use primitives::{Address, Bytes, Log, B256, U256};
 use state::EvmState;
 use std::{borrow::Cow, boxed::Box, string::String, vec::Vec};
 
@@ -554,6 +554,77 @@ pub enum InvalidHeader {
     PrevrandaoNotSet,
     /// `excess_blob_gas` is not set for Cancun and above.
     ExcessBlobGasNotSet,
+    /// Block number is not the parent block number plus one.
+    NumberNotSequential {
+        /// Parent block number.
+        parent: u64,
+        /// Block number.
+        number: u64,
+    },
+    /// Parent block number is the maximum block number.
+    NumberOverflow,
+    /// Block timestamp is not greater than the parent timestamp.
+    TimestampNotIncreasing {
+        /// Parent block timestamp.
+        parent: u64,
+        /// Block timestamp.
+        timestamp: u64,
+    },
+    /// Gas used is greater than the gas limit of the block.
+    GasUsedExceedsGasLimit {
+        /// Gas used by the block.
+        gas_used: u64,
+        /// Gas limit of the block.
+        gas_limit: u64,
+    },
+    /// Gas limit changed by more than allowed relative to the parent or is below the minimum.
+    InvalidGasLimit {
+        /// Parent gas limit.
+        parent: u64,
+        /// Block gas limit.
+        gas_limit: u64,
+    },
+    /// Gas limit of the London fork block overflows when scaled by the elasticity multiplier.
+    GasLimitOverflow {
+        /// Parent gas limit.
+        parent: u64,
+    },
+    /// `base_fee` is not set for London and above.
+    BaseFeeNotSet,
+    /// Base fee does not match the one calculated from the parent.
+    BaseFeeMismatch {
+        /// Expected base fee.
+        expected: u64,
+        /// Base fee of the block.
+        got: u64,
+    },
+    /// `blob_gas_used` is not set for Cancun and above.
+    BlobGasUsedNotSet,
+    /// Excess blob gas does not match the one calculated from the parent.
+    ExcessBlobGasMismatch {
+        /// Expected excess blob gas.
+        expected: u64,
+        /// Excess blob gas of the block.
+        got: u64,
+    },
+    /// `withdrawals_root` is not set for Shanghai and above.
+    WithdrawalsRootNotSet,
+    /// Withdrawals root does not match the withdrawals of the block.
+    WithdrawalsRootMismatch {
+        /// Expected withdrawals root.
+        expected: B256,
+        /// Withdrawals root of the block.
+        got: B256,
+    },
+    /// `requests_hash` is not set for Prague and above.
+    RequestsHashNotSet,
+    /// Requests hash does not match the requests of the block.
+    RequestsHashMismatch {
+        /// Expected requests hash.
+        expected: B256,
+        /// Requests hash of the block.
+        got: B256,
+    },
 }
 
 impl core::error::Error for InvalidHeader {}
@@ -563,6 +634,47 @@ impl fmt::Display for InvalidHeader {
         match self {
             Self::PrevrandaoNotSet => write!(f, "`prevrandao` not set"),
             Self::ExcessBlobGasNotSet => write!(f, "`excess_blob_gas` not set"),
+            Self::NumberNotSequential { parent, number } => {
+                write!(f, "block number {number} does not follow parent {parent}")
+            }
+            Self::NumberOverflow => write!(f, "block number overflow"),
+            Self::TimestampNotIncreasing { parent, timestamp } => write!(
+                f,
+                "block timestamp {timestamp} is not greater than parent timestamp {parent}"
+            ),
+            Self::GasUsedExceedsGasLimit {
+                gas_used,
+                gas_limit,
+            } => write!(f, "gas used {gas_used} exceeds gas limit {gas_limit}"),
+            Self::InvalidGasLimit { parent, gas_limit } => write!(
+                f,
+                "gas limit {gas_limit} is out of bounds for parent gas limit {parent}"
+            ),
+            Self::GasLimitOverflow { parent } => {
+                write!(f, "gas limit overflow for parent gas limit {parent}")
+            }
+            Self::BaseFeeNotSet => write!(f, "`base_fee` not set"),
+            Self::BaseFeeMismatch { expected, got } => {
+                write!(f, "base fee mismatch: expected {expected}, got {got}")
+            }
+            Self::BlobGasUsedNotSet => write!(f, "`blob_gas_used` not set"),
+            Self::ExcessBlobGasMismatch { expected, got } => {
+                write!(
+                    f,
+                    "excess blob gas mismatch: expected {expected}, got {got}"
+                )
+            }
+            Self::WithdrawalsRootNotSet => write!(f, "`withdrawals_root` not set"),
+            Self::WithdrawalsRootMismatch { expected, got } => {
+                write!(
+                    f,
+                    "withdrawals root mismatch: expected {expected}, got {got}"
+                )
+            }
+            Self::RequestsHashNotSet => write!(f, "`requests_hash` not set"),
+            Self::RequestsHashMismatch { expected, got } => {
+                write!(f, "requests hash mismatch: expected {expected}, got {got}")
+            }
         }
     }
 }
@@ -1,6 +1,11 @@
 //! This module contains [`BlockEnv`] and it implements [`Block`] trait.
-use context_interface::block::{BlobExcessGasAndPrice, Block};
-use primitives::{eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, Address, B256, U256};
+use context_interface::{
+    block::{BlobExcessGasAndPrice, BlobParams, Block, BlockHeader},
+    result::InvalidHeader,
+};
+use primitives::{
+    eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, hardfork::SpecId, Address, B256, U256,
+};
 
 /// The block environment
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -55,6 +60,44 @@ impl BlockEnv {
             base_fee_update_fraction,
         ));
     }
+
+    /// Derives the environment of the block following `parent`.
+    ///
+    /// Number, gas limit, base fee and excess blob gas are calculated from the parent header.
+    /// `spec` and `blob_params` are the ones active at the new block, `parent_blob_params` the
+    /// ones active at the parent. Beneficiary and `prevrandao` are left to their defaults and
+    /// need to be set by the caller.
+    pub fn next_block(
+        parent: &BlockHeader,
+        timestamp: u64,
+        spec: SpecId,
+        blob_params: BlobParams,
+        parent_blob_params: BlobParams,
+    ) -> Result<Self, InvalidHeader> {
+        if timestamp <= parent.timestamp {
+            return Err(InvalidHeader::TimestampNotIncreasing {
+                parent: parent.timestamp,
+                timestamp,
+            });
+        }
+        // Gas limit of the London fork block is scaled so the gas target stays the same.
+        let gas_limit = parent.next_block_parent_gas_limit(spec)?;
+        Ok(Self {
+            number: U256::from(parent.next_block_number()?),
+            timestamp: U256::from(timestamp),
+            gas_limit,
+            basefee: parent.next_block_base_fee(spec).unwrap_or_default(),
+            blob_excess_gas_and_price: parent
+                .next_block_excess_blob_gas(spec, blob_params, parent_blob_params)
+                .map(|excess_blob_gas| {
+                    BlobExcessGasAndPrice::new(
+                        excess_blob_gas,
+                        blob_params.blob_base_fee_update_fraction,
+                    )
+                }),
+            ..Default::default()
+        })
+    }
 }
 
 impl Block for BlockEnv {
@@ -1,7 +1,12 @@
 //! This module contains [`CfgEnv`] and implements [`Cfg`] trait for it.
-pub use context_interface::Cfg;
-
-use primitives::{eip170, eip3860, eip7825, hardfork::SpecId};
+pub use context_interface::{Cfg, GasMode};
+
+use context_interface::fee::FeePolicy;
+use primitives::{
+    constants::{CALL_STACK_LIMIT, STACK_LIMIT},
+    eip170, eip3860, eip7825,
+    hardfork::SpecId,
+};
 /// EVM configuration
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -34,6 +39,28 @@ pub struct CfgEnv<SPEC = SpecId> {
     ///
     /// Useful to increase this because of tests.
     pub limit_contract_initcode_size: Option<usize>,
+    /// Interpreter stack size limit override.
+    ///
+    /// If None, the mainnet limit of [`STACK_LIMIT`] items is used.
+    pub limit_stack_size: Option<usize>,
+    /// Call depth limit override.
+    ///
+    /// If None, the mainnet limit of [`CALL_STACK_LIMIT`] is used.
+    pub limit_call_depth: Option<u64>,
+    /// Gas metering mode of the interpreter.
+    ///
+    /// Defaults to [`GasMode::Metered`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_mode: GasMode,
+    /// Executes bytecode one basic block at a time, see [`Cfg::is_basic_block_execution_enabled`].
+    ///
+    /// Defaults to `false`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub basic_block_execution: bool,
+    /// Fee policy override.
+    ///
+    /// If None, the fees are distributed as on the chain, see [`Cfg::fee_policy`].
+    pub fee_policy: Option<FeePolicy>,
     /// Skips the nonce validation against the account's nonce
     pub disable_nonce_check: bool,
     /// Blob max count. EIP-7840 Add blob schedule to EL config files.
@@ -154,6 +181,11 @@ impl<SPEC> CfgEnv<SPEC> {
             tx_chain_id_check: true,
             limit_contract_code_size: None,
             limit_contract_initcode_size: None,
+            limit_stack_size: None,
+            limit_call_depth: None,
+            gas_mode: GasMode::Metered,
+            basic_block_execution: false,
+            fee_policy: None,
             spec,
             disable_nonce_check: false,
             max_blobs_per_tx: None,
@@ -205,6 +237,11 @@ impl<SPEC> CfgEnv<SPEC> {
             tx_chain_id_check: self.tx_chain_id_check,
             limit_contract_code_size: self.limit_contract_code_size,
             limit_contract_initcode_size: self.limit_contract_initcode_size,
+            limit_stack_size: self.limit_stack_size,
+            limit_call_depth: self.limit_call_depth,
+            gas_mode: self.gas_mode,
+            basic_block_execution: self.basic_block_execution,
+            fee_policy: self.fee_policy,
             spec,
             disable_nonce_check: self.disable_nonce_check,
             tx_gas_limit_cap: self.tx_gas_limit_cap,
@@ -231,6 +268,36 @@ impl<SPEC> CfgEnv<SPEC> {
         }
     }
 
+    /// Sets the interpreter stack size limit.
+    pub fn with_max_stack_size(mut self, max_stack_size: usize) -> Self {
+        self.limit_stack_size = Some(max_stack_size);
+        self
+    }
+
+    /// Sets the call depth limit.
+    pub fn with_max_call_depth(mut self, max_call_depth: u64) -> Self {
+        self.limit_call_depth = Some(max_call_depth);
+        self
+    }
+
+    /// Sets the gas metering mode of the interpreter.
+    pub fn with_gas_mode(mut self, gas_mode: GasMode) -> Self {
+        self.gas_mode = gas_mode;
+        self
+    }
+
+    /// Enables or disables execution of bytecode one basic block at a time.
+    pub fn with_basic_block_execution(mut self, enabled: bool) -> Self {
+        self.basic_block_execution = enabled;
+        self
+    }
+
+    /// Sets the fee policy.
+    pub fn with_fee_policy(mut self, fee_policy: FeePolicy) -> Self {
+        self.fee_policy = Some(fee_policy);
+        self
+    }
+
     /// Sets the blob target
     pub fn with_max_blobs_per_tx(mut self, max_blobs_per_tx: u64) -> Self {
         self.set_max_blobs_per_tx(max_blobs_per_tx);
@@ -410,6 +477,31 @@ impl<SPEC: Into<SpecId> + Copy> Cfg for CfgEnv<SPEC> {
             }
         }
     }
+
+    #[inline]
+    fn max_stack_size(&self) -> usize {
+        self.limit_stack_size.unwrap_or(STACK_LIMIT)
+    }
+
+    #[inline]
+    fn max_call_depth(&self) -> u64 {
+        self.limit_call_depth.unwrap_or(CALL_STACK_LIMIT)
+    }
+
+    #[inline]
+    fn gas_mode(&self) -> GasMode {
+        self.gas_mode
+    }
+
+    #[inline]
+    fn is_basic_block_execution_enabled(&self) -> bool {
+        self.basic_block_execution
+    }
+
+    #[inline]
+    fn fee_policy(&self) -> Option<&FeePolicy> {
+        self.fee_policy.as_ref()
+    }
 }
 
 impl<SPEC: Default> Default for CfgEnv<SPEC> {
@@ -2,9 +2,13 @@
 //!
 //! Entry submodule contains [`JournalEntry`] and [`JournalEntryTr`] traits.
 //! and inner submodule contains [`JournalInner`] struct that contains state.
+#[cfg(feature = "std")]
+pub mod code_cache;
 pub mod inner;
 pub mod warm_addresses;
 
+#[cfg(feature = "std")]
+pub use code_cache::{CodeCache, CodeCacheMetrics};
 pub use context_interface::journaled_state::entry::{JournalEntry, JournalEntryTr};
 pub use inner::JournalInner;
 
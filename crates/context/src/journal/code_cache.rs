@@ -0,0 +1,234 @@
+//! Shared cache of analyzed bytecode.
+use bytecode::Bytecode;
+use primitives::{HashMap, B256};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// Bounded, thread-safe cache of analyzed bytecode keyed by the code hash.
+///
+/// Cloning the cache is cheap and all clones share the same entries, so a single cache can be
+/// used by multiple journals, EVM instances and threads. When the cache is full the least
+/// recently used entry is evicted.
+///
+/// Set it on [`JournalInner::code_cache`](super::JournalInner::code_cache) to make the journal
+/// consult it when loading code.
+#[derive(Clone, Debug)]
+pub struct CodeCache {
+    inner: Arc<Mutex<CodeCacheInner>>,
+}
+
+/// Metrics of the [`CodeCache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CodeCacheMetrics {
+    /// Number of lookups that found the code in the cache.
+    pub hits: u64,
+    /// Number of lookups that did not find the code in the cache.
+    pub misses: u64,
+    /// Number of inserted codes.
+    pub inserts: u64,
+    /// Number of codes evicted because the cache was full.
+    pub evictions: u64,
+}
+
+#[derive(Debug)]
+struct CodeCacheInner {
+    /// Maximum number of entries.
+    capacity: usize,
+    /// Cached code and the tick of its last use.
+    entries: HashMap<B256, (Bytecode, u64)>,
+    /// Code hashes ordered by the tick of their last use.
+    lru: BTreeMap<u64, B256>,
+    /// Monotonic counter used to order the entries by use.
+    tick: u64,
+    metrics: CodeCacheMetrics,
+}
+
+impl CodeCacheInner {
+    fn touch(&mut self, hash: B256) -> Option<Bytecode> {
+        let (code, last_used) = self.entries.get_mut(&hash)?;
+        self.lru.remove(last_used);
+        self.tick += 1;
+        *last_used = self.tick;
+        self.lru.insert(self.tick, hash);
+        Some(code.clone())
+    }
+
+    fn insert(&mut self, hash: B256, code: Bytecode) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.entries.len() >= self.capacity {
+            let Some((_, evicted)) = self.lru.pop_first() else {
+                break;
+            };
+            self.entries.remove(&evicted);
+            self.metrics.evictions += 1;
+        }
+        self.tick += 1;
+        self.entries.insert(hash, (code, self.tick));
+        self.lru.insert(self.tick, hash);
+        self.metrics.inserts += 1;
+    }
+}
+
+impl CodeCache {
+    /// Creates a new cache that holds at most `capacity` codes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(CodeCacheInner {
+                capacity,
+                entries: HashMap::default(),
+                lru: BTreeMap::new(),
+                tick: 0,
+                metrics: CodeCacheMetrics::default(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CodeCacheInner> {
+        // Cache is always left in a consistent state so poisoning can be ignored.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the cached code and marks it as recently used.
+    pub fn get(&self, hash: &B256) -> Option<Bytecode> {
+        let mut inner = self.lock();
+        let code = inner.touch(*hash);
+        if code.is_some() {
+            inner.metrics.hits += 1;
+        } else {
+            inner.metrics.misses += 1;
+        }
+        code
+    }
+
+    /// Inserts the code, evicting the least recently used one if the cache is full.
+    pub fn insert(&self, hash: B256, code: Bytecode) {
+        let mut inner = self.lock();
+        if inner.touch(hash).is_none() {
+            inner.insert(hash, code);
+        }
+    }
+
+    /// Returns the cached code or inserts the given one.
+    ///
+    /// Returned code shares its analysis with all other users of the cache.
+    pub fn get_or_insert(&self, hash: B256, code: Bytecode) -> Bytecode {
+        let mut inner = self.lock();
+        if let Some(cached) = inner.touch(hash) {
+            inner.metrics.hits += 1;
+            return cached;
+        }
+        inner.metrics.misses += 1;
+        inner.insert(hash, code.clone());
+        code
+    }
+
+    /// Returns the cached code or loads it with `f` and inserts it.
+    ///
+    /// The lock is not held while `f` is running.
+    pub fn get_or_try_insert_with<E>(
+        &self,
+        hash: B256,
+        f: impl FnOnce() -> Result<Bytecode, E>,
+    ) -> Result<Bytecode, E> {
+        if let Some(code) = self.get(&hash) {
+            return Ok(code);
+        }
+        let code = f()?;
+        self.insert(hash, code.clone());
+        Ok(code)
+    }
+
+    /// Returns the number of cached codes.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of cached codes.
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// Returns the cache metrics.
+    pub fn metrics(&self) -> CodeCacheMetrics {
+        self.lock().metrics
+    }
+
+    /// Removes all cached codes. Metrics are kept.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.lru.clear();
+    }
+}
+
+impl PartialEq for CodeCache {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for CodeCache {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::Bytes;
+
+    fn code(byte: u8) -> (B256, Bytecode) {
+        let code = Bytecode::new_raw(Bytes::from(vec![0x60, byte, 0x00]));
+        (code.hash_slow(), code)
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = CodeCache::new(2);
+        let (hash1, code1) = code(1);
+        let (hash2, code2) = code(2);
+        let (hash3, code3) = code(3);
+
+        cache.insert(hash1, code1.clone());
+        cache.insert(hash2, code2);
+        // Makes `code2` the least recently used.
+        assert_eq!(cache.get(&hash1), Some(code1));
+        cache.insert(hash3, code3);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&hash2).is_none());
+        assert!(cache.get(&hash1).is_some());
+        assert!(cache.get(&hash3).is_some());
+        assert_eq!(
+            cache.metrics(),
+            CodeCacheMetrics {
+                hits: 3,
+                misses: 1,
+                inserts: 3,
+                evictions: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn shares_analysis() {
+        let cache = CodeCache::new(1);
+        let (hash, code) = code(1);
+        let cached = cache.get_or_insert(hash, code.clone());
+        let other = cache
+            .clone()
+            .get_or_try_insert_with::<()>(hash, || unreachable!())
+            .unwrap();
+        assert_eq!(
+            cached.legacy_jump_table().unwrap().as_slice().as_ptr(),
+            other.legacy_jump_table().unwrap().as_slice().as_ptr()
+        );
+    }
+}
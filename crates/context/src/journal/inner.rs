@@ -1,4 +1,6 @@
 //! Module containing the [`JournalInner`] that is part of [`crate::Journal`].
+#[cfg(feature = "std")]
+use super::code_cache::CodeCache;
 use super::warm_addresses::WarmAddresses;
 use bytecode::Bytecode;
 use context_interface::{
@@ -58,6 +60,12 @@ pub struct JournalInner<ENTRY> {
     pub spec: SpecId,
     /// Warm addresses containing both coinbase and current precompiles.
     pub warm_addresses: WarmAddresses,
+    /// Shared cache of analyzed bytecode consulted when loading code.
+    ///
+    /// Cache is not cleared between transactions and is not serialized.
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub code_cache: Option<CodeCache>,
 }
 
 impl<ENTRY: JournalEntryTr> Default for JournalInner<ENTRY> {
@@ -81,9 +89,18 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
             depth: 0,
             spec: SpecId::default(),
             warm_addresses: WarmAddresses::new(),
+            #[cfg(feature = "std")]
+            code_cache: None,
         }
     }
 
+    /// Sets the shared cache of analyzed bytecode.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn set_code_cache(&mut self, code_cache: CodeCache) {
+        self.code_cache = Some(code_cache);
+    }
+
     /// Returns the logs
     #[inline]
     pub fn take_logs(&mut self) -> Vec<Log> {
@@ -109,6 +126,8 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
             transaction_id,
             spec,
             warm_addresses,
+            #[cfg(feature = "std")]
+                code_cache: _,
         } = self;
         // Spec, precompiles, BAL and state are not changed. It is always set again execution.
         let _ = spec;
@@ -139,6 +158,8 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
             transaction_id,
             spec,
             warm_addresses,
+            #[cfg(feature = "std")]
+                code_cache: _,
         } = self;
         let is_spurious_dragon_enabled = spec.is_enabled_in(SPURIOUS_DRAGON);
         // iterate over all journals entries and revert our global state
@@ -171,6 +192,8 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
             transaction_id,
             spec,
             warm_addresses,
+            #[cfg(feature = "std")]
+                code_cache: _,
         } = self;
         // Spec is not changed. And it is always set again in execution.
         let _ = spec;
@@ -651,10 +674,7 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
     where
         'db: 'a,
     {
-        let mut load = self.load_account_mut_optional(db, address, skip_cold_load)?;
-        if load_code {
-            load.data.load_code_preserve_error()?;
-        }
+        let load = self.load_account_mut_optional_inner(db, address, load_code, skip_cold_load)?;
         Ok(load.map(|i| i.into_account()))
     }
 
@@ -684,11 +704,7 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
     where
         'db: 'a,
     {
-        let mut load = self.load_account_mut_optional(db, address, skip_cold_load)?;
-        if load_code {
-            load.data.load_code_preserve_error()?;
-        }
-        Ok(load)
+        self.load_account_mut_optional_inner(db, address, load_code, skip_cold_load)
     }
 
     /// Gets the account mut reference.
@@ -721,13 +737,30 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
     }
 
     /// Loads account. If account is already loaded it will be marked as warm.
-    #[inline(never)]
+    #[inline]
     pub fn load_account_mut_optional<'a, 'db, DB: Database>(
         &'a mut self,
         db: &'db mut DB,
         address: Address,
         skip_cold_load: bool,
     ) -> Result<StateLoad<JournaledAccount<'a, DB, ENTRY>>, JournalLoadError<DB::Error>>
+    where
+        'db: 'a,
+    {
+        self.load_account_mut_optional_inner(db, address, false, skip_cold_load)
+    }
+
+    /// Loads account and optionally its code. If account is already loaded it will be marked as warm.
+    ///
+    /// Code is loaded through the [`code_cache`](Self::code_cache) if it is set.
+    #[inline(never)]
+    fn load_account_mut_optional_inner<'a, 'db, DB: Database>(
+        &'a mut self,
+        db: &'db mut DB,
+        address: Address,
+        load_code: bool,
+        skip_cold_load: bool,
+    ) -> Result<StateLoad<JournaledAccount<'a, DB, ENTRY>>, JournalLoadError<DB::Error>>
     where
         'db: 'a,
     {
@@ -773,6 +806,13 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
                 let account = if let Some(account) = db.basic(address)? {
                     let mut account: Account = account.into();
                     account.transaction_id = self.transaction_id;
+                    // share the analysis of the code returned by the database.
+                    #[cfg(feature = "std")]
+                    if let (Some(cache), Some(code)) = (&self.code_cache, &mut account.info.code) {
+                        if account.info.code_hash != KECCAK_EMPTY {
+                            *code = cache.get_or_insert(account.info.code_hash, code.clone());
+                        }
+                    }
                     account
                 } else {
                     Account::new_not_existing(self.transaction_id)
@@ -787,6 +827,21 @@ impl<ENTRY: JournalEntryTr> JournalInner<ENTRY> {
             }
         };
 
+        if load_code && account.info.code.is_none() {
+            let hash = account.info.code_hash;
+            let code = 'code: {
+                if hash == KECCAK_EMPTY {
+                    break 'code Bytecode::default();
+                }
+                #[cfg(feature = "std")]
+                if let Some(cache) = &self.code_cache {
+                    break 'code cache.get_or_try_insert_with(hash, || db.code_by_hash(hash))?;
+                }
+                db.code_by_hash(hash)?
+            };
+            account.info.code = Some(code);
+        }
+
         Ok(StateLoad::new(
             JournaledAccount::new(
                 address,
@@ -968,4 +1023,52 @@ mod tests {
         assert!(!state_load.is_cold); // Should be warm
         assert_eq!(state_load.data, U256::ZERO); // Empty slot
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_load_code_uses_code_cache() {
+        use database::CacheDB;
+        use primitives::Bytes;
+
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x01, 0x00]));
+        let code_hash = code.hash_slow();
+        let test_address = address!("1000000000000000000000000000000000000000");
+        let account_info = AccountInfo {
+            code_hash,
+            ..Default::default()
+        };
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.cache.contracts.insert(code_hash, code.clone());
+        db.insert_account_info(test_address, account_info.clone());
+        // Database that does not contain the code.
+        let mut other_db = CacheDB::new(EmptyDB::default());
+        other_db.insert_account_info(test_address, account_info);
+
+        let cache = CodeCache::new(16);
+        let mut journal = JournalInner::<JournalEntry>::new();
+        journal.set_code_cache(cache.clone());
+        let loaded = journal
+            .load_code(&mut db, test_address)
+            .unwrap()
+            .data
+            .info
+            .code
+            .clone();
+        assert_eq!(loaded, Some(code.clone()));
+
+        let mut other_journal = JournalInner::<JournalEntry>::new();
+        other_journal.set_code_cache(cache.clone());
+        let cached = other_journal
+            .load_code(&mut other_db, test_address)
+            .unwrap()
+            .data
+            .info
+            .code
+            .clone();
+        assert_eq!(cached, Some(code));
+
+        let metrics = cache.metrics();
+        assert_eq!((metrics.hits, metrics.misses, metrics.inserts), (1, 1, 1));
+    }
 }
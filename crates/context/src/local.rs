@@ -1,5 +1,5 @@
 //! Local context that is filled by execution.
-use context_interface::LocalContextTr;
+use context_interface::{fee::FeeTransfer, LocalContextTr};
 use core::cell::RefCell;
 use std::{rc::Rc, string::String, vec::Vec};
 
@@ -10,6 +10,8 @@ pub struct LocalContext {
     pub shared_memory_buffer: Rc<RefCell<Vec<u8>>>,
     /// Optional precompile error message to bubble up.
     pub precompile_error_message: Option<String>,
+    /// Fee transfers of the last transaction.
+    pub fee_transfers: Vec<FeeTransfer>,
 }
 
 impl Default for LocalContext {
@@ -17,6 +19,7 @@ impl Default for LocalContext {
         Self {
             shared_memory_buffer: Rc::new(RefCell::new(Vec::with_capacity(1024 * 4))),
             precompile_error_message: None,
+            fee_transfers: Vec::new(),
         }
     }
 }
@@ -39,6 +42,23 @@ impl LocalContextTr for LocalContext {
     fn take_precompile_error_context(&mut self) -> Option<String> {
         self.precompile_error_message.take()
     }
+
+    fn record_fee_transfer(&mut self, transfer: FeeTransfer) {
+        self.fee_transfers.push(transfer);
+    }
+
+    fn fee_transfers(&self) -> &[FeeTransfer] {
+        &self.fee_transfers
+    }
+
+    fn take_fee_transfers(&mut self) -> Vec<FeeTransfer> {
+        core::mem::take(&mut self.fee_transfers)
+    }
+
+    fn clear_fee_transfers(&mut self) {
+        // Keeps the capacity for the transfers of the next transaction.
+        self.fee_transfers.clear();
+    }
 }
 
 impl LocalContext {
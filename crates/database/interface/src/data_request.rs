@@ -0,0 +1,97 @@
+//! Data requested by a database that does not have it.
+use crate::{DBErrorMarker, Database, DatabaseRef};
+use core::fmt;
+use primitives::{Address, StorageKey, StorageValue, B256};
+use state::{AccountInfo, Bytecode};
+
+/// Data that is missing in the database and needs to be fetched before the execution continues.
+///
+/// It is used as a database error by databases that do not block on missing data. Execution
+/// that supports suspension returns the request instead of failing, see
+/// [`DBErrorMarker::data_request`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataRequest {
+    /// Basic account information.
+    Basic(Address),
+    /// Code by its hash.
+    CodeByHash(B256),
+    /// Storage slot of the account.
+    Storage(Address, StorageKey),
+    /// Hash of the block with the given number.
+    BlockHash(u64),
+}
+
+impl fmt::Display for DataRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic(address) => write!(f, "missing account {address}"),
+            Self::CodeByHash(hash) => write!(f, "missing code {hash}"),
+            Self::Storage(address, key) => write!(f, "missing storage {key} of {address}"),
+            Self::BlockHash(number) => write!(f, "missing block hash {number}"),
+        }
+    }
+}
+
+impl core::error::Error for DataRequest {}
+
+impl DBErrorMarker for DataRequest {
+    fn data_request(&self) -> Option<DataRequest> {
+        Some(*self)
+    }
+}
+
+/// Database without any data that returns a [`DataRequest`] for every query.
+///
+/// It is meant to be wrapped in a caching database. Fetched data is inserted into the cache and
+/// execution is resumed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MissingDataDB;
+
+impl DatabaseRef for MissingDataDB {
+    type Error = DataRequest;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Err(DataRequest::Basic(address))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Err(DataRequest::CodeByHash(code_hash))
+    }
+
+    fn storage_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        Err(DataRequest::Storage(address, index))
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        Err(DataRequest::BlockHash(number))
+    }
+}
+
+impl Database for MissingDataDB {
+    type Error = DataRequest;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
@@ -30,6 +30,7 @@ pub const BENCH_CALLER_BALANCE: U256 = TEST_BALANCE;
 #[cfg(feature = "asyncdb")]
 pub mod async_db;
 pub mod bal;
+pub mod data_request;
 pub mod either;
 pub mod empty_db;
 pub mod erased_error;
@@ -37,12 +38,20 @@ pub mod try_commit;
 
 #[cfg(feature = "asyncdb")]
 pub use async_db::{DatabaseAsync, WrapDatabaseAsync};
+pub use data_request::{DataRequest, MissingDataDB};
 pub use empty_db::{EmptyDB, EmptyDBTyped};
 pub use erased_error::ErasedError;
 pub use try_commit::{ArcUpgradeError, TryDatabaseCommit};
 
 /// Database error marker is needed to implement From conversion for Error type.
-pub trait DBErrorMarker: core::error::Error + Send + Sync + 'static {}
+pub trait DBErrorMarker: core::error::Error + Send + Sync + 'static {
+    /// Returns the missing data if the error is caused by it.
+    ///
+    /// Execution that supports suspension returns the request instead of failing.
+    fn data_request(&self) -> Option<DataRequest> {
+        None
+    }
+}
 
 /// Implement marker for `()`.
 impl DBErrorMarker for Infallible {}
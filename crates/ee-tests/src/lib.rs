@@ -127,6 +127,9 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod native_aa_tests;
+
 #[cfg(test)]
 mod op_revm_tests;
 
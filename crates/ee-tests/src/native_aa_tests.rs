@@ -0,0 +1,364 @@
+//! Integration tests for the `revm-native-aa` crate.
+
+use native_aa::{
+    constants::{
+        AA_NONCE_MANAGER, POST_PAYMASTER_TRANSACTION_SELECTOR,
+        VALIDATE_PAYMASTER_TRANSACTION_SELECTOR, VALIDATE_TRANSACTION_SELECTOR,
+    },
+    nonce::nonce_slot,
+    AaBuilder, AaTransaction, AaTransactionError, AaTransactionParts, DefaultAa,
+};
+use revm::{
+    bytecode::opcode,
+    context::{
+        result::{EVMError, ExecutionResult, InvalidTransaction, ResultAndState},
+        TxEnv,
+    },
+    database::{CacheDB, EmptyDB},
+    primitives::{address, Address, Bytes, B256, U256},
+    state::{AccountInfo, Bytecode},
+    Context, ExecuteEvm,
+};
+use std::{convert::Infallible, vec::Vec};
+
+const SENDER: Address = address!("0x1000000000000000000000000000000000000001");
+const PAYMASTER: Address = address!("0x2000000000000000000000000000000000000002");
+const DEPLOYER: Address = address!("0x3000000000000000000000000000000000000003");
+const BALANCE: u64 = 1_000_000_000;
+const GAS_PRICE: u128 = 2;
+
+/// Code that runs the code of the branch whose selector starts the calldata, `fallback` if none.
+fn dispatch(branches: &[([u8; 4], Vec<u8>)], fallback: &[u8]) -> Vec<u8> {
+    // Every branch check is 16 bytes long.
+    let mut dest = branches.len() * 16 + fallback.len();
+    let mut code = Vec::new();
+    for (selector, branch) in branches {
+        code.extend_from_slice(&[
+            opcode::PUSH1,
+            0,
+            opcode::CALLDATALOAD,
+            opcode::PUSH1,
+            0xe0,
+            opcode::SHR,
+            opcode::PUSH4,
+        ]);
+        code.extend_from_slice(selector);
+        code.extend_from_slice(&[opcode::EQ, opcode::PUSH2]);
+        code.extend_from_slice(&(dest as u16).to_be_bytes());
+        code.push(opcode::JUMPI);
+        dest += 1 + branch.len();
+    }
+    code.extend_from_slice(fallback);
+    for (_, branch) in branches {
+        code.push(opcode::JUMPDEST);
+        code.extend_from_slice(branch);
+    }
+    code
+}
+
+/// Code that stores the 32 bytes words in memory and returns them.
+fn return_words(words: &[[u8; 32]]) -> Vec<u8> {
+    let mut code = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        code.push(opcode::PUSH32);
+        code.extend_from_slice(word);
+        code.extend_from_slice(&[opcode::PUSH1, (i * 32) as u8, opcode::MSTORE]);
+    }
+    code.extend_from_slice(&[
+        opcode::PUSH1,
+        (words.len() * 32) as u8,
+        opcode::PUSH1,
+        0,
+        opcode::RETURN,
+    ]);
+    code
+}
+
+fn magic_word(selector: [u8; 4]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[..4].copy_from_slice(&selector);
+    word
+}
+
+fn u256_word(value: u64) -> [u8; 32] {
+    U256::from(value).to_be_bytes()
+}
+
+/// Account that accepts every transaction if `accept` and stores 1 in slot 0 when executed.
+fn account_code(accept: bool) -> Bytes {
+    let magic = if accept {
+        VALIDATE_TRANSACTION_SELECTOR
+    } else {
+        [0u8; 4]
+    };
+    let execute = [
+        opcode::PUSH1,
+        1,
+        opcode::PUSH1,
+        0,
+        opcode::SSTORE,
+        opcode::STOP,
+    ];
+    dispatch(
+        &[(
+            VALIDATE_TRANSACTION_SELECTOR,
+            return_words(&[magic_word(magic)]),
+        )],
+        &execute,
+    )
+    .into()
+}
+
+/// Paymaster that accepts every transaction with a one byte context, its post-operation stops or
+/// reverts.
+fn paymaster_code(post_op_reverts: bool) -> Bytes {
+    let mut context = [0u8; 32];
+    context[0] = 1;
+    let validation = return_words(&[
+        magic_word(VALIDATE_PAYMASTER_TRANSACTION_SELECTOR),
+        u256_word(64),
+        u256_word(1),
+        context,
+    ]);
+    let post_op = if post_op_reverts {
+        vec![opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::REVERT]
+    } else {
+        vec![opcode::STOP]
+    };
+    dispatch(
+        &[
+            (VALIDATE_PAYMASTER_TRANSACTION_SELECTOR, validation),
+            (POST_PAYMASTER_TRANSACTION_SELECTOR, post_op),
+        ],
+        &[opcode::INVALID],
+    )
+    .into()
+}
+
+/// Factory that creates an account with [`account_code`].
+fn deployer_code() -> Bytes {
+    let runtime = account_code(true);
+    let mut init = vec![
+        opcode::PUSH1,
+        runtime.len() as u8,
+        opcode::DUP1,
+        opcode::PUSH1,
+        11,
+        opcode::PUSH1,
+        0,
+        opcode::CODECOPY,
+        opcode::PUSH1,
+        0,
+        opcode::RETURN,
+    ];
+    init.extend_from_slice(&runtime);
+    let mut code = vec![
+        opcode::PUSH1,
+        init.len() as u8,
+        opcode::PUSH1,
+        16,
+        opcode::PUSH1,
+        0,
+        opcode::CODECOPY,
+        opcode::PUSH1,
+        init.len() as u8,
+        opcode::PUSH1,
+        0,
+        opcode::PUSH1,
+        0,
+        opcode::CREATE,
+        opcode::POP,
+        opcode::STOP,
+    ];
+    code.extend_from_slice(&init);
+    code.into()
+}
+
+fn db_with(accounts: &[(Address, Bytes)]) -> CacheDB<EmptyDB> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    for (address, code) in accounts {
+        let mut info = AccountInfo::from_balance(U256::from(BALANCE));
+        if !code.is_empty() {
+            info = info.with_code(Bytecode::new_legacy(code.clone()));
+        }
+        db.insert_account_info(*address, info);
+    }
+    db
+}
+
+fn aa_tx(sender: Address, call_gas_limit: u64, parts: AaTransactionParts) -> AaTransaction<TxEnv> {
+    AaTransaction::new_aa(
+        TxEnv::builder()
+            .caller(sender)
+            .gas_limit(call_gas_limit)
+            .gas_price(GAS_PRICE)
+            .data(Bytes::from_static(&[0x01]))
+            .build_fill(),
+        AaTransactionParts {
+            validation_gas_limit: 200_000,
+            tx_hash: B256::repeat_byte(0xaa),
+            encoded_tx: Bytes::from_static(&[0x05, 0x01, 0x02]),
+            ..parts
+        },
+    )
+}
+
+fn paymaster_parts() -> AaTransactionParts {
+    AaTransactionParts {
+        paymaster: Some(PAYMASTER),
+        paymaster_validation_gas_limit: 50_000,
+        post_op_gas_limit: 30_000,
+        ..Default::default()
+    }
+}
+
+fn replay(
+    db: CacheDB<EmptyDB>,
+    tx: AaTransaction<TxEnv>,
+) -> Result<ResultAndState, EVMError<Infallible, AaTransactionError>> {
+    Context::aa().with_db(db).with_tx(tx).build_aa().replay()
+}
+
+fn slot(output: &ResultAndState, address: Address, slot: U256) -> U256 {
+    output.state[&address]
+        .storage
+        .get(&slot)
+        .map(|slot| slot.present_value)
+        .unwrap_or_default()
+}
+
+fn fee(result: &ExecutionResult) -> U256 {
+    U256::from(result.gas_used() as u128 * GAS_PRICE)
+}
+
+#[test]
+fn test_aa_tx_deployed_account() {
+    let db = db_with(&[(SENDER, account_code(true))]);
+    let output = replay(db, aa_tx(SENDER, 50_000, AaTransactionParts::default())).unwrap();
+
+    assert!(output.result.is_success());
+    assert_eq!(slot(&output, SENDER, U256::ZERO), U256::from(1));
+    let sender = &output.state[&SENDER].info;
+    assert_eq!(sender.nonce, 1);
+    assert_eq!(sender.balance, U256::from(BALANCE) - fee(&output.result));
+}
+
+#[test]
+fn test_aa_tx_nonce_key() {
+    let db = db_with(&[(SENDER, account_code(true))]);
+    let parts = AaTransactionParts {
+        nonce_key: U256::from(7),
+        ..Default::default()
+    };
+    let output = replay(db.clone(), aa_tx(SENDER, 50_000, parts.clone())).unwrap();
+
+    assert!(output.result.is_success());
+    assert_eq!(output.state[&SENDER].info.nonce, 0);
+    assert_eq!(
+        slot(&output, AA_NONCE_MANAGER, nonce_slot(SENDER, U256::from(7))),
+        U256::from(1)
+    );
+
+    // Sequence of an unused key is zero.
+    let mut tx = aa_tx(SENDER, 50_000, parts);
+    tx.base.nonce = 1;
+    assert!(matches!(
+        replay(db, tx),
+        Err(EVMError::Transaction(AaTransactionError::Base(
+            InvalidTransaction::NonceTooHigh { tx: 1, state: 0 }
+        )))
+    ));
+}
+
+#[test]
+fn test_aa_tx_deployment() {
+    let sender = DEPLOYER.create(0);
+    // Sender has no code yet, only the balance to pay for the gas.
+    let db = db_with(&[(DEPLOYER, deployer_code()), (sender, Bytes::new())]);
+    let parts = AaTransactionParts {
+        deployer: Some(DEPLOYER),
+        nonce_key: U256::from(1),
+        ..Default::default()
+    };
+    let output = replay(db, aa_tx(sender, 50_000, parts.clone())).unwrap();
+
+    assert!(output.result.is_success());
+    assert!(!output.state[&sender].info.is_empty_code_hash());
+    assert_eq!(slot(&output, sender, U256::ZERO), U256::from(1));
+
+    // Deployer can't be used once the sender is deployed.
+    let db = db_with(&[(DEPLOYER, deployer_code()), (sender, account_code(true))]);
+    assert_eq!(
+        replay(db, aa_tx(sender, 50_000, parts)).unwrap_err(),
+        EVMError::Transaction(AaTransactionError::SenderAlreadyDeployed)
+    );
+}
+
+#[test]
+fn test_aa_tx_account_rejected() {
+    let db = db_with(&[(SENDER, account_code(false))]);
+    assert_eq!(
+        replay(db, aa_tx(SENDER, 50_000, AaTransactionParts::default())).unwrap_err(),
+        EVMError::Transaction(AaTransactionError::AccountRejected)
+    );
+}
+
+#[test]
+fn test_aa_tx_sender_not_deployed() {
+    let db = db_with(&[(SENDER, Bytes::new())]);
+    assert_eq!(
+        replay(db, aa_tx(SENDER, 50_000, AaTransactionParts::default())).unwrap_err(),
+        EVMError::Transaction(AaTransactionError::SenderNotDeployed)
+    );
+}
+
+#[test]
+fn test_aa_tx_paymaster() {
+    let db = db_with(&[
+        (SENDER, account_code(true)),
+        (PAYMASTER, paymaster_code(false)),
+    ]);
+    let output = replay(db, aa_tx(SENDER, 50_000, paymaster_parts())).unwrap();
+
+    assert!(output.result.is_success());
+    assert_eq!(slot(&output, SENDER, U256::ZERO), U256::from(1));
+    assert_eq!(output.state[&SENDER].info.balance, U256::from(BALANCE));
+    assert_eq!(
+        output.state[&PAYMASTER].info.balance,
+        U256::from(BALANCE) - fee(&output.result)
+    );
+}
+
+#[test]
+fn test_aa_tx_post_op_revert() {
+    let db = db_with(&[
+        (SENDER, account_code(true)),
+        (PAYMASTER, paymaster_code(true)),
+    ]);
+    let output = replay(db, aa_tx(SENDER, 50_000, paymaster_parts())).unwrap();
+
+    // Reverted post-operation reverts the execution, the paymaster still pays.
+    assert!(matches!(output.result, ExecutionResult::Revert { .. }));
+    assert_eq!(slot(&output, SENDER, U256::ZERO), U256::ZERO);
+    assert_eq!(
+        output.state[&PAYMASTER].info.balance,
+        U256::from(BALANCE) - fee(&output.result)
+    );
+}
+
+#[test]
+fn test_aa_tx_unused_gas_penalty() {
+    let gas_used = |call_gas_limit| {
+        let db = db_with(&[(SENDER, account_code(true))]);
+        replay(
+            db,
+            aa_tx(SENDER, call_gas_limit, AaTransactionParts::default()),
+        )
+        .unwrap()
+        .result
+        .gas_used()
+    };
+
+    // Ten percent of the unused execution gas is charged.
+    assert_eq!(gas_used(60_000) - gas_used(50_000), 1_000);
+}
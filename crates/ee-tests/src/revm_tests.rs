@@ -3,9 +3,14 @@
 use crate::TestdataConfig;
 use revm::{
     bytecode::opcode,
-    context::{ContextTr, TxEnv},
-    database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET},
-    primitives::{address, b256, hardfork::SpecId, Bytes, TxKind, KECCAK_EMPTY, U256},
+    context::{
+        result::{ExecutionResult, HaltReason},
+        CfgEnv, ContextTr, GasMode, TxEnv,
+    },
+    context_interface::{FeeKind, FeePolicy, FeeRecipient, FeeSplit, FeeTransfer, LocalContextTr},
+    database::{BenchmarkDB, BENCH_CALLER, BENCH_CALLER_BALANCE, BENCH_TARGET},
+    handler::{MainnetContext, MainnetEvm},
+    primitives::{address, b256, hardfork::SpecId, Address, Bytes, TxKind, KECCAK_EMPTY, U256},
     state::{AccountStatus, Bytecode},
     Context, ExecuteEvm, MainBuilder, MainContext,
 };
@@ -283,3 +288,350 @@ fn test_disable_balance_check() {
     let expected_balance = U256::ZERO;
     assert_eq!(returned_balance, expected_balance);
 }
+
+#[test]
+fn test_basic_block_execution_matches_per_instruction() {
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        primitives::Address,
+        state::AccountInfo,
+    };
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const TARGET: Address = address!("0x2000000000000000000000000000000000000002");
+    const CHILD: Address = address!("0x3000000000000000000000000000000000000003");
+
+    // Loops ten times, static calls the child and stores the call result in slot zero.
+    let mut target = vec![
+        opcode::PUSH1,
+        0x0a,
+        opcode::JUMPDEST,
+        opcode::PUSH1,
+        0x01,
+        opcode::SWAP1,
+        opcode::SUB,
+        opcode::DUP1,
+        opcode::PUSH1,
+        0x02,
+        opcode::JUMPI,
+        opcode::PUSH0,
+        opcode::PUSH0,
+        opcode::PUSH0,
+        opcode::PUSH0,
+        opcode::PUSH20,
+    ];
+    target.extend_from_slice(CHILD.as_slice());
+    target.extend_from_slice(&[
+        opcode::GAS,
+        opcode::STATICCALL,
+        opcode::PUSH0,
+        opcode::SSTORE,
+        opcode::STOP,
+    ]);
+    // TSTORE halts in the middle of the block of a static call.
+    let child = [
+        opcode::PUSH1,
+        0x01,
+        opcode::PUSH0,
+        opcode::TSTORE,
+        opcode::PUSH1,
+        0x01,
+        opcode::STOP,
+    ];
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(CALLER, AccountInfo::from_balance(U256::from(1_000_000_000)));
+    db.insert_account_info(
+        TARGET,
+        AccountInfo::default().with_code(Bytecode::new_legacy(target.into())),
+    );
+    db.insert_account_info(
+        CHILD,
+        AccountInfo::default().with_code(Bytecode::new_legacy(Bytes::from_static(&child))),
+    );
+
+    let run = |gas_limit: u64, basic_blocks: bool| {
+        let mut evm = Context::mainnet()
+            .modify_cfg_chained(|cfg| cfg.basic_block_execution = basic_blocks)
+            .with_db(db.clone())
+            .build_mainnet();
+        evm.transact(
+            TxEnv::builder()
+                .caller(CALLER)
+                .call(TARGET)
+                .gas_limit(gas_limit)
+                .build_fill(),
+        )
+        .unwrap()
+    };
+
+    let output = run(100_000, true);
+    assert!(output.result.is_success());
+    assert_eq!(
+        output.state[&TARGET].storage[&U256::ZERO].present_value,
+        U256::ZERO
+    );
+    // Covers running out of gas in every block of the caller.
+    for gas_limit in (21_000..21_000 + 300).chain([30_000, 100_000]) {
+        let with_blocks = run(gas_limit, true);
+        let without_blocks = run(gas_limit, false);
+        assert_eq!(
+            with_blocks.result, without_blocks.result,
+            "gas limit {gas_limit}"
+        );
+        assert_eq!(
+            with_blocks.state, without_blocks.state,
+            "gas limit {gas_limit}"
+        );
+    }
+}
+
+/// Builds a mainnet EVM with `cfg` whose bench target runs `code`.
+fn bytecode_evm(cfg: CfgEnv, code: &[u8]) -> MainnetEvm<MainnetContext<BenchmarkDB>> {
+    Context::mainnet()
+        .with_cfg(cfg)
+        .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(
+            code.to_vec().into(),
+        )))
+        .build_mainnet()
+}
+
+#[test]
+fn test_configured_stack_and_call_depth_limits() {
+    let transact = |cfg: CfgEnv, code: &[u8]| {
+        bytecode_evm(cfg, code)
+            .transact(TxEnv::builder_for_bench().gas_limit(100_000).build_fill())
+            .unwrap()
+    };
+
+    let push3 = [
+        opcode::PUSH1,
+        0x01,
+        opcode::PUSH1,
+        0x01,
+        opcode::PUSH1,
+        0x01,
+    ];
+    assert!(transact(CfgEnv::default(), &push3).result.is_success());
+    assert_eq!(
+        transact(CfgEnv::default().with_max_stack_size(2), &push3).result,
+        ExecutionResult::Halt {
+            reason: HaltReason::StackOverflow,
+            gas_used: 100_000,
+        }
+    );
+    // Stacks above the default limit grow on demand.
+    assert!(
+        transact(CfgEnv::default().with_max_stack_size(usize::MAX), &push3)
+            .result
+            .is_success()
+    );
+
+    // Stores the result of a call to the identity precompile in slot 0.
+    let call = [
+        opcode::PUSH1,
+        0x00,
+        opcode::DUP1,
+        opcode::DUP1,
+        opcode::DUP1,
+        opcode::DUP1,
+        opcode::PUSH1,
+        0x04,
+        opcode::GAS,
+        opcode::CALL,
+        opcode::PUSH1,
+        0x00,
+        opcode::SSTORE,
+    ];
+    let call_result =
+        |cfg| transact(cfg, &call).state[&BENCH_TARGET].storage[&U256::ZERO].present_value;
+    assert_eq!(call_result(CfgEnv::default()), U256::from(1));
+    assert_eq!(
+        call_result(CfgEnv::default().with_max_call_depth(0)),
+        U256::ZERO
+    );
+}
+
+#[test]
+fn test_gas_modes() {
+    let code = [
+        opcode::PUSH1,
+        0x01,
+        opcode::PUSH1,
+        0x00,
+        opcode::SSTORE,
+        opcode::PUSH1,
+        0x01,
+        opcode::DUP1,
+        opcode::SSTORE,
+    ];
+    let transact = |gas_mode, gas_limit| {
+        bytecode_evm(CfgEnv::default().with_gas_mode(gas_mode), &code)
+            .transact(
+                TxEnv::builder_for_bench()
+                    .gas_limit(gas_limit)
+                    .gas_price(1)
+                    .build_fill(),
+            )
+            .unwrap()
+    };
+
+    let metered = transact(GasMode::Metered, 1_000_000).result;
+    assert!(metered.is_success());
+    assert!(metered.gas_used() > 30_000);
+    assert!(transact(GasMode::Metered, 30_000).result.is_halt());
+
+    // The overrun is reported in the gas used but only the gas limit is paid for.
+    let tracked = transact(GasMode::Tracked, 30_000);
+    assert!(tracked.result.is_success());
+    assert_eq!(tracked.result.gas_used(), metered.gas_used());
+    assert_eq!(
+        tracked.state[&BENCH_CALLER].info.balance,
+        BENCH_CALLER_BALANCE - U256::from(30_000)
+    );
+    assert_eq!(
+        tracked.state[&Address::ZERO].info.balance,
+        U256::from(30_000)
+    );
+
+    assert!(transact(GasMode::Unmetered, 30_000).result.is_success());
+}
+
+#[test]
+fn test_gas_modes_with_call() {
+    // Sets slot 0, calls itself once and stores the call result in slot 1.
+    let code = [
+        opcode::PUSH1,
+        0x00,
+        opcode::SLOAD,
+        opcode::ISZERO,
+        opcode::PUSH1,
+        0x08,
+        opcode::JUMPI,
+        opcode::STOP,
+        opcode::JUMPDEST,
+        opcode::PUSH1,
+        0x01,
+        opcode::PUSH1,
+        0x00,
+        opcode::SSTORE,
+        opcode::PUSH1,
+        0x00,
+        opcode::DUP1,
+        opcode::DUP1,
+        opcode::DUP1,
+        opcode::DUP1,
+        opcode::ADDRESS,
+        opcode::GAS,
+        opcode::CALL,
+        opcode::PUSH1,
+        0x01,
+        opcode::SSTORE,
+    ];
+    let transact = |gas_mode, gas_limit| {
+        bytecode_evm(CfgEnv::default().with_gas_mode(gas_mode), &code)
+            .transact(TxEnv::builder_for_bench().gas_limit(gas_limit).build_fill())
+            .unwrap()
+    };
+    let call_result = |gas_mode, gas_limit| {
+        let output = transact(gas_mode, gas_limit);
+        assert!(output.result.is_success());
+        let storage = &output.state[&BENCH_TARGET].storage;
+        assert_eq!(storage[&U256::ZERO].present_value, U256::from(1));
+        (
+            output.result.gas_used(),
+            storage[&U256::from(1)].present_value,
+        )
+    };
+
+    let (metered_gas_used, metered_call) = call_result(GasMode::Metered, 1_000_000);
+    assert_eq!(metered_call, U256::from(1));
+    assert!(transact(GasMode::Metered, 30_000).result.is_halt());
+
+    // The child call gets no gas but does not run out of it.
+    let (tracked_gas_used, tracked_call) = call_result(GasMode::Tracked, 30_000);
+    assert!(tracked_gas_used > 30_000);
+    assert_eq!(tracked_gas_used, metered_gas_used);
+    assert_eq!(tracked_call, U256::from(1));
+
+    let (_, unmetered_call) = call_result(GasMode::Unmetered, 30_000);
+    assert_eq!(unmetered_call, U256::from(1));
+}
+
+#[test]
+fn test_configured_fee_policy() {
+    let treasury = address!("0x0000000000000000000000000000000000007ea5");
+    let policy = FeePolicy::mainnet().with_split(
+        FeeKind::BaseFee,
+        FeeSplit::burn()
+            .with_share(FeeRecipient::Address(treasury), 50)
+            .unwrap(),
+    );
+    let mut evm = bytecode_evm(CfgEnv::default().with_fee_policy(policy), &[opcode::STOP]);
+    evm.ctx.modify_block(|block| block.basefee = 10);
+    let output = evm
+        .transact(
+            TxEnv::builder_for_bench()
+                .gas_limit(100_000)
+                .gas_price(20)
+                .build_fill(),
+        )
+        .unwrap();
+
+    let gas_used = U256::from(output.result.gas_used());
+    assert_eq!(
+        evm.ctx.local.fee_transfers(),
+        [
+            FeeTransfer {
+                kind: FeeKind::BaseFee,
+                to: Some(treasury),
+                amount: gas_used * U256::from(5),
+            },
+            FeeTransfer {
+                kind: FeeKind::BaseFee,
+                to: None,
+                amount: gas_used * U256::from(5),
+            },
+            FeeTransfer {
+                kind: FeeKind::PriorityFee,
+                to: Some(Address::ZERO),
+                amount: gas_used * U256::from(10),
+            },
+        ]
+    );
+    assert_eq!(
+        output.state[&treasury].info.balance,
+        gas_used * U256::from(5)
+    );
+}
+
+#[test]
+fn test_default_fee_transfers() {
+    let mut evm = bytecode_evm(CfgEnv::default(), &[opcode::STOP]);
+    evm.ctx.modify_block(|block| block.basefee = 10);
+    let output = evm
+        .transact(
+            TxEnv::builder_for_bench()
+                .gas_limit(100_000)
+                .gas_price(20)
+                .build_fill(),
+        )
+        .unwrap();
+
+    let gas_used = U256::from(output.result.gas_used());
+    assert_eq!(
+        evm.ctx.local.fee_transfers(),
+        [
+            FeeTransfer {
+                kind: FeeKind::BaseFee,
+                to: None,
+                amount: gas_used * U256::from(10),
+            },
+            FeeTransfer {
+                kind: FeeKind::PriorityFee,
+                to: Some(Address::ZERO),
+                amount: gas_used * U256::from(10),
+            },
+        ]
+    );
+}
@@ -0,0 +1,441 @@
+//! Extension point for executing bytecode compiled ahead of time.
+//!
+//! A [`CompiledBackend`] supplies a [`CompiledCode`] for a bytecode hash. It is called by
+//! [`EvmTr::frame_run`](crate::EvmTr::frame_run) through [`run_compiled`] and runs against the
+//! same interpreter state and host. Compiled code does not need to cover the whole bytecode,
+//! instructions that it does not handle are executed by the interpreter until the next
+//! `JUMPDEST`, where the compiled code is entered again.
+use bytecode::{opcode, Bytecode};
+use core::fmt;
+use interpreter::{
+    instructions::is_default_instruction,
+    interpreter_types::{InterpreterTypes, Jumps, LoopControl, StackTr},
+    Host, InstructionResult, InstructionTable, Interpreter, InterpreterAction,
+};
+use primitives::{HashMap, B256, U256};
+use std::{boxed::Box, vec::Vec};
+
+/// Code compiled ahead of time for a bytecode.
+///
+/// It is called with the interpreter positioned at its current program counter, which is the
+/// start of the bytecode, the instruction after a call or create returned or a `JUMPDEST`. It
+/// executes instructions in the same way as the interpreter and returns either after setting the
+/// interpreter action or when it reaches an instruction it does not handle. In the latter case
+/// the interpreter continues the execution from that instruction, see [`run_compiled`].
+pub type CompiledCode<WIRE, HOST> = Box<dyn Fn(&mut Interpreter<WIRE>, &mut HOST) + Send + Sync>;
+
+/// Backend that supplies compiled code for bytecode.
+pub trait CompiledBackend<WIRE: InterpreterTypes, HOST: ?Sized>: fmt::Debug + Send + Sync {
+    /// Returns the compiled code for the bytecode with the given hash.
+    fn compiled_code(&self, code_hash: &B256) -> Option<&CompiledCode<WIRE, HOST>>;
+
+    /// Returns `true` if the compiled code implements the instructions of the table.
+    ///
+    /// Compiled code is not executed while the instruction table of the interpreter is not
+    /// supported, for example after an instruction it implements was customised.
+    fn supports_instruction_table(&self, instruction_table: &InstructionTable<WIRE, HOST>) -> bool
+    where
+        HOST: Host + Sized,
+    {
+        let _ = instruction_table;
+        true
+    }
+}
+
+/// Executes the interpreter with compiled code until it returns or stops.
+///
+/// Compiled code is entered at the current program counter and again every time the interpreter
+/// reaches a `JUMPDEST`, the interpreter executes the instructions in between.
+pub fn run_compiled<WIRE: InterpreterTypes, HOST: Host + ?Sized>(
+    interpreter: &mut Interpreter<WIRE>,
+    compiled: &CompiledCode<WIRE, HOST>,
+    instruction_table: &InstructionTable<WIRE, HOST>,
+    host: &mut HOST,
+) -> InterpreterAction {
+    loop {
+        compiled(interpreter, host);
+        loop {
+            if !interpreter.bytecode.is_not_end() {
+                return interpreter.take_next_action();
+            }
+            interpreter.step(instruction_table, host);
+            if interpreter.bytecode.is_not_end()
+                && interpreter.bytecode.opcode() == opcode::JUMPDEST
+            {
+                break;
+            }
+        }
+    }
+}
+
+/// Single compiled instruction.
+type CompiledInstruction<WIRE, HOST> = Box<dyn Fn(&mut Interpreter<WIRE>, &mut HOST) + Send + Sync>;
+
+/// Reference [`CompiledBackend`] that compiles a handful of opcodes to closures.
+///
+/// Compiled opcodes are `STOP`, `ADD`, `MUL`, `SUB`, `POP`, `JUMPDEST`, `PUSH1`-`PUSH32`,
+/// `DUP1`-`DUP16` and `SWAP1`-`SWAP16`. Straight-line runs of them are compiled for the start
+/// of the bytecode and for every `JUMPDEST`, everything else is left to the interpreter.
+pub struct ClosureBackend<WIRE: InterpreterTypes, HOST: ?Sized> {
+    compiled: HashMap<B256, CompiledCode<WIRE, HOST>>,
+}
+
+impl<WIRE: InterpreterTypes, HOST: ?Sized> fmt::Debug for ClosureBackend<WIRE, HOST> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClosureBackend")
+            .field("compiled", &self.compiled.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<WIRE: InterpreterTypes, HOST: ?Sized> Default for ClosureBackend<WIRE, HOST> {
+    fn default() -> Self {
+        Self {
+            compiled: HashMap::default(),
+        }
+    }
+}
+
+impl<WIRE: InterpreterTypes + 'static, HOST: ?Sized + 'static> ClosureBackend<WIRE, HOST> {
+    /// Creates an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compiles the legacy bytecode and returns its hash.
+    ///
+    /// `instruction_table` needs to be the table used by the interpreter. Compiled instructions
+    /// implement the default instructions and have no dynamic gas, so gas params do not apply
+    /// to them. Returns `None` if the bytecode is not legacy bytecode or if any compiled opcode
+    /// is customised in `instruction_table`.
+    pub fn compile(
+        &mut self,
+        bytecode: &Bytecode,
+        instruction_table: &InstructionTable<WIRE, HOST>,
+    ) -> Option<B256>
+    where
+        HOST: Host + Sized,
+    {
+        let Bytecode::LegacyAnalyzed(_) = bytecode else {
+            return None;
+        };
+        if !compiled_opcodes_are_default(instruction_table) {
+            return None;
+        }
+        let hash = bytecode.hash_slow();
+        let code = bytecode.original_byte_slice();
+        // Padded bytecode, so the immediates of a truncated PUSH are zero.
+        let padded = bytecode.bytecode();
+
+        // Compiled runs of instructions indexed by their starting program counter.
+        let mut runs: HashMap<usize, Vec<CompiledInstruction<WIRE, HOST>>> = HashMap::default();
+        let mut run = Vec::new();
+        let mut run_start = 0;
+        let mut pc = 0;
+        while pc < code.len() {
+            let op = code[pc];
+            if op == opcode::JUMPDEST && pc != run_start {
+                if !run.is_empty() {
+                    runs.insert(run_start, core::mem::take(&mut run));
+                }
+                run_start = pc;
+            }
+
+            let immediate_size = opcode::OpCode::new(op)
+                .map(|op| op.info().immediate_size() as usize)
+                .unwrap_or_default();
+            let static_gas = instruction_table[op as usize].static_gas();
+            match compile_instruction(op, &padded[pc + 1..pc + 1 + immediate_size], static_gas) {
+                Some(instruction) if run_start != usize::MAX => run.push(instruction),
+                Some(_) => {}
+                None => {
+                    if !run.is_empty() {
+                        runs.insert(run_start, core::mem::take(&mut run));
+                    }
+                    // Skip until the next `JUMPDEST`.
+                    run_start = usize::MAX;
+                }
+            }
+            pc += 1 + immediate_size;
+        }
+        if !run.is_empty() {
+            runs.insert(run_start, run);
+        }
+
+        let compiled = boxed(move |interpreter, host| {
+            while interpreter.bytecode.is_not_end() {
+                let Some(run) = runs.get(&interpreter.bytecode.pc()) else {
+                    return;
+                };
+                for instruction in run {
+                    instruction(interpreter, host);
+                    if !interpreter.bytecode.is_not_end() {
+                        return;
+                    }
+                }
+            }
+        });
+        self.compiled.insert(hash, compiled);
+        Some(hash)
+    }
+}
+
+impl<WIRE: InterpreterTypes, HOST: ?Sized> CompiledBackend<WIRE, HOST>
+    for ClosureBackend<WIRE, HOST>
+{
+    fn compiled_code(&self, code_hash: &B256) -> Option<&CompiledCode<WIRE, HOST>> {
+        self.compiled.get(code_hash)
+    }
+
+    fn supports_instruction_table(&self, instruction_table: &InstructionTable<WIRE, HOST>) -> bool
+    where
+        HOST: Host + Sized,
+    {
+        compiled_opcodes_are_default(instruction_table)
+    }
+}
+
+/// Returns `true` if no opcode compiled by [`ClosureBackend`] is customised in the table.
+fn compiled_opcodes_are_default<WIRE: InterpreterTypes, HOST: Host>(
+    instruction_table: &InstructionTable<WIRE, HOST>,
+) -> bool {
+    [opcode::STOP, opcode::ADD, opcode::MUL, opcode::SUB]
+        .into_iter()
+        .chain([opcode::POP, opcode::JUMPDEST])
+        .chain(opcode::PUSH1..=opcode::PUSH32)
+        .chain(opcode::DUP1..=opcode::DUP16)
+        .chain(opcode::SWAP1..=opcode::SWAP16)
+        .all(|op| is_default_instruction(instruction_table, op))
+}
+
+/// Moves past the opcode and charges its static gas. Returns `false` if out of gas.
+#[inline(always)]
+fn enter<WIRE: InterpreterTypes>(interpreter: &mut Interpreter<WIRE>, static_gas: u64) -> bool {
+    interpreter.bytecode.relative_jump(1);
+    if interpreter.gas.record_cost_unsafe(static_gas) {
+        interpreter.halt_oog();
+        return false;
+    }
+    true
+}
+
+/// Boxes the closure, helps the inference of its signature.
+#[inline]
+fn boxed<WIRE, HOST, F>(f: F) -> CompiledInstruction<WIRE, HOST>
+where
+    WIRE: InterpreterTypes,
+    HOST: ?Sized,
+    F: Fn(&mut Interpreter<WIRE>, &mut HOST) + Send + Sync + 'static,
+{
+    Box::new(f)
+}
+
+/// Compiles a single instruction. Returns `None` if the opcode is not supported.
+fn compile_instruction<WIRE: InterpreterTypes + 'static, HOST: ?Sized + 'static>(
+    op: u8,
+    immediate: &[u8],
+    static_gas: u64,
+) -> Option<CompiledInstruction<WIRE, HOST>> {
+    let instruction: CompiledInstruction<WIRE, HOST> = match op {
+        opcode::STOP => boxed(move |interpreter, _| {
+            if enter(interpreter, static_gas) {
+                interpreter.halt(InstructionResult::Stop);
+            }
+        }),
+        opcode::JUMPDEST => boxed(move |interpreter, _| {
+            enter(interpreter, static_gas);
+        }),
+        opcode::ADD | opcode::MUL | opcode::SUB => {
+            let f: fn(U256, U256) -> U256 = match op {
+                opcode::ADD => U256::wrapping_add,
+                opcode::MUL => U256::wrapping_mul,
+                _ => U256::wrapping_sub,
+            };
+            boxed(move |interpreter, _| {
+                if !enter(interpreter, static_gas) {
+                    return;
+                }
+                let Some(([a], b)) = interpreter.stack.popn_top::<1>() else {
+                    interpreter.halt_underflow();
+                    return;
+                };
+                *b = f(a, *b);
+            })
+        }
+        opcode::POP => boxed(move |interpreter, _| {
+            if enter(interpreter, static_gas) && interpreter.stack.pop().is_none() {
+                interpreter.halt_underflow();
+            }
+        }),
+        opcode::PUSH1..=opcode::PUSH32 => {
+            let value = U256::from_be_slice(immediate);
+            let len = immediate.len() as isize;
+            boxed(move |interpreter, _| {
+                if !enter(interpreter, static_gas) {
+                    return;
+                }
+                if !interpreter.stack.push(value) {
+                    interpreter.halt_overflow();
+                    return;
+                }
+                interpreter.bytecode.relative_jump(len);
+            })
+        }
+        opcode::DUP1..=opcode::DUP16 => {
+            let n = (op - opcode::DUP1 + 1) as usize;
+            boxed(move |interpreter, _| {
+                if enter(interpreter, static_gas) && !interpreter.stack.dup(n) {
+                    interpreter.halt_overflow();
+                }
+            })
+        }
+        opcode::SWAP1..=opcode::SWAP16 => {
+            let n = (op - opcode::SWAP1 + 1) as usize;
+            boxed(move |interpreter, _| {
+                if enter(interpreter, static_gas) && !interpreter.stack.exchange(0, n) {
+                    interpreter.halt_overflow();
+                }
+            })
+        }
+        _ => return None,
+    };
+    Some(instruction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use interpreter::{
+        gas::params::GasParams,
+        host::DummyHost,
+        instruction_table,
+        interpreter::{EthInterpreter, ExtBytecode},
+        InputsImpl, SharedMemory,
+    };
+    use primitives::{hardfork::SpecId, Bytes};
+
+    #[test]
+    fn compiled_matches_interpreter() {
+        let bytecode = Bytecode::new_raw(Bytes::from_static(&[
+            opcode::PUSH1,
+            0x02,
+            opcode::PUSH2,
+            0x01,
+            0x00,
+            opcode::DUP2,
+            opcode::MUL,
+            opcode::SWAP1,
+            opcode::POP,
+            opcode::PUSH1,
+            0x0e,
+            opcode::JUMP,
+            opcode::INVALID,
+            opcode::INVALID,
+            opcode::JUMPDEST, // 0x0e
+            opcode::PUSH1,
+            0x01,
+            opcode::SWAP1,
+            opcode::SUB,
+            opcode::PUSH1,
+            0x03,
+            opcode::ADD,
+            opcode::STOP,
+        ]));
+        let table = instruction_table::<EthInterpreter, DummyHost>();
+        let mut backend = ClosureBackend::new();
+        let hash = backend.compile(&bytecode, &table).unwrap();
+
+        let run = |gas_limit, compiled: bool| {
+            let mut interpreter = Interpreter::<EthInterpreter>::new(
+                SharedMemory::new(),
+                ExtBytecode::new(bytecode.clone()),
+                InputsImpl::default(),
+                false,
+                SpecId::default(),
+                gas_limit,
+                GasParams::default(),
+            );
+            let action = if compiled {
+                let compiled = backend.compiled_code(&hash).unwrap();
+                run_compiled(&mut interpreter, compiled, &table, &mut DummyHost)
+            } else {
+                interpreter.run_plain(&table, &mut DummyHost)
+            };
+            (
+                action.instruction_result(),
+                interpreter.gas.remaining(),
+                interpreter.stack.data().to_vec(),
+            )
+        };
+
+        assert_eq!(
+            run(u64::MAX, true).2,
+            [U256::from(0x200 + 2)],
+            "2 * 0x100 - 1 + 3"
+        );
+        for gas_limit in 0..80 {
+            assert_eq!(
+                run(gas_limit, true),
+                run(gas_limit, false),
+                "gas limit {gas_limit}"
+            );
+        }
+    }
+
+    #[test]
+    fn compiled_code_is_entered_at_jumpdest() {
+        // Loops three times through `JUMPDEST PUSH1 1 SWAP1 SUB DUP1 PUSH1 2 JUMPI`.
+        let bytecode = Bytecode::new_raw(Bytes::from_static(&[
+            opcode::PUSH1,
+            0x03,
+            opcode::JUMPDEST, // 0x02
+            opcode::PUSH1,
+            0x01,
+            opcode::SWAP1,
+            opcode::SUB,
+            opcode::DUP1,
+            opcode::PUSH1,
+            0x02,
+            opcode::JUMPI,
+            opcode::STOP,
+        ]));
+        let table = instruction_table::<EthInterpreter, DummyHost>();
+        let mut backend = ClosureBackend::new();
+        let hash = backend.compile(&bytecode, &table).unwrap();
+        let inner = backend.compiled.remove(&hash).unwrap();
+
+        let entries = std::sync::Arc::new(core::sync::atomic::AtomicUsize::new(0));
+        let counter = entries.clone();
+        let compiled: CompiledCode<EthInterpreter, DummyHost> = boxed(move |interpreter, host| {
+            counter.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            inner(interpreter, host)
+        });
+
+        let mut interpreter = Interpreter::<EthInterpreter>::new(
+            SharedMemory::new(),
+            ExtBytecode::new(bytecode),
+            InputsImpl::default(),
+            false,
+            SpecId::default(),
+            u64::MAX,
+            GasParams::default(),
+        );
+        let action = run_compiled(&mut interpreter, &compiled, &table, &mut DummyHost);
+        assert_eq!(action.instruction_result(), Some(InstructionResult::Stop));
+        assert_eq!(interpreter.stack.data(), &[U256::ZERO]);
+        // Entered at the start and after each of the two taken jumps.
+        assert_eq!(entries.load(core::sync::atomic::Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn compile_refuses_customised_table() {
+        let bytecode = Bytecode::new_raw(Bytes::from_static(&[opcode::PUSH1, 0x01, opcode::STOP]));
+        let mut table = instruction_table::<EthInterpreter, DummyHost>();
+        let mut backend = ClosureBackend::new();
+        assert!(backend.compile(&bytecode, &table).is_some());
+
+        table[opcode::ADD as usize] =
+            interpreter::Instruction::new(interpreter::instructions::arithmetic::sub, 3);
+        assert!(backend.compile(&bytecode, &table).is_none());
+    }
+}
@@ -1,10 +1,10 @@
 use crate::{
-    instructions::InstructionProvider, item_or_result::FrameInitOrResult, EthFrame, FrameResult,
-    ItemOrResult, PrecompileProvider,
+    compiled::run_compiled, instructions::InstructionProvider, item_or_result::FrameInitOrResult,
+    EthFrame, FrameResult, ItemOrResult, PrecompileProvider,
 };
 use auto_impl::auto_impl;
 use context::{ContextTr, Database, Evm, FrameStack};
-use context_interface::context::ContextError;
+use context_interface::{context::ContextError, Cfg};
 use interpreter::{interpreter::EthInterpreter, interpreter_action::FrameInit, InterpreterResult};
 
 /// Type alias for database error within a context
@@ -208,9 +208,38 @@ where
         let context = &mut self.ctx;
         let instructions = &mut self.instruction;
 
-        let action = frame
-            .interpreter
-            .run_plain(instructions.instruction_table(), context);
+        let use_fused = instructions.fused_instruction_table().is_some()
+            && frame.interpreter.bytecode.use_fused();
+        let code_hash = frame.interpreter.bytecode.hash();
+        let is_compiled =
+            code_hash.is_some_and(|code_hash| instructions.compiled_code(&code_hash).is_some());
+        let gas_mode = frame.interpreter.gas.mode();
+        // Basic blocks are computed before the instruction table is borrowed, they are cached by
+        // the instruction provider.
+        let use_basic_blocks = !is_compiled
+            && gas_mode.is_metered()
+            && context.cfg().is_basic_block_execution_enabled()
+            && frame
+                .interpreter
+                .bytecode
+                .ensure_basic_blocks(|code, code_hash| instructions.basic_blocks(code, code_hash));
+
+        let instruction_table = match instructions.fused_instruction_table() {
+            Some(fused_table) if use_fused => fused_table,
+            _ => instructions.instruction_table(),
+        };
+        let compiled = code_hash.and_then(|code_hash| instructions.compiled_code(&code_hash));
+        let action = if let Some(compiled) = compiled {
+            run_compiled(&mut frame.interpreter, compiled, instruction_table, context)
+        } else if gas_mode.is_unmetered() {
+            frame.interpreter.run_unmetered(instruction_table, context)
+        } else if use_basic_blocks {
+            frame
+                .interpreter
+                .run_basic_blocks(instruction_table, context)
+        } else {
+            frame.interpreter.run_plain(instruction_table, context)
+        };
 
         frame.process_next_action(context, action).inspect(|i| {
             if i.is_result() {
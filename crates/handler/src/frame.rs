@@ -21,7 +21,6 @@ use interpreter::{
     InterpreterResult, InterpreterTypes, SharedMemory,
 };
 use primitives::{
-    constants::CALL_STACK_LIMIT,
     hardfork::SpecId::{self, HOMESTEAD, LONDON, SPURIOUS_DRAGON},
     keccak256, Address, Bytes, U256,
 };
@@ -38,6 +37,14 @@ use std::{borrow::ToOwned, boxed::Box, vec::Vec};
     <IW as InterpreterTypes>::RuntimeFlag,
     <IW as InterpreterTypes>::Extend,
 )]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Interpreter<IW>: serde::Serialize",
+        deserialize = "Interpreter<IW>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct EthFrame<IW: InterpreterTypes = EthInterpreter> {
     /// Frame-specific data (Call, Create, or EOFCreate).
     pub data: FrameData,
@@ -164,7 +171,7 @@ impl EthFrame<EthInterpreter> {
         };
 
         // Check depth
-        if depth > CALL_STACK_LIMIT as usize {
+        if depth > ctx.cfg().max_call_depth() as usize {
             return return_result(InstructionResult::CallTooDeep);
         }
 
@@ -234,7 +241,8 @@ impl EthFrame<EthInterpreter> {
         }
 
         // Create interpreter and executes call and push new CallStackFrame.
-        this.get(EthFrame::invalid).clear(
+        let frame = this.get(EthFrame::invalid);
+        frame.clear(
             FrameData::Call(CallFrame {
                 return_memory_range: inputs.return_memory_offset.clone(),
             }),
@@ -249,6 +257,11 @@ impl EthFrame<EthInterpreter> {
             checkpoint,
             gas_params,
         );
+        frame
+            .interpreter
+            .stack
+            .set_limit(ctx.cfg().max_stack_size());
+        frame.interpreter.gas.set_mode(ctx.cfg().gas_mode());
         Ok(ItemOrResult::Item(this.consume()))
     }
 
@@ -278,7 +291,7 @@ impl EthFrame<EthInterpreter> {
         };
 
         // Check depth
-        if depth > CALL_STACK_LIMIT as usize {
+        if depth > context.cfg().max_call_depth() as usize {
             return return_error(InstructionResult::CallTooDeep);
         }
 
@@ -339,7 +352,8 @@ impl EthFrame<EthInterpreter> {
         };
         let gas_limit = inputs.gas_limit();
 
-        this.get(EthFrame::invalid).clear(
+        let frame = this.get(EthFrame::invalid);
+        frame.clear(
             FrameData::Create(CreateFrame { created_address }),
             FrameInput::Create(inputs),
             depth,
@@ -352,6 +366,11 @@ impl EthFrame<EthInterpreter> {
             checkpoint,
             gas_params,
         );
+        frame
+            .interpreter
+            .stack
+            .set_limit(context.cfg().max_stack_size());
+        frame.interpreter.gas.set_mode(context.cfg().gas_mode());
         Ok(ItemOrResult::Item(this.consume()))
     }
 
@@ -492,6 +511,7 @@ impl EthFrame<EthInterpreter> {
                 let _ = interpreter.stack.push(item);
 
                 // Return unspend gas.
+                interpreter.gas.record_overrun(out_gas.overrun());
                 if ins_result.is_ok_or_revert() {
                     interpreter.gas.erase_cost(out_gas.remaining());
                     interpreter
@@ -524,6 +544,7 @@ impl EthFrame<EthInterpreter> {
                 );
 
                 let this_gas = &mut interpreter.gas;
+                this_gas.record_overrun(outcome.gas().overrun());
                 if instruction_result.is_ok_or_revert() {
                     this_gas.erase_cost(outcome.gas().remaining());
                 }
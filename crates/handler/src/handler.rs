@@ -109,11 +109,13 @@ pub trait Handler {
 
     /// Configure the handler:
     ///  * Set Instruction gas table to the spec id.
+    ///  * Discard the fee transfers of the previous transaction.
     #[inline]
     fn configure(&mut self, evm: &mut Self::Evm) {
         let spec_id = evm.ctx().cfg().spec().into();
         // sets static gas depending on the spec id.
         evm.ctx_instructions().1.set_spec(spec_id);
+        evm.ctx().local_mut().clear_fee_transfers();
     }
 
     /// Runs the system call.
@@ -358,9 +360,11 @@ pub trait Handler {
         let gas = frame_result.gas_mut();
         let remaining = gas.remaining();
         let refunded = gas.refunded();
+        let overrun = gas.overrun();
 
         // Spend the gas limit. Gas is reimbursed when the tx returns successfully.
         *gas = Gas::new_spent(evm.ctx().tx().gas_limit());
+        gas.record_overrun(overrun);
 
         if instruction_result.is_ok_or_revert() {
             gas.erase_cost(remaining);
@@ -454,6 +458,10 @@ pub trait Handler {
     }
 
     /// Transfers transaction fees to the block beneficiary's account.
+    ///
+    /// Fees are distributed by the [`FeePolicy`][context_interface::FeePolicy] of the
+    /// configuration if set, the transfers can be read from the local context after the
+    /// transaction.
     #[inline]
     fn reward_beneficiary(
         &self,
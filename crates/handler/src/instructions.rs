@@ -1,11 +1,19 @@
+use crate::compiled::{CompiledBackend, CompiledCode};
 use auto_impl::auto_impl;
+use bytecode::BasicBlocks;
 use interpreter::{
     gas::params::GasParams,
-    instructions::{instruction_table_gas_changes_spec, InstructionTable},
+    instructions::{
+        fused_instruction_table, instruction_table_gas_changes_spec, static_gas_table,
+        InstructionTable,
+    },
     Host, Instruction, InterpreterTypes,
 };
-use primitives::hardfork::SpecId;
-use std::boxed::Box;
+use primitives::{hardfork::SpecId, B256Map, B256};
+use std::{boxed::Box, sync::Arc};
+
+/// Maximum number of bytecodes whose basic blocks are cached by [`EthInstructions`].
+const MAX_CACHED_BASIC_BLOCKS: usize = 4096;
 
 /// Stores instructions for EVM.
 #[auto_impl(&mut, Box)]
@@ -18,6 +26,39 @@ pub trait InstructionProvider {
     /// Returns the instruction table that is used by EvmTr to execute instructions.
     fn instruction_table(&self) -> &InstructionTable<Self::InterpreterTypes, Self::Context>;
 
+    /// Returns the instruction table used to execute fused bytecode.
+    ///
+    /// If `None`, fused bytecode is executed as regular bytecode.
+    fn fused_instruction_table(
+        &self,
+    ) -> Option<&InstructionTable<Self::InterpreterTypes, Self::Context>> {
+        None
+    }
+
+    /// Returns the code compiled ahead of time for the bytecode hash.
+    ///
+    /// If `Some`, it is executed before the interpreter, see [`CompiledCode`].
+    fn compiled_code(
+        &self,
+        code_hash: &B256,
+    ) -> Option<&CompiledCode<Self::InterpreterTypes, Self::Context>> {
+        let _ = code_hash;
+        None
+    }
+
+    /// Returns the basic blocks of the original bytes of a legacy bytecode, computed with the
+    /// static gas costs of the instruction table, see [`static_gas_table`].
+    ///
+    /// `code_hash` is the hash of the bytecode if known, implementations can use it to reuse the
+    /// blocks across frames.
+    fn basic_blocks(&mut self, code: &[u8], code_hash: Option<B256>) -> Arc<BasicBlocks> {
+        let _ = code_hash;
+        Arc::new(BasicBlocks::analyze(
+            code,
+            &static_gas_table(self.instruction_table()),
+        ))
+    }
+
     /// Returns the gas params that is used by EvmTr to execute instructions.
     fn gas_params(&self) -> GasParams;
 
@@ -34,6 +75,21 @@ pub struct EthInstructions<WIRE: InterpreterTypes, HOST: ?Sized> {
     pub gas_params: GasParams,
     /// Spec that is used to set gas costs for instructions.
     pub spec: SpecId,
+    /// Table used to execute fused bytecode. Fusion is disabled if `None`.
+    ///
+    /// Only set while fusion is enabled and the instructions it fuses are not customised,
+    /// see [`fused_instruction_table`].
+    pub fused_instruction_table: Option<Box<InstructionTable<WIRE, HOST>>>,
+    /// Whether fusion was enabled with [`EthInstructions::enable_fusion`].
+    pub fusion_enabled: bool,
+    /// Backend that supplies code compiled ahead of time.
+    compiled_backend: Option<Arc<dyn CompiledBackend<WIRE, HOST>>>,
+    /// Whether the backend supports the instruction table, updated with the table.
+    compiled_code_enabled: bool,
+    /// Static gas costs of the instruction table, updated with the table.
+    static_gas: [u64; 256],
+    /// Basic blocks computed with `static_gas` by code hash.
+    basic_blocks: B256Map<Arc<BasicBlocks>>,
 }
 
 impl<WIRE, HOST: Host + ?Sized> Clone for EthInstructions<WIRE, HOST>
@@ -45,6 +101,12 @@ where
             instruction_table: self.instruction_table.clone(),
             gas_params: self.gas_params.clone(),
             spec: self.spec,
+            fused_instruction_table: self.fused_instruction_table.clone(),
+            fusion_enabled: self.fusion_enabled,
+            compiled_backend: self.compiled_backend.clone(),
+            compiled_code_enabled: self.compiled_code_enabled,
+            static_gas: self.static_gas,
+            basic_blocks: self.basic_blocks.clone(),
         }
     }
 }
@@ -72,9 +134,83 @@ where
         spec: SpecId,
     ) -> Self {
         Self {
+            static_gas: static_gas_table(&base_table),
             instruction_table: Box::new(base_table),
             gas_params,
             spec,
+            fused_instruction_table: None,
+            fusion_enabled: false,
+            compiled_backend: None,
+            compiled_code_enabled: false,
+            basic_blocks: B256Map::default(),
+        }
+    }
+
+    /// Sets the backend that supplies code compiled ahead of time.
+    ///
+    /// Compiled code is not used while the backend does not support the instruction table, see
+    /// [`CompiledBackend::supports_instruction_table`].
+    #[inline]
+    pub fn with_compiled_backend(
+        mut self,
+        compiled_backend: Arc<dyn CompiledBackend<WIRE, HOST>>,
+    ) -> Self {
+        self.compiled_backend = Some(compiled_backend);
+        self.update_compiled_code();
+        self
+    }
+
+    /// Returns the backend that supplies code compiled ahead of time.
+    #[inline]
+    pub fn compiled_backend(&self) -> Option<&Arc<dyn CompiledBackend<WIRE, HOST>>> {
+        self.compiled_backend.as_ref()
+    }
+
+    /// Checks whether the backend supports the instruction table.
+    #[inline]
+    fn update_compiled_code(&mut self) {
+        self.compiled_code_enabled = self
+            .compiled_backend
+            .as_ref()
+            .is_some_and(|backend| backend.supports_instruction_table(&self.instruction_table));
+    }
+
+    /// Enables execution of [fused bytecode](bytecode::Bytecode::with_fusion).
+    ///
+    /// Fused bytecode is not used when running with an inspector, or while any instruction of a
+    /// fused sequence is customised in the instruction table.
+    #[inline]
+    pub fn with_fusion(mut self) -> Self {
+        self.enable_fusion();
+        self
+    }
+
+    /// Enables execution of fused bytecode.
+    ///
+    /// See [`EthInstructions::with_fusion`] for more details.
+    #[inline]
+    pub fn enable_fusion(&mut self) {
+        self.fusion_enabled = true;
+        self.update_fused_instruction_table();
+    }
+
+    /// Rebuilds the fused instruction table from the instruction table.
+    #[inline]
+    fn update_fused_instruction_table(&mut self) {
+        self.fused_instruction_table = self
+            .fusion_enabled
+            .then(|| fused_instruction_table(&self.instruction_table))
+            .flatten()
+            .map(Box::new);
+    }
+
+    /// Updates the static gas costs and drops the basic blocks computed with the previous ones.
+    #[inline]
+    fn update_static_gas(&mut self) {
+        let static_gas = static_gas_table(&self.instruction_table);
+        if static_gas != self.static_gas {
+            self.static_gas = static_gas;
+            self.basic_blocks.clear();
         }
     }
 
@@ -82,6 +218,9 @@ where
     #[inline]
     pub fn insert_instruction(&mut self, opcode: u8, instruction: Instruction<WIRE, HOST>) {
         self.instruction_table[opcode as usize] = instruction;
+        self.update_fused_instruction_table();
+        self.update_compiled_code();
+        self.update_static_gas();
     }
 }
 
@@ -97,6 +236,37 @@ where
         &self.instruction_table
     }
 
+    fn fused_instruction_table(
+        &self,
+    ) -> Option<&InstructionTable<Self::InterpreterTypes, Self::Context>> {
+        self.fused_instruction_table.as_deref()
+    }
+
+    fn compiled_code(
+        &self,
+        code_hash: &B256,
+    ) -> Option<&CompiledCode<Self::InterpreterTypes, Self::Context>> {
+        if !self.compiled_code_enabled {
+            return None;
+        }
+        self.compiled_backend.as_ref()?.compiled_code(code_hash)
+    }
+
+    fn basic_blocks(&mut self, code: &[u8], code_hash: Option<B256>) -> Arc<BasicBlocks> {
+        let Some(code_hash) = code_hash else {
+            return Arc::new(BasicBlocks::analyze(code, &self.static_gas));
+        };
+        if let Some(basic_blocks) = self.basic_blocks.get(&code_hash) {
+            return basic_blocks.clone();
+        }
+        if self.basic_blocks.len() >= MAX_CACHED_BASIC_BLOCKS {
+            self.basic_blocks.clear();
+        }
+        let basic_blocks = Arc::new(BasicBlocks::analyze(code, &self.static_gas));
+        self.basic_blocks.insert(code_hash, basic_blocks.clone());
+        basic_blocks
+    }
+
     fn gas_params(&self) -> GasParams {
         self.gas_params.clone()
     }
@@ -106,7 +276,12 @@ where
             return false;
         }
         *self.instruction_table = instruction_table_gas_changes_spec(spec);
+        let cost_fns = self.gas_params.cost_fns().cloned();
         self.gas_params = GasParams::new_spec(spec);
+        self.gas_params.set_cost_fns(cost_fns);
+        self.update_fused_instruction_table();
+        self.update_compiled_code();
+        self.update_static_gas();
 
         true
     }
@@ -121,3 +296,53 @@ where
         Self::new_mainnet()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiled::ClosureBackend;
+    use bytecode::{opcode, Bytecode};
+    use context_interface::DummyHost;
+    use interpreter::interpreter::EthInterpreter;
+    use primitives::Bytes;
+
+    #[test]
+    fn basic_blocks_are_cached_by_code_hash() {
+        let mut instructions = EthInstructions::<EthInterpreter, DummyHost>::new_mainnet();
+        let code = [opcode::PUSH1, 0x01, opcode::STOP];
+        let code_hash = B256::repeat_byte(1);
+
+        let basic_blocks = instructions.basic_blocks(&code, Some(code_hash));
+        assert!(Arc::ptr_eq(
+            &basic_blocks,
+            &instructions.basic_blocks(&code, Some(code_hash))
+        ));
+        assert!(!Arc::ptr_eq(
+            &basic_blocks,
+            &instructions.basic_blocks(&code, None)
+        ));
+
+        // Blocks are computed again with the static gas costs of the new spec.
+        assert!(instructions.set_spec(SpecId::FRONTIER));
+        assert!(!Arc::ptr_eq(
+            &basic_blocks,
+            &instructions.basic_blocks(&code, Some(code_hash))
+        ));
+    }
+
+    #[test]
+    fn customised_instructions_disable_compiled_code() {
+        let mut instructions = EthInstructions::<EthInterpreter, DummyHost>::new_mainnet();
+        let bytecode = Bytecode::new_raw(Bytes::from_static(&[opcode::PUSH1, 0x01, opcode::STOP]));
+        let mut backend = ClosureBackend::new();
+        let code_hash = backend
+            .compile(&bytecode, instructions.instruction_table())
+            .unwrap();
+        instructions = instructions.with_compiled_backend(Arc::new(backend));
+        assert!(instructions.compiled_code(&code_hash).is_some());
+
+        // Compiled code runs the default instruction, not the inserted one.
+        instructions.insert_instruction(opcode::ADD, Instruction::new(|_| {}, 3));
+        assert!(instructions.compiled_code(&code_hash).is_none());
+    }
+}
@@ -9,6 +9,7 @@ extern crate alloc as std;
 
 /// EVM execution API traits and implementations.
 pub mod api;
+pub mod compiled;
 /// Core EVM traits for execution and frame management.
 pub mod evm;
 /// EVM execution logic and utilities.
@@ -26,6 +27,8 @@ mod mainnet_handler;
 pub mod post_execution;
 pub mod pre_execution;
 mod precompile_provider;
+pub mod resumable;
+pub mod snapshot;
 /// System call implementations for special EVM operations.
 pub mod system_call;
 /// Transaction and environment validation utilities.
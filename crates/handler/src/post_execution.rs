@@ -3,7 +3,8 @@ use context::journaled_state::account::JournaledAccountTr;
 use context_interface::{
     journaled_state::JournalTr,
     result::{ExecutionResult, HaltReason, HaltReasonTr},
-    Block, Cfg, ContextTr, Database, LocalContextTr, Transaction,
+    Block, Cfg, ContextTr, Database, FeeKind, FeePolicy, FeeTransfer, LocalContextTr, Transaction,
+    TxFees,
 };
 use interpreter::{Gas, InitialAndFloorGas, SuccessOrHalt};
 use primitives::{hardfork::SpecId, U256};
@@ -54,28 +55,101 @@ pub fn reimburse_caller<CTX: ContextTr>(
 }
 
 /// Rewards the beneficiary with transaction fees.
+///
+/// Fees are distributed by the fee policy of the configuration if set. Otherwise the priority
+/// fee is credited to the beneficiary and the base fee and the blob fee are burned, as
+/// [`FeePolicy::mainnet`] does without allocating the transfers.
 #[inline]
 pub fn reward_beneficiary<CTX: ContextTr>(
     context: &mut CTX,
     gas: &Gas,
 ) -> Result<(), <CTX::Db as Database>::Error> {
-    let (block, tx, cfg, journal, _, _) = context.all_mut();
+    let fees = tx_fees(context, gas);
+    if context.cfg().fee_policy().is_some() {
+        return distribute_fees(context, &fees, |_| FeePolicy::mainnet());
+    }
+
+    let (block, _, _, journal, _, local) = context.all_mut();
+    let beneficiary = block.beneficiary();
+
+    // reward beneficiary
+    journal
+        .load_account_mut(beneficiary)?
+        .incr_balance(fees.priority_fee);
+
+    for transfer in [
+        FeeTransfer {
+            kind: FeeKind::BaseFee,
+            to: None,
+            amount: fees.base_fee,
+        },
+        FeeTransfer {
+            kind: FeeKind::PriorityFee,
+            to: Some(beneficiary),
+            amount: fees.priority_fee,
+        },
+        FeeTransfer {
+            kind: FeeKind::BlobFee,
+            to: None,
+            amount: fees.blob_fee,
+        },
+    ] {
+        if !transfer.amount.is_zero() {
+            local.record_fee_transfer(transfer);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the base fee, the priority fee and the blob fee paid by the transaction.
+pub fn tx_fees<CTX: ContextTr>(context: &CTX, gas: &Gas) -> TxFees {
+    let (block, tx, cfg) = (context.block(), context.tx(), context.cfg());
     let basefee = block.basefee() as u128;
     let effective_gas_price = tx.effective_gas_price(basefee);
+    let gas_used = gas.used() as u128;
 
-    // Transfer fee to coinbase/beneficiary.
     // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
-    let coinbase_gas_price = if cfg.spec().into().is_enabled_in(SpecId::LONDON) {
-        effective_gas_price.saturating_sub(basefee)
+    let base_gas_price = if cfg.spec().into().is_enabled_in(SpecId::LONDON) {
+        basefee.min(effective_gas_price)
     } else {
-        effective_gas_price
+        0
     };
+    let blob_gas_price = block.blob_gasprice().unwrap_or_default();
 
-    // reward beneficiary
-    journal
-        .load_account_mut(block.beneficiary())?
-        .incr_balance(U256::from(coinbase_gas_price * gas.used() as u128));
+    TxFees {
+        base_fee: U256::from(base_gas_price.saturating_mul(gas_used)),
+        priority_fee: U256::from((effective_gas_price - base_gas_price).saturating_mul(gas_used)),
+        blob_fee: U256::from((tx.total_blob_gas() as u128).saturating_mul(blob_gas_price)),
+        ..Default::default()
+    }
+}
+
+/// Pays the fees of the transaction to their recipients.
+///
+/// Fees are distributed by the fee policy of the configuration, by the policy of the chain if
+/// none is set. Transfers are recorded in the local context, where they can be read after the
+/// transaction, a transfer of a zero amount touches its recipient but is not recorded.
+pub fn distribute_fees<CTX: ContextTr>(
+    context: &mut CTX,
+    fees: &TxFees,
+    chain_policy: impl FnOnce(&CTX::Chain) -> FeePolicy,
+) -> Result<(), <CTX::Db as Database>::Error> {
+    let (block, _, cfg, journal, chain, local) = context.all_mut();
+    let beneficiary = block.beneficiary();
+    let transfers = match cfg.fee_policy() {
+        Some(policy) => policy.transfers(fees, beneficiary),
+        None => chain_policy(chain).transfers(fees, beneficiary),
+    };
 
+    for transfer in transfers {
+        if let Some(to) = transfer.to {
+            journal.load_account_mut(to)?.incr_balance(transfer.amount);
+        }
+        if !transfer.amount.is_zero() {
+            local.record_fee_transfer(transfer);
+        }
+    }
     Ok(())
 }
 
@@ -88,9 +162,10 @@ pub fn output<CTX: ContextTr<Journal: JournalTr>, HALTREASON: HaltReasonTr>(
     // FrameResult should be a generic that returns gas and interpreter result.
     result: FrameResult,
 ) -> ExecutionResult<HALTREASON> {
-    // Used gas with refund calculated.
+    // Used gas with refund calculated, including the gas spent above the limit in
+    // `GasMode::Tracked` mode that is not paid for.
     let gas_refunded = result.gas().refunded() as u64;
-    let gas_used = result.gas().used();
+    let gas_used = result.gas().used() + result.gas().overrun();
     let output = result.output();
     let instruction_result = result.into_interpreter_result();
 
@@ -97,9 +97,10 @@ impl<CTX: ContextTr> PrecompileProvider<CTX> for EthPrecompiles {
             return Ok(None);
         };
 
+        let gas_mode = context.cfg().gas_mode();
         let mut result = InterpreterResult {
             result: InstructionResult::Return,
-            gas: Gas::new(inputs.gas_limit),
+            gas: Gas::new(inputs.gas_limit).with_mode(gas_mode),
             output: Bytes::new(),
         };
 
@@ -116,7 +117,7 @@ impl<CTX: ContextTr> PrecompileProvider<CTX> for EthPrecompiles {
                 }
                 CallInput::Bytes(bytes) => bytes.0.iter().as_slice(),
             };
-            precompile.execute(input_bytes, inputs.gas_limit)
+            precompile.execute(input_bytes, result.gas.available())
         };
 
         match exec_result {
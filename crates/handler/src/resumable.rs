@@ -0,0 +1,564 @@
+//! Resumable execution that suspends when the database is missing data.
+//!
+//! When a database returns an error with a [`DataRequest`] (See
+//! [`DBErrorMarker::data_request`]), the instruction or frame creation that needed the data is
+//! rolled back and [`Resumable::Suspended`] is returned. The frame stack stays in the EVM, so
+//! after the data is fetched and made available to the database the execution continues from
+//! the same instruction. Post-execution, which loads the beneficiary, is rolled back and run
+//! again in the same way.
+//!
+//! This allows executing transactions over remote databases without blocking threads:
+//!
+//! ```ignore
+//! let mut execution = ResumableExecution::default();
+//! let result = loop {
+//!     match run_resumable_tx(&mut handler, &mut evm, &mut execution)? {
+//!         Resumable::Ready(result) => break result,
+//!         Resumable::Suspended(request) => fetch_into_cache(&mut evm, request).await,
+//!     }
+//! };
+//! ```
+use crate::{
+    evm::ContextDbError, instructions::InstructionProvider, item_or_result::FrameInitOrResult,
+    EthFrame, EvmTr, FrameResult, FrameTr, Handler, ItemOrResult, PrecompileProvider,
+};
+use bytecode::opcode;
+use context::{ContextTr, Evm, JournalTr};
+use context_interface::{
+    context::ContextError,
+    journaled_state::JournalCheckpoint,
+    result::{EVMError, ExecutionResult},
+    Transaction,
+};
+use database_interface::{DBErrorMarker, DataRequest};
+use interpreter::{
+    interpreter::EthInterpreter,
+    interpreter_action::FrameInit,
+    interpreter_types::{Jumps, LoopControl},
+    InitialAndFloorGas, InstructionTable, Interpreter, InterpreterAction, InterpreterResult,
+};
+use primitives::U256;
+
+/// Result of a resumable execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resumable<T> {
+    /// Execution finished.
+    Ready(T),
+    /// Execution is suspended until the requested data is available.
+    Suspended(DataRequest),
+}
+
+/// Errors that can be caused by missing data.
+pub trait DataRequestError {
+    /// Returns the missing data if the error is caused by it.
+    fn data_request(&self) -> Option<DataRequest>;
+}
+
+impl<DBError: DBErrorMarker, TX> DataRequestError for EVMError<DBError, TX> {
+    fn data_request(&self) -> Option<DataRequest> {
+        match self {
+            EVMError::Database(e) => e.data_request(),
+            _ => None,
+        }
+    }
+}
+
+impl<DBError: DBErrorMarker> DataRequestError for ContextError<DBError> {
+    fn data_request(&self) -> Option<DataRequest> {
+        match self {
+            ContextError::Db(e) => e.data_request(),
+            ContextError::Custom(_) => None,
+        }
+    }
+}
+
+/// Returns `true` if the instruction loads data from the database.
+#[inline]
+const fn loads_data(op: u8) -> bool {
+    matches!(
+        op,
+        opcode::BALANCE
+            | opcode::EXTCODESIZE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::BLOCKHASH
+            | opcode::SELFBALANCE
+            | opcode::SLOAD
+            | opcode::SSTORE
+            | opcode::CALL
+            | opcode::CALLCODE
+            | opcode::DELEGATECALL
+            | opcode::STATICCALL
+            | opcode::SELFDESTRUCT
+    )
+}
+
+/// Maximum number of stack items taken by an instruction that loads data.
+const MAX_INPUTS: usize = 7;
+
+/// Runs the interpreter until it returns, stops or an instruction misses data.
+///
+/// Instructions that load data run inside a journal checkpoint. If one of them misses data,
+/// the journal, stack, gas, memory and program counter are restored to the state before the
+/// instruction, so running the interpreter again retries the instruction.
+pub fn run_resumable<CTX: ContextTr>(
+    interpreter: &mut Interpreter<EthInterpreter>,
+    instruction_table: &InstructionTable<EthInterpreter, CTX>,
+    context: &mut CTX,
+) -> Resumable<InterpreterAction> {
+    while interpreter.bytecode.is_not_end() {
+        if !loads_data(interpreter.bytecode.opcode()) {
+            interpreter.step(instruction_table, context);
+            continue;
+        }
+
+        let pc = interpreter.bytecode.pc();
+        let gas = interpreter.gas;
+        let memory_len = interpreter.memory.len();
+        let stack = interpreter.stack.data();
+        let inputs = stack.len().min(MAX_INPUTS);
+        let mut top = [U256::ZERO; MAX_INPUTS];
+        top[..inputs].copy_from_slice(&stack[stack.len() - inputs..]);
+        let stack_len = stack.len();
+        let depth = context.journal().depth();
+        let checkpoint = context.journal_mut().checkpoint();
+
+        interpreter.step(instruction_table, context);
+
+        let request = match context.error() {
+            Err(e) => e.data_request(),
+            Ok(()) => None,
+        };
+        let Some(request) = request else {
+            context.journal_mut().checkpoint_commit();
+            continue;
+        };
+
+        *context.error() = Ok(());
+        revert_to(context, depth, checkpoint);
+        interpreter.bytecode.action().take();
+        interpreter.bytecode.reset_action();
+        interpreter.bytecode.absolute_jump(pc);
+        interpreter.gas = gas;
+        interpreter.memory.resize(memory_len);
+        let stack = interpreter.stack.data_mut();
+        stack.truncate(stack_len - inputs);
+        stack.extend_from_slice(&top[..inputs]);
+        return Resumable::Suspended(request);
+    }
+    Resumable::Ready(interpreter.take_next_action())
+}
+
+/// Reverts the journal to the checkpoint created at `depth`, dropping checkpoints created after it.
+#[inline]
+fn revert_to<CTX: ContextTr>(context: &mut CTX, depth: usize, checkpoint: JournalCheckpoint) {
+    let journal = context.journal_mut();
+    while journal.depth() > depth + 1 {
+        journal.checkpoint_commit();
+    }
+    journal.checkpoint_revert(checkpoint);
+}
+
+/// EVM that can suspend the execution of frames when the database is missing data.
+pub trait ResumableEvmTr:
+    EvmTr<Frame: FrameTr<FrameInit = FrameInit, FrameResult = FrameResult>>
+{
+    /// Runs the frame from the top of the stack, see [`EvmTr::frame_run`].
+    ///
+    /// Returns [`Resumable::Suspended`] if an instruction misses data, running the frame again
+    /// retries the instruction. Compiled code is not used.
+    fn frame_run_resumable(
+        &mut self,
+    ) -> Result<Resumable<FrameInitOrResult<Self::Frame>>, ContextDbError<Self::Context>>;
+
+    /// Creates the frame, see [`EvmTr::frame_init`].
+    ///
+    /// Returns [`Resumable::Suspended`] if creation misses data, the journal is reverted and the
+    /// frame can be created again with the same input.
+    fn frame_init_resumable(
+        &mut self,
+        frame_input: FrameInit,
+    ) -> Result<Resumable<ItemOrResult<(), FrameResult>>, ContextDbError<Self::Context>>;
+}
+
+impl<CTX, INSP, I, P> ResumableEvmTr for Evm<CTX, INSP, I, P, EthFrame<EthInterpreter>>
+where
+    CTX: ContextTr,
+    I: InstructionProvider<Context = CTX, InterpreterTypes = EthInterpreter>,
+    P: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    fn frame_run_resumable(
+        &mut self,
+    ) -> Result<Resumable<FrameInitOrResult<Self::Frame>>, ContextDbError<CTX>> {
+        let frame = self.frame_stack.get();
+        let context = &mut self.ctx;
+        let instructions = &mut self.instruction;
+
+        let instruction_table = match instructions.fused_instruction_table() {
+            Some(fused_table) if frame.interpreter.bytecode.use_fused() => fused_table,
+            _ => instructions.instruction_table(),
+        };
+        let action = match run_resumable(&mut frame.interpreter, instruction_table, context) {
+            Resumable::Ready(action) => action,
+            Resumable::Suspended(request) => return Ok(Resumable::Suspended(request)),
+        };
+
+        frame
+            .process_next_action(context, action)
+            .inspect(|i| {
+                if i.is_result() {
+                    frame.set_finished(true);
+                }
+            })
+            .map(Resumable::Ready)
+    }
+
+    fn frame_init_resumable(
+        &mut self,
+        frame_input: FrameInit,
+    ) -> Result<Resumable<ItemOrResult<(), FrameResult>>, ContextDbError<CTX>> {
+        let depth = self.ctx.journal().depth();
+        let checkpoint = self.ctx.journal_mut().checkpoint();
+        match self.frame_init(frame_input) {
+            Ok(res) => {
+                let res = res.map_frame(|_| ());
+                self.ctx.journal_mut().checkpoint_commit();
+                Ok(Resumable::Ready(res))
+            }
+            Err(e) => match e.data_request() {
+                Some(request) => {
+                    revert_to(&mut self.ctx, depth, checkpoint);
+                    Ok(Resumable::Suspended(request))
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+/// State of a transaction executed with [`run_resumable_tx`].
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResumableExecution {
+    stage: Stage,
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Stage {
+    /// Transaction is not validated yet.
+    #[default]
+    Start,
+    /// Frames are executing.
+    Execution {
+        init_and_floor_gas: InitialAndFloorGas,
+        eip7702_refund: i64,
+        /// Frame that needs to be created.
+        pending: Option<FrameInit>,
+    },
+    /// Frames are executed and post-execution needs to run.
+    Finish {
+        init_and_floor_gas: InitialAndFloorGas,
+        eip7702_refund: i64,
+        /// Result of the first frame.
+        frame_result: FrameResult,
+    },
+}
+
+/// Executes the transaction set in the context, suspending when the database is missing data.
+///
+/// Calling it again with the same `execution` resumes the execution. If data is missing before
+/// the first frame is created, the transaction is discarded and started again on resume.
+pub fn run_resumable_tx<H>(
+    handler: &mut H,
+    evm: &mut H::Evm,
+    execution: &mut ResumableExecution,
+) -> Result<Resumable<ExecutionResult<H::HaltReason>>, H::Error>
+where
+    H: Handler,
+    H::Evm: ResumableEvmTr,
+    H::Error: DataRequestError,
+{
+    handler.configure(evm);
+
+    if let Stage::Start = execution.stage {
+        match start(handler, evm) {
+            Ok((init_and_floor_gas, eip7702_refund, first_frame_input)) => {
+                execution.stage = Stage::Execution {
+                    init_and_floor_gas,
+                    eip7702_refund,
+                    pending: Some(first_frame_input),
+                };
+            }
+            Err(e) => {
+                if let Some(request) = e.data_request() {
+                    evm.ctx().journal_mut().discard_tx();
+                    return Ok(Resumable::Suspended(request));
+                }
+                return handler.catch_error(evm, e).map(Resumable::Ready);
+            }
+        }
+    }
+
+    if let Stage::Execution {
+        init_and_floor_gas,
+        eip7702_refund,
+        pending,
+    } = &mut execution.stage
+    {
+        let init_and_floor_gas = *init_and_floor_gas;
+        let eip7702_refund = *eip7702_refund;
+        match run_exec_loop(evm, pending) {
+            Ok(Resumable::Ready(frame_result)) => {
+                execution.stage = Stage::Finish {
+                    init_and_floor_gas,
+                    eip7702_refund,
+                    frame_result,
+                };
+            }
+            Ok(Resumable::Suspended(request)) => return Ok(Resumable::Suspended(request)),
+            Err(e) => {
+                execution.stage = Stage::Start;
+                return handler.catch_error(evm, e.into()).map(Resumable::Ready);
+            }
+        }
+    }
+
+    let Stage::Finish {
+        init_and_floor_gas,
+        eip7702_refund,
+        frame_result,
+    } = &execution.stage
+    else {
+        unreachable!("frames are executed")
+    };
+
+    // Post-execution runs inside a journal checkpoint so it can be run again from the same
+    // frame result if it misses data.
+    let mut frame_result = frame_result.clone();
+    let depth = evm.ctx().journal().depth();
+    let checkpoint = evm.ctx().journal_mut().checkpoint();
+    match finish(
+        handler,
+        evm,
+        &mut frame_result,
+        *init_and_floor_gas,
+        *eip7702_refund,
+    ) {
+        Ok(()) => evm.ctx().journal_mut().checkpoint_commit(),
+        Err(e) => {
+            if let Some(request) = e.data_request() {
+                revert_to(evm.ctx(), depth, checkpoint);
+                return Ok(Resumable::Suspended(request));
+            }
+            execution.stage = Stage::Start;
+            return handler.catch_error(evm, e).map(Resumable::Ready);
+        }
+    }
+    execution.stage = Stage::Start;
+
+    match handler.execution_result(evm, frame_result) {
+        Ok(result) => Ok(Resumable::Ready(result)),
+        Err(e) => handler.catch_error(evm, e).map(Resumable::Ready),
+    }
+}
+
+/// Validates the transaction, runs pre-execution and creates the first frame input.
+#[inline]
+fn start<H: Handler>(
+    handler: &mut H,
+    evm: &mut H::Evm,
+) -> Result<(InitialAndFloorGas, i64, FrameInit), H::Error> {
+    let init_and_floor_gas = handler.validate(evm)?;
+    let eip7702_refund = handler.pre_execution(evm)? as i64;
+    let gas_limit = evm.ctx().tx().gas_limit() - init_and_floor_gas.initial_gas;
+    let first_frame_input = handler.first_frame_input(evm, gas_limit)?;
+    Ok((init_and_floor_gas, eip7702_refund, first_frame_input))
+}
+
+/// Handles the last frame result and runs post-execution.
+#[inline]
+fn finish<H: Handler>(
+    handler: &mut H,
+    evm: &mut H::Evm,
+    frame_result: &mut FrameResult,
+    init_and_floor_gas: InitialAndFloorGas,
+    eip7702_refund: i64,
+) -> Result<(), H::Error> {
+    handler.last_frame_result(evm, frame_result)?;
+    handler.post_execution(evm, frame_result, init_and_floor_gas, eip7702_refund)
+}
+
+/// Resumable version of [`Handler::run_exec_loop`].
+fn run_exec_loop<EVM>(
+    evm: &mut EVM,
+    pending: &mut Option<FrameInit>,
+) -> Result<Resumable<FrameResult>, ContextDbError<EVM::Context>>
+where
+    EVM: ResumableEvmTr,
+{
+    loop {
+        let result = match pending.take() {
+            Some(init) => match evm.frame_init_resumable(init.clone())? {
+                Resumable::Suspended(request) => {
+                    *pending = Some(init);
+                    return Ok(Resumable::Suspended(request));
+                }
+                Resumable::Ready(ItemOrResult::Item(())) => continue,
+                // First frame returned without being created.
+                Resumable::Ready(ItemOrResult::Result(result))
+                    if evm.frame_stack().index().is_none() =>
+                {
+                    return Ok(Resumable::Ready(result));
+                }
+                Resumable::Ready(ItemOrResult::Result(result)) => result,
+            },
+            None => match evm.frame_run_resumable()? {
+                Resumable::Suspended(request) => return Ok(Resumable::Suspended(request)),
+                Resumable::Ready(ItemOrResult::Item(init)) => {
+                    *pending = Some(init);
+                    continue;
+                }
+                Resumable::Ready(ItemOrResult::Result(result)) => result,
+            },
+        };
+
+        if let Some(result) = evm.frame_return_result(result)? {
+            return Ok(Resumable::Ready(result));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExecuteEvm, MainBuilder, MainContext, MainnetHandler};
+    use bytecode::Bytecode;
+    use context::{Context, TxEnv};
+    use context_interface::result::InvalidTransaction;
+    use database::{CacheDB, DbAccount, InMemoryDB};
+    use database_interface::{DatabaseRef, MissingDataDB};
+    use primitives::{address, Address, Bytes, StorageKey, TxKind};
+    use state::AccountInfo;
+    use std::vec::Vec;
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const CONTRACT: Address = address!("0x2000000000000000000000000000000000000002");
+    const CALLEE: Address = address!("0x3000000000000000000000000000000000000003");
+
+    /// Copies the requested data from `source` into the cache.
+    fn fetch(db: &mut CacheDB<MissingDataDB>, source: &InMemoryDB, request: DataRequest) {
+        match request {
+            DataRequest::Basic(address) => match source.basic_ref(address).unwrap() {
+                Some(info) => db.insert_account_info(address, info),
+                None => {
+                    db.cache
+                        .accounts
+                        .insert(address, DbAccount::new_not_existing());
+                }
+            },
+            DataRequest::CodeByHash(hash) => {
+                let code = source.code_by_hash_ref(hash).unwrap();
+                db.cache.contracts.insert(hash, code);
+            }
+            DataRequest::Storage(address, key) => {
+                let value = source.storage_ref(address, key).unwrap();
+                db.insert_account_storage(address, key, value).unwrap();
+            }
+            DataRequest::BlockHash(number) => {
+                let hash = source.block_hash_ref(number).unwrap();
+                db.cache.block_hashes.insert(U256::from(number), hash);
+            }
+        }
+    }
+
+    #[test]
+    fn resumed_execution_matches_execution() {
+        // Copies slot 1 to slot 2 and calls `CALLEE`.
+        let code = Bytes::from(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x02,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::DUP1,
+                opcode::DUP1,
+                opcode::DUP1,
+                opcode::DUP1,
+                opcode::PUSH20,
+            ]
+            .into_iter()
+            .chain(CALLEE.0)
+            .chain([opcode::GAS, opcode::CALL, opcode::STOP])
+            .collect::<Vec<_>>(),
+        );
+        // Stores 7 to slot 0.
+        let callee_code = Bytes::from_static(&[
+            opcode::PUSH1,
+            0x07,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]);
+
+        let mut source = InMemoryDB::default();
+        source.insert_account_info(
+            CALLER,
+            AccountInfo::default().with_balance(U256::from(10u64.pow(18))),
+        );
+        source.insert_account_info(
+            CONTRACT,
+            AccountInfo::default().with_code(Bytecode::new_legacy(code)),
+        );
+        source
+            .insert_account_storage(CONTRACT, StorageKey::from(1), U256::from(42))
+            .unwrap();
+        source.insert_account_info(
+            CALLEE,
+            AccountInfo::default().with_code(Bytecode::new_legacy(callee_code)),
+        );
+        let tx = TxEnv::builder()
+            .caller(CALLER)
+            .kind(TxKind::Call(CONTRACT))
+            .gas_limit(100_000)
+            .build()
+            .unwrap();
+
+        let mut evm = Context::mainnet().with_db(source.clone()).build_mainnet();
+        let expected = evm.transact(tx.clone()).unwrap();
+
+        let mut evm = Context::mainnet()
+            .with_db(CacheDB::new(MissingDataDB))
+            .with_tx(tx)
+            .build_mainnet();
+        let mut handler = MainnetHandler::<
+            _,
+            EVMError<DataRequest, InvalidTransaction>,
+            EthFrame<EthInterpreter>,
+        >::default();
+        let mut execution = ResumableExecution::default();
+        let mut requests = Vec::new();
+        let result = loop {
+            match run_resumable_tx(&mut handler, &mut evm, &mut execution).unwrap() {
+                Resumable::Ready(result) => break result,
+                Resumable::Suspended(request) => {
+                    requests.push(request);
+                    fetch(evm.ctx.db_mut(), &source, request);
+                }
+            }
+        };
+        let state = evm.finalize();
+
+        assert!(requests.contains(&DataRequest::Storage(CONTRACT, StorageKey::from(1))));
+        assert!(requests.contains(&DataRequest::Basic(CALLEE)));
+        // The beneficiary is only loaded in post-execution.
+        assert_eq!(requests.last(), Some(&DataRequest::Basic(Address::ZERO)));
+        assert_eq!(result, expected.result);
+        assert_eq!(state, expected.state);
+    }
+}
@@ -0,0 +1,300 @@
+//! Snapshots of in-flight execution.
+//!
+//! [`ExecutionSnapshot`] captures the frame stack and the journal of an EVM between two steps of
+//! the execution. It can be restored into an EVM with the same configuration and database to
+//! continue the execution, or serialized with the `serde` feature to be stored on disk or moved
+//! to another process.
+use crate::EthFrame;
+use context::{
+    ContextTr, Database, Evm, FrameStack, Journal, JournalEntry, JournalEntryTr, JournalInner,
+    LocalContextTr,
+};
+use core::cell::RefCell;
+use interpreter::interpreter::EthInterpreter;
+use std::{rc::Rc, vec::Vec};
+
+/// Snapshot of an in-flight execution.
+///
+/// Contains the active frames, with the stack, memory, program counter, gas and return data of
+/// their interpreters, and the journal state. Database is not part of the snapshot.
+///
+/// Snapshot can be taken whenever the execution is not running, for example when
+/// [`run_resumable_tx`](crate::resumable::run_resumable_tx) is suspended. The
+/// [`ResumableExecution`](crate::resumable::ResumableExecution) needs to be stored along with it
+/// to continue the transaction.
+///
+/// Data derived from the bytecode is not serialized: fused bytecode is computed again on
+/// deserialization of the frames that executed it and basic blocks when the frame runs. Frames
+/// of a deserialized snapshot don't share the memory buffer, [`ExecutionSnapshot::restore`]
+/// makes them share the buffer of the local context again.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecutionSnapshot<ENTRY = JournalEntry> {
+    /// Active frames, the last one being the current frame.
+    pub frame_stack: FrameStack<EthFrame<EthInterpreter>>,
+    /// Journal state.
+    pub journal: JournalInner<ENTRY>,
+}
+
+impl<ENTRY: JournalEntryTr + Clone> ExecutionSnapshot<ENTRY> {
+    /// Captures the frame stack and the journal of the EVM.
+    pub fn capture<CTX, DB, INSP, I, P>(
+        evm: &Evm<CTX, INSP, I, P, EthFrame<EthInterpreter>>,
+    ) -> Self
+    where
+        DB: Database,
+        CTX: ContextTr<Db = DB, Journal = Journal<DB, ENTRY>>,
+    {
+        let mut frames = evm.frame_stack.frames().to_vec();
+        // Cloned frames still share the memory buffer with the EVM.
+        if let Some(buffer) = current_buffer(&frames) {
+            let buffer = Rc::new(RefCell::new(buffer.borrow().clone()));
+            share_buffer(&mut frames, &buffer);
+        }
+        Self {
+            frame_stack: FrameStack::from_frames(frames),
+            journal: evm.ctx.journal().inner.clone(),
+        }
+    }
+
+    /// Restores the frame stack and the journal into the EVM.
+    ///
+    /// Memory of the frames is moved to the shared memory buffer of the local context. Code cache
+    /// of the EVM journal is kept.
+    pub fn restore<CTX, DB, INSP, I, P>(
+        self,
+        evm: &mut Evm<CTX, INSP, I, P, EthFrame<EthInterpreter>>,
+    ) where
+        DB: Database,
+        CTX: ContextTr<Db = DB, Journal = Journal<DB, ENTRY>>,
+    {
+        let Self {
+            mut frame_stack,
+            journal,
+        } = self;
+        let local_buffer = evm.ctx.local().shared_memory_buffer().clone();
+        if let Some(buffer) = current_buffer(frame_stack.frames()) {
+            if !Rc::ptr_eq(buffer, &local_buffer) {
+                let memory = buffer.borrow().clone();
+                *local_buffer.borrow_mut() = memory;
+            }
+        }
+        share_buffer(frame_stack.frames_mut(), &local_buffer);
+        evm.frame_stack = frame_stack;
+
+        let inner = &mut evm.ctx.journal_mut().inner;
+        #[cfg(feature = "std")]
+        let code_cache = inner.code_cache.take();
+        *inner = journal;
+        #[cfg(feature = "std")]
+        {
+            inner.code_cache = code_cache;
+        }
+    }
+}
+
+/// Returns the memory buffer of the current frame, it contains the memory of all frames.
+#[inline]
+fn current_buffer(frames: &[EthFrame<EthInterpreter>]) -> Option<&Rc<RefCell<Vec<u8>>>> {
+    frames.last()?.interpreter.memory.shared_buffer()
+}
+
+/// Makes all frames use the same memory buffer.
+#[inline]
+fn share_buffer(frames: &mut [EthFrame<EthInterpreter>], buffer: &Rc<RefCell<Vec<u8>>>) {
+    for frame in frames {
+        frame.interpreter.memory.set_shared_buffer(buffer.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        resumable::{run_resumable_tx, Resumable, ResumableExecution},
+        ExecuteEvm, MainBuilder, MainContext, MainnetHandler,
+    };
+    use bytecode::{opcode, Bytecode};
+    use context::{
+        result::{EVMError, InvalidTransaction},
+        Context, TxEnv,
+    };
+    use database::{CacheDB, DbAccount};
+    use database_interface::{DataRequest, MissingDataDB};
+    use primitives::{address, Address, Bytes, StorageKey, TxKind, U256};
+    use state::AccountInfo;
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const CONTRACT: Address = address!("0x2000000000000000000000000000000000000002");
+    const CALLEE: Address = address!("0x3000000000000000000000000000000000000003");
+
+    #[test]
+    fn restored_execution_continues() {
+        check_restored_execution(false, |snapshot| snapshot);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialized_execution_continues() {
+        let round_trip = |snapshot: ExecutionSnapshot| {
+            let json = serde_json::to_string(&snapshot).unwrap();
+            let snapshot: ExecutionSnapshot = serde_json::from_str(&json).unwrap();
+            let frames = snapshot.frame_stack.frames();
+            assert!(frames[0].interpreter.bytecode.is_fused());
+            assert!(!Rc::ptr_eq(
+                current_buffer(frames).unwrap(),
+                frames[0].interpreter.memory.shared_buffer().unwrap()
+            ));
+            snapshot
+        };
+        check_restored_execution(true, round_trip);
+    }
+
+    /// Suspends a call on missing storage, restores the snapshot passed through `round_trip` into
+    /// a new EVM and compares its execution with the original one.
+    fn check_restored_execution(
+        fused: bool,
+        round_trip: impl FnOnce(ExecutionSnapshot) -> ExecutionSnapshot,
+    ) {
+        // Jumps over `PUSH1 3 JUMP`, stores 0xaa to memory and calls `CALLEE` with it.
+        let code = Bytes::from(
+            [
+                opcode::PUSH1,
+                0x03,
+                opcode::JUMP,
+                opcode::JUMPDEST,
+                opcode::PUSH1,
+                0xaa,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH20,
+            ]
+            .into_iter()
+            .chain(CALLEE.0)
+            .chain([opcode::GAS, opcode::CALL, opcode::STOP])
+            .collect::<Vec<_>>(),
+        );
+        // Stores the sum of the call data and slot 1 to slot 0.
+        let callee_code = Bytes::from_static(&[
+            opcode::PUSH1,
+            0x00,
+            opcode::CALLDATALOAD,
+            opcode::PUSH1,
+            0x01,
+            opcode::SLOAD,
+            opcode::ADD,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]);
+
+        // Slot 1 of `CALLEE` is missing.
+        let mut db = CacheDB::new(MissingDataDB);
+        db.insert_account_info(
+            CALLER,
+            AccountInfo::default().with_balance(U256::from(10u64.pow(18))),
+        );
+        db.insert_account_info(
+            CONTRACT,
+            AccountInfo::default().with_code(if fused {
+                Bytecode::new_legacy(code).with_fusion()
+            } else {
+                Bytecode::new_legacy(code)
+            }),
+        );
+        db.insert_account_info(
+            CALLEE,
+            AccountInfo::default().with_code(Bytecode::new_legacy(callee_code)),
+        );
+        db.insert_account_storage(CALLEE, StorageKey::ZERO, U256::ZERO)
+            .unwrap();
+        let tx = TxEnv::builder()
+            .caller(CALLER)
+            .kind(TxKind::Call(CONTRACT))
+            .gas_limit(100_000)
+            .build()
+            .unwrap();
+        let evm = |db| {
+            let mut evm = Context::mainnet()
+                .with_db(db)
+                .with_tx(tx.clone())
+                .build_mainnet();
+            if fused {
+                evm.instruction.enable_fusion();
+            }
+            evm
+        };
+        let mut handler = MainnetHandler::<
+            _,
+            EVMError<DataRequest, InvalidTransaction>,
+            EthFrame<EthInterpreter>,
+        >::default();
+
+        let mut evm1 = evm(db.clone());
+        let mut execution = ResumableExecution::default();
+        let request = run_resumable_tx(&mut handler, &mut evm1, &mut execution).unwrap();
+        assert_eq!(
+            request,
+            Resumable::Suspended(DataRequest::Storage(CALLEE, StorageKey::from(1)))
+        );
+        let snapshot = ExecutionSnapshot::capture(&evm1);
+        assert_eq!(snapshot.frame_stack.frames().len(), 2);
+        let resumed_execution = execution.clone();
+
+        db.insert_account_storage(CALLEE, StorageKey::from(1), U256::from(1))
+            .unwrap();
+        *evm1.ctx.db_mut() = db.clone();
+        // The beneficiary is loaded in post-execution.
+        let request = run_resumable_tx(&mut handler, &mut evm1, &mut execution).unwrap();
+        assert_eq!(
+            request,
+            Resumable::Suspended(DataRequest::Basic(Address::ZERO))
+        );
+
+        db.cache
+            .accounts
+            .insert(Address::ZERO, DbAccount::new_not_existing());
+        *evm1.ctx.db_mut() = db.clone();
+        let Resumable::Ready(result1) =
+            run_resumable_tx(&mut handler, &mut evm1, &mut execution).unwrap()
+        else {
+            panic!("data is available");
+        };
+        let state1 = evm1.finalize();
+
+        let mut evm2 = evm(db);
+        round_trip(snapshot).restore(&mut evm2);
+        let local_buffer = evm2.ctx.local().shared_memory_buffer().clone();
+        for frame in evm2.frame_stack.frames() {
+            let buffer = frame.interpreter.memory.shared_buffer().unwrap();
+            assert!(Rc::ptr_eq(buffer, &local_buffer));
+        }
+        let mut execution = resumed_execution;
+        let Resumable::Ready(result2) =
+            run_resumable_tx(&mut handler, &mut evm2, &mut execution).unwrap()
+        else {
+            panic!("data is available");
+        };
+        let state2 = evm2.finalize();
+
+        assert!(result1.is_success());
+        assert_eq!(result1, result2);
+        assert_eq!(state1, state2);
+        assert_eq!(
+            state2[&CALLEE].storage[&StorageKey::ZERO].present_value,
+            U256::from(0xab)
+        );
+    }
+}
@@ -309,7 +309,7 @@ mod tests {
                 gas_used: 22143,
                 gas_refunded: 0,
                 logs: vec![],
-                output: Output::Call(Bytes::default())
+                output: Output::Call(Bytes::default()),
             }
         );
         // only system contract is updated and present
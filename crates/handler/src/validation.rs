@@ -1,7 +1,8 @@
+use crate::pre_execution::{calculate_caller_fee, validate_account_nonce_and_code_with_components};
 use context_interface::{
     result::{InvalidHeader, InvalidTransaction},
     transaction::{Transaction, TransactionType},
-    Block, Cfg, ContextTr,
+    Block, Cfg, ContextTr, Database,
 };
 use core::cmp;
 use interpreter::gas::{self, InitialAndFloorGas};
@@ -256,16 +257,58 @@ pub fn validate_initial_tx_gas(
     Ok(gas)
 }
 
+/// Validates the transaction against the block, configuration and current state without executing it.
+///
+/// Runs the same checks as the handler does before execution: environment and transaction fields,
+/// blob and authorization list rules, intrinsic and floor gas, caller nonce and code (EIP-3607)
+/// and caller balance for the maximum transaction cost.
+///
+/// The caller account is read directly from the database, the journal is not touched, so it can
+/// be used by transaction pools to check pending transactions against the latest state.
+///
+/// Returns the initial and floor gas of the transaction.
+pub fn validate_tx_against_state<
+    CTX: ContextTr,
+    ERROR: From<InvalidHeader> + From<InvalidTransaction> + From<<CTX::Db as Database>::Error>,
+>(
+    context: &mut CTX,
+) -> Result<InitialAndFloorGas, ERROR> {
+    validate_env::<_, ERROR>(&mut *context)?;
+    let gas = validate_initial_tx_gas(
+        context.tx(),
+        context.cfg().spec().into(),
+        context.cfg().is_eip7623_disabled(),
+    )?;
+
+    let caller = context.tx().caller();
+    let db = context.db_mut();
+    let mut caller_info = db.basic(caller)?.unwrap_or_default();
+    if caller_info.code.is_none() && !caller_info.is_empty_code_hash() {
+        caller_info.code = Some(db.code_by_hash(caller_info.code_hash)?);
+    }
+
+    validate_account_nonce_and_code_with_components(&caller_info, context.tx(), context.cfg())?;
+    calculate_caller_fee(
+        caller_info.balance,
+        context.tx(),
+        context.block(),
+        context.cfg(),
+    )?;
+
+    Ok(gas)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::validate_tx_against_state;
     use crate::{api::ExecuteEvm, ExecuteCommitEvm, MainBuilder, MainContext};
     use bytecode::opcode;
     use context::{
         result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction, Output},
-        Context, ContextTr, TxEnv,
+        Context, ContextSetters, ContextTr, TxEnv,
     };
     use database::{CacheDB, EmptyDB};
-    use primitives::{address, eip3860, eip7907, hardfork::SpecId, Bytes, TxKind, B256};
+    use primitives::{address, eip3860, eip7907, hardfork::SpecId, Bytes, TxKind, B256, U256};
     use state::{AccountInfo, Bytecode};
 
     fn deploy_contract(
@@ -693,4 +736,54 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn test_validate_tx_against_state() {
+        let caller = address!("0x1000000000000000000000000000000000000001");
+        let mut db = CacheDB::<EmptyDB>::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                balance: U256::from(21_000 * 10),
+                nonce: 1,
+                ..Default::default()
+            },
+        );
+        let mut ctx = Context::mainnet().with_db(db);
+
+        let tx = |nonce, gas_price| {
+            TxEnv::builder()
+                .caller(caller)
+                .call(address!("0x2000000000000000000000000000000000000002"))
+                .nonce(nonce)
+                .gas_price(gas_price)
+                .gas_limit(21_000)
+                .build_fill()
+        };
+
+        ctx.set_tx(tx(1, 10));
+        let gas =
+            validate_tx_against_state::<_, EVMError<core::convert::Infallible>>(&mut ctx).unwrap();
+        assert_eq!(gas.initial_gas, 21_000);
+
+        ctx.set_tx(tx(0, 10));
+        assert!(matches!(
+            validate_tx_against_state::<_, EVMError<core::convert::Infallible>>(&mut ctx),
+            Err(EVMError::Transaction(InvalidTransaction::NonceTooLow {
+                tx: 0,
+                state: 1
+            }))
+        ));
+
+        ctx.set_tx(tx(1, 11));
+        assert!(matches!(
+            validate_tx_against_state::<_, EVMError<core::convert::Infallible>>(&mut ctx),
+            Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForMaxFee { .. }
+            ))
+        ));
+
+        // Validation does not load the caller into the journal.
+        assert!(ctx.journal_ref().state.is_empty());
+    }
 }
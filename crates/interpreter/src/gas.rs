@@ -3,9 +3,13 @@
 mod calc;
 mod constants;
 pub mod params;
+pub mod schedule;
 
 pub use calc::*;
 pub use constants::*;
+pub use context_interface::cfg::GasMode;
+
+use params::GasParams;
 
 /// Represents the state of gas during execution.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
@@ -19,6 +23,12 @@ pub struct Gas {
     refunded: i64,
     /// Memoisation of values for memory expansion cost.
     memory: MemoryGas,
+    /// Metering mode.
+    #[cfg_attr(feature = "serde", serde(default))]
+    mode: GasMode,
+    /// Gas spent above the limit in [`GasMode::Tracked`] mode.
+    #[cfg_attr(feature = "serde", serde(default))]
+    overrun: u64,
 }
 
 impl Gas {
@@ -30,6 +40,8 @@ impl Gas {
             remaining: limit,
             refunded: 0,
             memory: MemoryGas::new(),
+            mode: GasMode::Metered,
+            overrun: 0,
         }
     }
 
@@ -41,9 +53,30 @@ impl Gas {
             remaining: 0,
             refunded: 0,
             memory: MemoryGas::new(),
+            mode: GasMode::Metered,
+            overrun: 0,
         }
     }
 
+    /// Sets the metering mode.
+    #[inline]
+    pub const fn with_mode(mut self, mode: GasMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the metering mode.
+    #[inline]
+    pub const fn mode(&self) -> GasMode {
+        self.mode
+    }
+
+    /// Sets the metering mode.
+    #[inline]
+    pub fn set_mode(&mut self, mode: GasMode) {
+        self.mode = mode;
+    }
+
     /// Returns the gas limit.
     #[inline]
     pub const fn limit(&self) -> u64 {
@@ -68,12 +101,32 @@ impl Gas {
         self.refunded
     }
 
-    /// Returns the total amount of gas spent.
+    /// Returns the amount of gas spent within the limit.
+    ///
+    /// Gas spent above the limit is reported by [`overrun`](Self::overrun).
     #[inline]
     pub const fn spent(&self) -> u64 {
         self.limit - self.remaining
     }
 
+    /// Returns the gas spent above the limit in [`GasMode::Tracked`] mode.
+    #[inline]
+    pub const fn overrun(&self) -> u64 {
+        self.overrun
+    }
+
+    /// Returns the total amount of gas spent, including the [overrun](Self::overrun).
+    #[inline]
+    pub const fn total_spent(&self) -> u64 {
+        self.spent() + self.overrun
+    }
+
+    /// Records gas spent above the limit, for example by a child call.
+    #[inline]
+    pub fn record_overrun(&mut self, overrun: u64) {
+        self.overrun += overrun;
+    }
+
     /// Returns the final amount of gas used by subtracting the refund from spent gas.
     #[inline]
     pub const fn used(&self) -> u64 {
@@ -92,6 +145,18 @@ impl Gas {
         self.remaining
     }
 
+    /// Returns the amount of gas that can be spent without running out of gas.
+    ///
+    /// It is the remaining gas in [`GasMode::Metered`] mode and `u64::MAX` otherwise.
+    #[inline]
+    pub const fn available(&self) -> u64 {
+        if self.mode.is_metered() {
+            self.remaining
+        } else {
+            u64::MAX
+        }
+    }
+
     /// Erases a gas cost from the totals.
     #[inline]
     pub fn erase_cost(&mut self, returned: u64) {
@@ -131,6 +196,8 @@ impl Gas {
     }
 
     /// Set a spent value. This overrides the current spent value.
+    ///
+    /// The spent value is capped at the limit, the [overrun](Self::overrun) is kept.
     #[inline]
     pub fn set_spent(&mut self, spent: u64) {
         self.remaining = self.limit.saturating_sub(spent);
@@ -138,7 +205,8 @@ impl Gas {
 
     /// Records an explicit cost.
     ///
-    /// Returns `false` if the gas limit is exceeded.
+    /// Returns `false` if the gas limit is exceeded, this never happens if the mode is not
+    /// [`GasMode::Metered`].
     #[inline]
     #[must_use = "prefer using `gas!` instead to return an out-of-gas error on failure"]
     pub fn record_cost(&mut self, cost: u64) -> bool {
@@ -146,18 +214,40 @@ impl Gas {
             self.remaining = new_remaining;
             return true;
         }
-        false
+        self.exceed_limit(cost - self.remaining)
     }
 
     /// Records an explicit cost. In case of underflow the gas will wrap around cost.
     ///
-    /// Returns `true` if the gas limit is exceeded.
+    /// Returns `true` if the gas limit is exceeded, this never happens if the mode is not
+    /// [`GasMode::Metered`].
     #[inline(always)]
     #[must_use = "In case of not enough gas, the interpreter should halt with an out-of-gas error"]
     pub fn record_cost_unsafe(&mut self, cost: u64) -> bool {
         let oog = self.remaining < cost;
         self.remaining = self.remaining.wrapping_sub(cost);
-        oog
+        // Missing gas is the wrapped around part of the remaining gas.
+        oog && !self.exceed_limit(self.remaining.wrapping_neg())
+    }
+
+    /// Handles a cost that exceeds the remaining gas by `missing`.
+    ///
+    /// Returns `true` if the cost is recorded, which depends on the mode.
+    #[cold]
+    #[inline(never)]
+    fn exceed_limit(&mut self, missing: u64) -> bool {
+        match self.mode {
+            GasMode::Metered => false,
+            GasMode::Tracked => {
+                self.remaining = 0;
+                self.overrun += missing;
+                true
+            }
+            GasMode::Unmetered => {
+                self.remaining = 0;
+                true
+            }
+        }
     }
 }
 
@@ -207,21 +297,55 @@ impl MemoryGas {
 
     /// Records a new memory length and calculates additional cost if memory is expanded.
     /// Returns the additional gas cost required, or None if no expansion is needed.
+    ///
+    /// The cost is the difference between the [memory cost](GasParams::memory_cost) of the new and
+    /// the current length. Custom costs may not be monotonic, expansion to a cheaper length costs
+    /// nothing and keeps the current expansion cost.
     #[inline]
-    pub fn record_new_len(
-        &mut self,
-        new_num: usize,
-        linear_cost: u64,
-        quadratic_cost: u64,
-    ) -> Option<u64> {
+    pub fn record_new_len(&mut self, new_num: usize, gas_params: &GasParams) -> Option<u64> {
         if new_num <= self.words_num {
             return None;
         }
         self.words_num = new_num;
-        let mut cost = crate::gas::calc::memory_gas(new_num, linear_cost, quadratic_cost);
-        core::mem::swap(&mut self.expansion_cost, &mut cost);
-        // Safe to subtract because we know that new_len > length
-        // Notice the swap above.
-        Some(self.expansion_cost - cost)
+        let expansion_cost = gas_params.memory_cost(new_num);
+        let cost = expansion_cost.saturating_sub(self.expansion_cost);
+        self.expansion_cost = self.expansion_cost.max(expansion_cost);
+        Some(cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gas_modes() {
+        let mut metered = Gas::new(10);
+        assert!(!metered.record_cost(11));
+        assert!(metered.record_cost_unsafe(11));
+
+        let mut tracked = Gas::new(10).with_mode(GasMode::Tracked);
+        assert!(tracked.record_cost(6));
+        assert!(tracked.record_cost(6));
+        assert!(!tracked.record_cost_unsafe(3));
+        assert_eq!(tracked.remaining(), 0);
+        assert_eq!(tracked.overrun(), 5);
+        assert_eq!(tracked.spent(), 10);
+        assert_eq!(tracked.total_spent(), 15);
+        assert_eq!(tracked.available(), u64::MAX);
+
+        tracked.record_refund(4);
+        tracked.set_final_refund(true);
+        assert_eq!(tracked.refunded(), 2);
+        assert_eq!(tracked.used(), 8);
+
+        tracked.set_spent(8);
+        assert_eq!(tracked.overrun(), 5);
+        assert_eq!(tracked.spent(), 8);
+
+        let mut unmetered = Gas::new(10).with_mode(GasMode::Unmetered);
+        assert!(!unmetered.record_cost_unsafe(11));
+        assert!(unmetered.record_cost(11));
+        assert_eq!(unmetered.spent(), 10);
     }
 }
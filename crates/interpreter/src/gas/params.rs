@@ -5,6 +5,7 @@ use crate::{
     num_words,
 };
 use context_interface::context::SStoreResult;
+use core::{fmt, hash};
 use primitives::{
     hardfork::SpecId::{self},
     U256,
@@ -12,14 +13,157 @@ use primitives::{
 use std::sync::Arc;
 
 /// Gas table for dynamic gas constants.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+///
+/// Dynamic gas costs are computed from the table values with the mainnet formulas, or with
+/// custom [`GasCostFns`] if they are set.
+#[derive(Clone)]
 pub struct GasParams {
     /// Table of gas costs for operations
     table: Arc<[u64; 256]>,
     /// Pointer to the table.
     ptr: *const u64,
+    /// Custom formulas of the dynamic gas costs.
+    cost_fns: Option<Arc<dyn GasCostFns>>,
 }
 
+impl fmt::Debug for GasParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GasParams")
+            .field("table", &self.table)
+            .field("cost_fns", &self.cost_fns)
+            .finish()
+    }
+}
+
+impl PartialEq for GasParams {
+    fn eq(&self, other: &Self) -> bool {
+        let same_cost_fns = match (&self.cost_fns, &other.cost_fns) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        same_cost_fns && self.table == other.table
+    }
+}
+
+impl Eq for GasParams {}
+
+impl hash::Hash for GasParams {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.table.hash(state);
+        self.cost_fns.is_some().hash(state);
+    }
+}
+
+/// Formulas of the dynamic gas costs.
+///
+/// Each method computes a cost from the input of the operation and the values of the gas table,
+/// the default implementations are the mainnet formulas. Implement the methods that need to be
+/// repriced and set the implementation with [`GasParams::with_cost_fns`], the [`GasParams`]
+/// methods with the same name then use it.
+///
+/// ```rust
+/// use revm_interpreter::gas::params::{GasCostFns, GasParams, MainnetCostFns};
+/// use std::sync::Arc;
+///
+/// /// Doubles the memory expansion cost.
+/// #[derive(Debug)]
+/// struct DoubleMemoryCost;
+///
+/// impl GasCostFns for DoubleMemoryCost {
+///     fn memory_cost(&self, params: &GasParams, num_words: usize) -> u64 {
+///         MainnetCostFns.memory_cost(params, num_words) * 2
+///     }
+/// }
+///
+/// let mainnet = GasParams::default();
+/// let params = mainnet.clone().with_cost_fns(Arc::new(DoubleMemoryCost));
+/// assert_eq!(params.memory_cost(10), 2 * mainnet.memory_cost(10));
+/// assert_eq!(params.keccak256_cost(64), mainnet.keccak256_cost(64));
+/// ```
+pub trait GasCostFns: fmt::Debug + Send + Sync {
+    /// `EXP` cost for the exponent.
+    fn exp_cost(&self, params: &GasParams, power: U256) -> u64 {
+        if power.is_zero() {
+            return 0;
+        }
+        // EIP-160: EXP cost increase
+        params
+            .get(GasId::exp_byte_gas())
+            .saturating_mul(log2floor(power) / 8 + 1)
+    }
+
+    /// Cost of memory with the given number of words. Expansion is charged with the difference
+    /// between the new and the previous cost.
+    fn memory_cost(&self, params: &GasParams, num_words: usize) -> u64 {
+        let len = num_words as u64;
+        params
+            .get(GasId::memory_linear_cost())
+            .saturating_mul(len)
+            .saturating_add(
+                (len.saturating_mul(len))
+                    .saturating_div(params.get(GasId::memory_quadratic_reduction())),
+            )
+    }
+
+    /// Cost of copying words, used by `CALLDATACOPY`, `CODECOPY` and `RETURNDATACOPY`.
+    fn copy_per_word_cost(&self, params: &GasParams, word_num: usize) -> u64 {
+        params
+            .get(GasId::copy_per_word())
+            .saturating_mul(word_num as u64)
+    }
+
+    /// `EXTCODECOPY` cost for the copied length.
+    fn extcodecopy(&self, params: &GasParams, len: usize) -> u64 {
+        params
+            .get(GasId::extcodecopy_per_word())
+            .saturating_mul(num_words(len) as u64)
+    }
+
+    /// `MCOPY` cost for the copied length.
+    fn mcopy_cost(&self, params: &GasParams, len: usize) -> u64 {
+        params
+            .get(GasId::mcopy_per_word())
+            .saturating_mul(num_words(len) as u64)
+    }
+
+    /// Hashing cost for the length, used by `KECCAK256` and `CREATE2`.
+    fn keccak256_cost(&self, params: &GasParams, len: usize) -> u64 {
+        params
+            .get(GasId::keccak256_per_word())
+            .saturating_mul(num_words(len) as u64)
+    }
+
+    /// `LOG` cost for the number of topics and the data length.
+    fn log_cost(&self, params: &GasParams, n: u8, len: u64) -> u64 {
+        params
+            .get(GasId::logdata())
+            .saturating_mul(len)
+            .saturating_add(params.get(GasId::logtopic()) * n as u64)
+    }
+
+    /// Initcode cost for the length.
+    fn initcode_cost(&self, params: &GasParams, len: usize) -> u64 {
+        params
+            .get(GasId::initcode_per_word())
+            .saturating_mul(num_words(len) as u64)
+    }
+
+    /// Maximum gas that can be forwarded to a call from the remaining gas.
+    fn call_stipend_reduction(&self, params: &GasParams, gas_limit: u64) -> u64 {
+        gas_limit - gas_limit / params.get(GasId::call_stipend_reduction())
+    }
+}
+
+/// Mainnet formulas of the dynamic gas costs.
+///
+/// Used when [`GasParams`] has no custom [`GasCostFns`], custom implementations can call it to
+/// adjust the mainnet cost.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MainnetCostFns;
+
+impl GasCostFns for MainnetCostFns {}
+
 #[cfg(feature = "serde")]
 mod serde {
     use super::{Arc, GasParams};
@@ -70,9 +214,31 @@ impl GasParams {
         Self {
             ptr: table.as_ptr(),
             table,
+            cost_fns: None,
         }
     }
 
+    /// Sets the formulas of the dynamic gas costs.
+    ///
+    /// They are not serialized, deserialized `GasParams` use the mainnet formulas.
+    #[inline]
+    pub fn with_cost_fns(mut self, cost_fns: Arc<dyn GasCostFns>) -> Self {
+        self.set_cost_fns(Some(cost_fns));
+        self
+    }
+
+    /// Sets the formulas of the dynamic gas costs, `None` restores the mainnet formulas.
+    #[inline]
+    pub fn set_cost_fns(&mut self, cost_fns: Option<Arc<dyn GasCostFns>>) {
+        self.cost_fns = cost_fns;
+    }
+
+    /// Returns the custom formulas of the dynamic gas costs.
+    #[inline]
+    pub fn cost_fns(&self) -> Option<&Arc<dyn GasCostFns>> {
+        self.cost_fns.as_ref()
+    }
+
     /// Overrides the gas cost for the given gas id.
     ///
     /// It will clone underlying table and override the values.
@@ -93,7 +259,8 @@ impl GasParams {
         for (id, value) in values.into_iter() {
             table[id.as_usize()] = value;
         }
-        *self = Self::new(Arc::new(table));
+        self.table = Arc::new(table);
+        self.ptr = self.table.as_ptr();
     }
 
     /// Returns the table.
@@ -191,12 +358,10 @@ impl GasParams {
     /// `EXP` opcode cost calculation.
     #[inline]
     pub fn exp_cost(&self, power: U256) -> u64 {
-        if power.is_zero() {
-            return 0;
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.exp_cost(self, power);
         }
-        // EIP-160: EXP cost increase
-        self.get(GasId::exp_byte_gas())
-            .saturating_mul(log2floor(power) / 8 + 1)
+        MainnetCostFns.exp_cost(self, power)
     }
 
     /// Selfdestruct refund.
@@ -236,15 +401,19 @@ impl GasParams {
     /// EXTCODECOPY gas cost
     #[inline]
     pub fn extcodecopy(&self, len: usize) -> u64 {
-        self.get(GasId::extcodecopy_per_word())
-            .saturating_mul(num_words(len) as u64)
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.extcodecopy(self, len);
+        }
+        MainnetCostFns.extcodecopy(self, len)
     }
 
     /// MCOPY gas cost
     #[inline]
     pub fn mcopy_cost(&self, len: usize) -> u64 {
-        self.get(GasId::mcopy_per_word())
-            .saturating_mul(num_words(len) as u64)
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.mcopy_cost(self, len);
+        }
+        MainnetCostFns.mcopy_cost(self, len)
     }
 
     /// Static gas cost for SSTORE opcode
@@ -363,36 +532,38 @@ impl GasParams {
 
     /// `LOG` opcode cost calculation.
     #[inline]
-    pub const fn log_cost(&self, n: u8, len: u64) -> u64 {
-        self.get(GasId::logdata())
-            .saturating_mul(len)
-            .saturating_add(self.get(GasId::logtopic()) * n as u64)
+    pub fn log_cost(&self, n: u8, len: u64) -> u64 {
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.log_cost(self, n, len);
+        }
+        MainnetCostFns.log_cost(self, n, len)
     }
 
     /// KECCAK256 gas cost per word
     #[inline]
     pub fn keccak256_cost(&self, len: usize) -> u64 {
-        self.get(GasId::keccak256_per_word())
-            .saturating_mul(num_words(len) as u64)
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.keccak256_cost(self, len);
+        }
+        MainnetCostFns.keccak256_cost(self, len)
     }
 
     /// Memory gas cost
     #[inline]
     pub fn memory_cost(&self, len: usize) -> u64 {
-        let len = len as u64;
-        self.get(GasId::memory_linear_cost())
-            .saturating_mul(len)
-            .saturating_add(
-                (len.saturating_mul(len))
-                    .saturating_div(self.get(GasId::memory_quadratic_reduction())),
-            )
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.memory_cost(self, len);
+        }
+        MainnetCostFns.memory_cost(self, len)
     }
 
     /// Initcode word cost
     #[inline]
     pub fn initcode_cost(&self, len: usize) -> u64 {
-        self.get(GasId::initcode_per_word())
-            .saturating_mul(num_words(len) as u64)
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.initcode_cost(self, len);
+        }
+        MainnetCostFns.initcode_cost(self, len)
     }
 
     /// Create gas cost
@@ -404,10 +575,7 @@ impl GasParams {
     /// Create2 gas cost.
     #[inline]
     pub fn create2_cost(&self, len: usize) -> u64 {
-        self.get(GasId::create()).saturating_add(
-            self.get(GasId::keccak256_per_word())
-                .saturating_mul(num_words(len) as u64),
-        )
+        self.create_cost().saturating_add(self.keccak256_cost(len))
     }
 
     /// Call stipend.
@@ -419,7 +587,10 @@ impl GasParams {
     /// Call stipend reduction. Call stipend is reduced by 1/64 of the gas limit.
     #[inline]
     pub fn call_stipend_reduction(&self, gas_limit: u64) -> u64 {
-        gas_limit - gas_limit / self.get(GasId::call_stipend_reduction())
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.call_stipend_reduction(self, gas_limit);
+        }
+        MainnetCostFns.call_stipend_reduction(self, gas_limit)
     }
 
     /// Transfer value cost
@@ -479,8 +650,10 @@ impl GasParams {
     /// Copy per word cost
     #[inline]
     pub fn copy_per_word_cost(&self, word_num: usize) -> u64 {
-        self.get(GasId::copy_per_word())
-            .saturating_mul(word_num as u64)
+        if let Some(cost_fns) = &self.cost_fns {
+            return cost_fns.copy_per_word_cost(self, word_num);
+        }
+        MainnetCostFns.copy_per_word_cost(self, word_num)
     }
 }
 
@@ -779,4 +952,37 @@ mod tests {
             unique_names.len()
         );
     }
+
+    #[test]
+    fn custom_cost_fns() {
+        /// Charges memory linearly and makes `EXP` free.
+        #[derive(Debug)]
+        struct CostFns;
+
+        impl GasCostFns for CostFns {
+            fn memory_cost(&self, params: &GasParams, num_words: usize) -> u64 {
+                params.get(GasId::memory_linear_cost()) * num_words as u64
+            }
+
+            fn exp_cost(&self, _params: &GasParams, _power: U256) -> u64 {
+                0
+            }
+        }
+
+        let mainnet = GasParams::new_spec(SpecId::PRAGUE);
+        let mut params = mainnet.clone().with_cost_fns(Arc::new(CostFns));
+        assert_ne!(params, mainnet);
+        assert_eq!(params.memory_cost(1024), 3 * 1024);
+        assert_eq!(mainnet.memory_cost(1024), 3 * 1024 + 1024 * 1024 / 512);
+        assert_eq!(params.exp_cost(U256::MAX), 0);
+        assert_eq!(params.copy_cost(64), mainnet.copy_cost(64));
+
+        // Overridden values are used by the custom formulas.
+        params.override_gas([(GasId::memory_linear_cost(), 5)]);
+        assert_eq!(params.memory_cost(10), 50);
+        assert!(params.cost_fns().is_some());
+
+        params.set_cost_fns(None);
+        assert_eq!(params.memory_cost(1024), 5 * 1024 + 1024 * 1024 / 512);
+    }
 }
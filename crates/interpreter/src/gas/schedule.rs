@@ -0,0 +1,152 @@
+//! Gas schedules that can be loaded from files.
+
+use super::params::{GasId, GasParams};
+use core::fmt;
+use primitives::hardfork::SpecId;
+use std::{collections::BTreeMap, string::String, vec::Vec};
+
+/// Gas table values keyed by their [`GasId::name`].
+///
+/// Schedule is applied on top of the [`GasParams`] of a spec so it only needs to contain the
+/// repriced values. With the `serde` feature it (de)serializes as a map, for example in JSON:
+///
+/// ```json
+/// { "memory_linear_cost": 6, "keccak256_per_word": 12 }
+/// ```
+///
+/// Formulas of the dynamic gas costs are not part of the schedule, see
+/// [`GasCostFns`](super::params::GasCostFns).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct GasSchedule {
+    /// Gas values keyed by name.
+    pub gas: BTreeMap<String, u64>,
+}
+
+/// Error of applying a [`GasSchedule`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GasScheduleError {
+    /// Name does not match any [`GasId`].
+    UnknownGas(String),
+    /// Gas is used as a divisor and can't be zero.
+    ZeroDivisor(GasId),
+}
+
+impl core::error::Error for GasScheduleError {}
+
+impl fmt::Display for GasScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownGas(name) => write!(f, "unknown gas `{name}`"),
+            Self::ZeroDivisor(id) => write!(f, "gas `{}` can't be zero", id.name()),
+        }
+    }
+}
+
+impl GasSchedule {
+    /// Creates an empty schedule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a schedule with all named values of the gas params.
+    pub fn from_params(params: &GasParams) -> Self {
+        let gas = (0..=u8::MAX)
+            .map(GasId::new)
+            .filter(|id| id.name() != "unknown")
+            .map(|id| (id.name().into(), params.get(id)))
+            .collect();
+        Self { gas }
+    }
+
+    /// Sets the gas value.
+    pub fn with_gas(mut self, id: GasId, value: u64) -> Self {
+        self.gas.insert(id.name().into(), value);
+        self
+    }
+
+    /// Overrides the values of the gas params with the values of the schedule.
+    ///
+    /// Gas params are left unchanged if the schedule is invalid.
+    pub fn apply(&self, params: &mut GasParams) -> Result<(), GasScheduleError> {
+        let mut values = Vec::with_capacity(self.gas.len());
+        for (name, value) in &self.gas {
+            let id =
+                GasId::from_name(name).ok_or_else(|| GasScheduleError::UnknownGas(name.clone()))?;
+            if *value == 0
+                && (id == GasId::memory_quadratic_reduction()
+                    || id == GasId::call_stipend_reduction())
+            {
+                return Err(GasScheduleError::ZeroDivisor(id));
+            }
+            values.push((id, *value));
+        }
+        params.override_gas(values);
+        Ok(())
+    }
+
+    /// Returns the gas params of the spec with the values of the schedule.
+    pub fn gas_params(&self, spec: SpecId) -> Result<GasParams, GasScheduleError> {
+        let mut params = GasParams::new_spec(spec);
+        self.apply(&mut params)?;
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_schedule() {
+        let schedule = GasSchedule::new()
+            .with_gas(GasId::memory_linear_cost(), 6)
+            .with_gas(GasId::keccak256_per_word(), 12);
+        let params = schedule.gas_params(SpecId::PRAGUE).unwrap();
+        assert_eq!(params.memory_cost(1), 6);
+        assert_eq!(params.keccak256_cost(64), 24);
+        assert_eq!(
+            params.get(GasId::create()),
+            GasParams::default().get(GasId::create())
+        );
+
+        let mainnet = GasParams::new_spec(SpecId::PRAGUE);
+        let mut params = mainnet.clone();
+        GasSchedule::from_params(&mainnet)
+            .apply(&mut params)
+            .unwrap();
+        assert_eq!(params, mainnet);
+
+        let mut schedule = GasSchedule::new();
+        schedule.gas.insert("unknown".into(), 1);
+        assert_eq!(
+            schedule.apply(&mut params),
+            Err(GasScheduleError::UnknownGas("unknown".into()))
+        );
+        assert_eq!(
+            GasSchedule::new()
+                .with_gas(GasId::memory_quadratic_reduction(), 0)
+                .apply(&mut params),
+            Err(GasScheduleError::ZeroDivisor(
+                GasId::memory_quadratic_reduction()
+            ))
+        );
+        assert_eq!(params, mainnet);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn load_schedule() {
+        let schedule: GasSchedule =
+            serde_json::from_str(r#"{ "exp_byte_gas": 25, "call_stipend_reduction": 32 }"#)
+                .unwrap();
+        let params = schedule.gas_params(SpecId::PRAGUE).unwrap();
+        assert_eq!(params.exp_cost(primitives::U256::from(0x100)), 50);
+        assert_eq!(params.call_stipend_reduction(64), 62);
+        assert_eq!(
+            serde_json::to_string(&schedule).unwrap(),
+            r#"{"call_stipend_reduction":32,"exp_byte_gas":25}"#
+        );
+    }
+}
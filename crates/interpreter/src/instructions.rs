@@ -12,6 +12,8 @@ pub mod block_info;
 pub mod contract;
 /// Control flow instructions (JUMP, JUMPI, REVERT, etc.).
 pub mod control;
+/// Fused instructions executing hot instruction sequences.
+pub mod fused;
 /// Host environment interactions (SLOAD, SSTORE, LOG, etc.).
 pub mod host;
 /// Signed 256-bit integer operations.
@@ -83,6 +85,66 @@ pub const fn instruction_table<WIRE: InterpreterTypes, H: Host>() -> [Instructio
     const { instruction_table_impl::<WIRE, H>() }
 }
 
+/// Returns `true` if the opcode has the default implementation and static gas in the table.
+///
+/// Functions are compared by address, so an instruction can be conservatively reported as
+/// customised if the compiler duplicated its function.
+#[inline]
+pub fn is_default_instruction<WIRE: InterpreterTypes, H: Host>(
+    table: &InstructionTable<WIRE, H>,
+    opcode: u8,
+) -> bool {
+    let default = instruction_table::<WIRE, H>()[opcode as usize];
+    let instruction = &table[opcode as usize];
+    instruction.static_gas == default.static_gas
+        && core::ptr::fn_addr_eq(instruction.fn_, default.fn_)
+}
+
+/// Returns the instruction table extended with handlers for fused opcodes.
+///
+/// It must only be used to execute [fused bytecode](bytecode::LegacyAnalyzedBytecode::with_fusion),
+/// fused opcodes are not valid in regular bytecode. Static gas of a fused opcode is the static gas
+/// of the first instruction of the sequence, the rest is charged by the handler.
+///
+/// Fused handlers execute the default implementation and static gas of every instruction of the
+/// sequence. Returns `None` if any of those instructions was customised in `table`, in which case
+/// fusion must stay disabled, see [`is_default_instruction`].
+pub fn fused_instruction_table<WIRE: InterpreterTypes, H: Host>(
+    table: &InstructionTable<WIRE, H>,
+) -> Option<InstructionTable<WIRE, H>> {
+    use bytecode::{legacy::fusion::*, opcode::*};
+    if ![PUSH1, PUSH2, PUSH4, JUMP, JUMPI, SWAP1, POP, DUP1, EQ]
+        .into_iter()
+        .all(|op| is_default_instruction(table, op))
+    {
+        return None;
+    }
+
+    let mut fused_table = *table;
+    let static_gas = |op: u8| table[op as usize].static_gas();
+
+    fused_table[FUSED_PUSH1_JUMP as usize] = Instruction::new(fused::push1_jump, static_gas(PUSH1));
+    fused_table[FUSED_PUSH2_JUMP as usize] = Instruction::new(fused::push2_jump, static_gas(PUSH2));
+    fused_table[FUSED_PUSH1_JUMPI as usize] =
+        Instruction::new(fused::push1_jumpi, static_gas(PUSH1));
+    fused_table[FUSED_PUSH2_JUMPI as usize] =
+        Instruction::new(fused::push2_jumpi, static_gas(PUSH2));
+    fused_table[FUSED_SWAP1_POP as usize] = Instruction::new(fused::swap1_pop, static_gas(SWAP1));
+    fused_table[FUSED_DISPATCH as usize] = Instruction::new(fused::dispatch, static_gas(DUP1));
+    Some(fused_table)
+}
+
+/// Returns the static gas cost of every opcode in the instruction table.
+///
+/// Used to compute [`BasicBlocks`](bytecode::BasicBlocks) for
+/// [`Interpreter::run_basic_blocks`](crate::Interpreter::run_basic_blocks).
+#[inline]
+pub fn static_gas_table<WIRE: InterpreterTypes, H: Host + ?Sized>(
+    table: &InstructionTable<WIRE, H>,
+) -> [u64; 256] {
+    core::array::from_fn(|i| table[i].static_gas())
+}
+
 /// Create a instruction table with applied spec changes to static gas cost.
 #[inline]
 pub fn instruction_table_gas_changes_spec<WIRE: InterpreterTypes, H: Host>(
@@ -1,4 +1,7 @@
-use super::i256::{i256_div, i256_mod};
+use super::{
+    i256::{i256_div, i256_mod},
+    utility::as_u64_pair,
+};
 use crate::{
     interpreter_types::{InterpreterTypes, StackTr},
     InstructionContext,
@@ -27,7 +30,10 @@ pub fn sub<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H,
 pub fn div<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
     popn_top!([op1], op2, context.interpreter);
     if !op2.is_zero() {
-        *op2 = op1.wrapping_div(*op2);
+        *op2 = match as_u64_pair(&op1, op2) {
+            Some((a, b)) => U256::from(a / b),
+            None => op1.wrapping_div(*op2),
+        };
     }
 }
 
@@ -45,7 +51,10 @@ pub fn sdiv<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H
 pub fn rem<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
     popn_top!([op1], op2, context.interpreter);
     if !op2.is_zero() {
-        *op2 = op1.wrapping_rem(*op2);
+        *op2 = match as_u64_pair(&op1, op2) {
+            Some((a, b)) => U256::from(a % b),
+            None => op1.wrapping_rem(*op2),
+        };
     }
 }
 
@@ -123,3 +132,56 @@ pub fn signextend<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext
         *x = if bit { *x | !mask } else { *x & mask };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{div, rem};
+    use crate::{host::DummyHost, interpreter::EthInterpreter, InstructionContext, Interpreter};
+    use primitives::U256;
+
+    type Instruction = fn(InstructionContext<'_, DummyHost, EthInterpreter>);
+
+    /// Executes the instruction with `a` on top of the stack and `b` below it.
+    fn run(instruction: Instruction, a: U256, b: U256) -> U256 {
+        let mut interpreter = Interpreter::default();
+        assert!(interpreter.stack.push(b));
+        assert!(interpreter.stack.push(a));
+        instruction(InstructionContext {
+            host: &mut DummyHost,
+            interpreter: &mut interpreter,
+        });
+        interpreter.stack.pop().unwrap()
+    }
+
+    #[test]
+    fn test_u64_fast_path_boundaries() {
+        let max = U256::from(u64::MAX);
+
+        // Division and remainder by zero are zero.
+        assert_eq!(run(div, max, U256::ZERO), U256::ZERO);
+        assert_eq!(run(rem, max, U256::ZERO), U256::ZERO);
+        assert_eq!(run(div, max, U256::from(2)), U256::from(u64::MAX / 2));
+        assert_eq!(run(rem, max, U256::from(2)), U256::from(1));
+    }
+
+    #[test]
+    fn test_u64_fast_path_matches_u256() {
+        let values = [
+            U256::ZERO,
+            U256::from(1),
+            U256::from(2),
+            U256::from(u64::MAX - 1),
+            U256::from(u64::MAX),
+            U256::from(1) << 64,
+            U256::MAX,
+        ];
+        let div_or_zero = |a: U256, b: U256| if b.is_zero() { b } else { a.wrapping_div(b) };
+        let rem_or_zero = |a: U256, b: U256| if b.is_zero() { b } else { a.wrapping_rem(b) };
+        for a in values {
+            for b in values {
+                assert_eq!(run(div, a, b), div_or_zero(a, b), "{a} / {b}");
+                assert_eq!(run(rem, a, b), rem_or_zero(a, b), "{a} % {b}");
+            }
+        }
+    }
+}
@@ -147,6 +147,7 @@ mod tests {
     use crate::{
         host::DummyHost,
         instructions::bitwise::{byte, clz, sar, shl, shr},
+        interpreter::EthInterpreter,
         InstructionContext, Interpreter,
     };
     use primitives::{hardfork::SpecId, uint, U256};
@@ -425,6 +426,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shift_boundaries() {
+        let mut interpreter = Interpreter::default();
+        type Shift = fn(InstructionContext<'_, DummyHost, EthInterpreter>);
+
+        let value = U256::from(0xdead_beef_u64 << 32);
+        for shift in [0usize, 1, 31, 63, 64, 65, 255, 256, 1 << 32, usize::MAX] {
+            let (left, right) = if shift < 256 {
+                (value << shift, value >> shift)
+            } else {
+                (U256::ZERO, U256::ZERO)
+            };
+            let cases: [(Shift, U256); 3] = [(shl, left), (shr, right), (sar, right)];
+            for (op, expected) in cases {
+                push!(interpreter, value);
+                push!(interpreter, U256::from(shift));
+                op(InstructionContext {
+                    host: &mut DummyHost,
+                    interpreter: &mut interpreter,
+                });
+                assert_eq!(interpreter.stack.pop().unwrap(), expected, "shift {shift}");
+            }
+        }
+    }
+
     #[test]
     fn test_byte() {
         struct TestCase {
@@ -106,7 +106,7 @@ pub fn load_account_delegated_handle_error<H: Host + ?Sized>(
     create_empty_account: bool,
 ) -> Option<(u64, Bytecode, B256)> {
     // move this to static gas.
-    let remaining_gas = context.interpreter.gas.remaining();
+    let remaining_gas = context.interpreter.gas.available();
     let gas_table = &context.interpreter.gas_params;
     match load_account_delegated(
         context.host,
@@ -0,0 +1,180 @@
+use super::{bitwise, control, stack};
+use crate::{
+    gas,
+    interpreter::Interpreter,
+    interpreter_types::{InterpreterTypes, Jumps, LoopControl},
+    InstructionContext,
+};
+
+/// Executes the next instruction of a fused sequence the same way [`Interpreter::step`] does.
+///
+/// Fused handlers use the default instruction and static gas, the
+/// [fused table](crate::instructions::fused_instruction_table) is not built if they are customised.
+///
+/// Returns `false` if the execution stopped.
+#[inline(always)]
+fn step<WIRE: InterpreterTypes, H: ?Sized>(
+    interpreter: &mut Interpreter<WIRE>,
+    host: &mut H,
+    static_gas: u64,
+    instruction: fn(InstructionContext<'_, H, WIRE>),
+) -> bool {
+    if interpreter.bytecode.is_end() {
+        return false;
+    }
+    interpreter.bytecode.relative_jump(1);
+    if interpreter.gas.record_cost_unsafe(static_gas) {
+        interpreter.halt_oog();
+        return false;
+    }
+    instruction(InstructionContext { interpreter, host });
+    true
+}
+
+/// Fused `PUSH1 x JUMP`.
+pub fn push1_jump<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
+    let InstructionContext { interpreter, host } = context;
+    stack::push::<1, _, _>(InstructionContext { interpreter, host });
+    step(interpreter, host, gas::MID, control::jump);
+}
+
+/// Fused `PUSH2 x JUMP`.
+pub fn push2_jump<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
+    let InstructionContext { interpreter, host } = context;
+    stack::push::<2, _, _>(InstructionContext { interpreter, host });
+    step(interpreter, host, gas::MID, control::jump);
+}
+
+/// Fused `PUSH1 x JUMPI`.
+pub fn push1_jumpi<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
+    let InstructionContext { interpreter, host } = context;
+    stack::push::<1, _, _>(InstructionContext { interpreter, host });
+    step(interpreter, host, gas::HIGH, control::jumpi);
+}
+
+/// Fused `PUSH2 x JUMPI`.
+pub fn push2_jumpi<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
+    let InstructionContext { interpreter, host } = context;
+    stack::push::<2, _, _>(InstructionContext { interpreter, host });
+    step(interpreter, host, gas::HIGH, control::jumpi);
+}
+
+/// Fused `SWAP1 POP`.
+pub fn swap1_pop<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
+    let InstructionContext { interpreter, host } = context;
+    stack::swap::<1, _, _>(InstructionContext { interpreter, host });
+    step(interpreter, host, gas::BASE, stack::pop);
+}
+
+/// Fused function dispatch `DUP1 PUSH4 selector EQ PUSH2 target JUMPI`.
+pub fn dispatch<WIRE: InterpreterTypes, H: ?Sized>(context: InstructionContext<'_, H, WIRE>) {
+    let InstructionContext { interpreter, host } = context;
+    stack::dup::<1, _, _>(InstructionContext { interpreter, host });
+    let _ = step(interpreter, host, gas::VERYLOW, stack::push::<4, _, _>)
+        && step(interpreter, host, gas::VERYLOW, bitwise::eq)
+        && step(interpreter, host, gas::VERYLOW, stack::push::<2, _, _>)
+        && step(interpreter, host, gas::HIGH, control::jumpi);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        gas::params::GasParams,
+        host::DummyHost,
+        instructions::{fused_instruction_table, instruction_table},
+        interpreter::{EthInterpreter, ExtBytecode, InputsImpl, SharedMemory},
+        Instruction, Interpreter,
+    };
+    use bytecode::{opcode, Bytecode};
+    use primitives::{hardfork::SpecId, Bytes};
+
+    #[test]
+    fn fused_matches_plain() {
+        // Dispatches on selector 0x01020304 and loops through `PUSH1 JUMP` and `SWAP1 POP`.
+        let code = Bytes::from_static(&[
+            opcode::PUSH4,
+            0x01,
+            0x02,
+            0x03,
+            0x04,
+            opcode::DUP1,
+            opcode::PUSH4,
+            0x01,
+            0x02,
+            0x03,
+            0x04,
+            opcode::EQ,
+            opcode::PUSH2,
+            0x00,
+            0x13,
+            opcode::JUMPI,
+            opcode::INVALID,
+            opcode::INVALID,
+            opcode::INVALID,
+            opcode::JUMPDEST, // 0x13
+            opcode::PUSH1,
+            0x07,
+            opcode::SWAP1,
+            opcode::POP,
+            opcode::PUSH1,
+            0x1b,
+            opcode::JUMP,
+            opcode::JUMPDEST, // 0x1b
+            opcode::STOP,
+        ]);
+        let bytecode = Bytecode::new_raw(code).with_fusion();
+        assert!(bytecode.legacy_fused_bytecode().is_some());
+
+        let table = instruction_table::<EthInterpreter, DummyHost>();
+        let fused_table = fused_instruction_table(&table).unwrap();
+
+        let run = |gas_limit, fused| {
+            let mut ext_bytecode = ExtBytecode::new(bytecode.clone());
+            if fused {
+                assert!(ext_bytecode.use_fused());
+            }
+            let mut interpreter = Interpreter::<EthInterpreter>::new(
+                SharedMemory::new(),
+                ext_bytecode,
+                InputsImpl::default(),
+                false,
+                SpecId::default(),
+                gas_limit,
+                GasParams::default(),
+            );
+            let instructions = if fused { &fused_table } else { &table };
+            let action = interpreter.run_plain(instructions, &mut DummyHost);
+            (
+                action.instruction_result(),
+                interpreter.gas.remaining(),
+                interpreter.stack.data().to_vec(),
+            )
+        };
+
+        for gas_limit in 0..100 {
+            assert_eq!(
+                run(gas_limit, true),
+                run(gas_limit, false),
+                "gas limit {gas_limit}"
+            );
+        }
+    }
+
+    #[test]
+    fn fusion_disabled_for_customised_table() {
+        let table = instruction_table::<EthInterpreter, DummyHost>();
+        assert!(fused_instruction_table(&table).is_some());
+
+        let mut repriced = table;
+        repriced[opcode::JUMP as usize] = Instruction::new(super::control::jump, 1);
+        assert!(fused_instruction_table(&repriced).is_none());
+
+        let mut replaced = table;
+        replaced[opcode::POP as usize] = Instruction::new(super::control::stop, 2);
+        assert!(fused_instruction_table(&replaced).is_none());
+
+        let mut unrelated = table;
+        unrelated[opcode::ADD as usize] = Instruction::new(super::control::stop, 3);
+        assert!(fused_instruction_table(&unrelated).is_some());
+    }
+}
@@ -199,7 +199,7 @@ pub fn sload<WIRE: InterpreterTypes, H: Host + ?Sized>(context: InstructionConte
             .interpreter
             .gas_params
             .cold_storage_additional_cost();
-        let skip_cold = context.interpreter.gas.remaining() < additional_cold_cost;
+        let skip_cold = context.interpreter.gas.available() < additional_cold_cost;
         let res = context.host.sload_skip_cold_load(target, *index, skip_cold);
         match res {
             Ok(storage) => {
@@ -233,7 +233,7 @@ pub fn sstore<WIRE: InterpreterTypes, H: Host + ?Sized>(context: InstructionCont
     // EIP-2200: Structured Definitions for Net Gas Metering
     // If gasleft is less than or equal to gas stipend, fail the current call frame with ‘out of gas’ exception.
     if spec_id.is_enabled_in(ISTANBUL)
-        && context.interpreter.gas.remaining() <= context.interpreter.gas_params.call_stipend()
+        && context.interpreter.gas.available() <= context.interpreter.gas_params.call_stipend()
     {
         context
             .interpreter
@@ -251,7 +251,7 @@ pub fn sstore<WIRE: InterpreterTypes, H: Host + ?Sized>(context: InstructionCont
             .interpreter
             .gas_params
             .cold_storage_additional_cost();
-        let skip_cold = context.interpreter.gas.remaining() < additional_cold_cost;
+        let skip_cold = context.interpreter.gas.available() < additional_cold_cost;
         let res = context
             .host
             .sstore_skip_cold_load(target, index, value, skip_cold);
@@ -360,7 +360,7 @@ pub fn selfdestruct<WIRE: InterpreterTypes, H: Host + ?Sized>(
 
     let cold_load_gas = context.interpreter.gas_params.selfdestruct_cold_cost();
 
-    let skip_cold_load = context.interpreter.gas.remaining() < cold_load_gas;
+    let skip_cold_load = context.interpreter.gas.available() < cold_load_gas;
     let res = match context.host.selfdestruct(
         context.interpreter.input.target_address(),
         target,
@@ -87,9 +87,33 @@ pub fn i256_cmp(first: &U256, second: &U256) -> Ordering {
     }
 }
 
+/// Returns the value as `i64` if it fits in 64 bits.
+#[inline]
+fn i256_as_i64(val: &U256) -> Option<i64> {
+    let [val, high @ ..] = *val.as_limbs();
+    // Sign extension of the lowest limb.
+    let ext = ((val as i64) >> 63) as u64;
+    (high == [ext; 3]).then_some(val as i64)
+}
+
+/// Converts `i128` into its 256-bit two's complement representation.
+#[inline]
+fn i256_from_i128(val: i128) -> U256 {
+    let ext = (val >> 127) as u64;
+    U256::from_limbs([val as u64, (val >> 64) as u64, ext, ext])
+}
+
 /// Performs signed division of two 256-bit integers.
 #[inline]
 pub fn i256_div(mut first: U256, mut second: U256) -> U256 {
+    if let (Some(first), Some(second)) = (i256_as_i64(&first), i256_as_i64(&second)) {
+        // Dividing in `i128` can't overflow, `i64::MIN / -1` does not fit in `i64`.
+        return match second {
+            0 => U256::ZERO,
+            _ => i256_from_i128(first as i128 / second as i128),
+        };
+    }
+
     let second_sign = i256_sign_compl(&mut second);
     if second_sign == Sign::Zero {
         return U256::ZERO;
@@ -120,6 +144,14 @@ pub fn i256_div(mut first: U256, mut second: U256) -> U256 {
 /// Performs signed modulo of two 256-bit integers.
 #[inline]
 pub fn i256_mod(mut first: U256, mut second: U256) -> U256 {
+    if let (Some(first), Some(second)) = (i256_as_i64(&first), i256_as_i64(&second)) {
+        // Remainder has the sign of the dividend, same as in EVM.
+        return match second {
+            0 => U256::ZERO,
+            _ => i256_from_i128(first as i128 % second as i128),
+        };
+    }
+
     let first_sign = i256_sign_compl(&mut first);
     if first_sign == Sign::Zero {
         return U256::ZERO;
@@ -164,6 +196,29 @@ mod tests {
             assert_eq!(i256_div(100_U256, 2_U256), 50_U256);
         }
     }
+    #[test]
+    fn i64_operands() {
+        uint! {
+            assert_eq!(i256_div(-7_U256, 2_U256), -3_U256);
+            assert_eq!(i256_div(7_U256, -2_U256), -3_U256);
+            assert_eq!(i256_div(-7_U256, -2_U256), 3_U256);
+            assert_eq!(i256_div(-7_U256, 0_U256), 0_U256);
+            assert_eq!(i256_mod(-7_U256, 3_U256), -1_U256);
+            assert_eq!(i256_mod(7_U256, -3_U256), 1_U256);
+            assert_eq!(i256_mod(-7_U256, 0_U256), 0_U256);
+
+            // Quotient does not fit in `i64`.
+            let i64_min = -0x8000000000000000_U256;
+            assert_eq!(i256_as_i64(&i64_min), Some(i64::MIN));
+            assert_eq!(i256_div(i64_min, -1_U256), 0x8000000000000000_U256);
+            assert_eq!(i256_mod(i64_min, -1_U256), 0_U256);
+
+            // Operands that don't fit in `i64` take the full path.
+            assert_eq!(i256_as_i64(&0x8000000000000000_U256), None);
+            assert_eq!(i256_div(0x8000000000000000_U256, -1_U256), i64_min);
+        }
+    }
+
     #[test]
     fn test_i256_sign() {
         uint! {
@@ -55,7 +55,7 @@ macro_rules! berlin_load_account {
             .interpreter
             .gas_params
             .cold_account_additional_cost();
-        let skip_cold_load = $context.interpreter.gas.remaining() < cold_load_gas;
+        let skip_cold_load = $context.interpreter.gas.available() < cold_load_gas;
         match $context
             .host
             .load_account_info_skip_cold_load($address, $load_code, skip_cold_load)
@@ -30,6 +30,16 @@ impl IntoAddress for U256 {
     }
 }
 
+/// Returns both values as `u64` if they fit in 64 bits.
+///
+/// Used by the fast paths of the division instructions, operands usually are small numbers.
+#[inline(always)]
+pub(crate) fn as_u64_pair(a: &U256, b: &U256) -> Option<(u64, u64)> {
+    let [a0, a1, a2, a3] = *a.as_limbs();
+    let [b0, b1, b2, b3] = *b.as_limbs();
+    ((a1 | a2 | a3 | b1 | b2 | b3) == 0).then_some((a0, b0))
+}
+
 #[cfg(test)]
 mod tests {
     use primitives::address;
@@ -166,7 +166,9 @@ impl<EXT: Default> Interpreter<EthInterpreter<EXT>> {
 
     /// Sets the specid for the interpreter.
     pub fn set_spec_id(&mut self, spec_id: SpecId) {
+        let cost_fns = self.gas_params.cost_fns().cloned();
         self.gas_params = GasParams::new_spec(spec_id);
+        self.gas_params.set_cost_fns(cost_fns);
         self.runtime_flag.spec_id = spec_id;
     }
 }
@@ -348,6 +350,113 @@ impl<IW: InterpreterTypes> Interpreter<IW> {
         }
         self.take_next_action()
     }
+
+    /// Executes the interpreter until it returns or stops without charging static gas.
+    ///
+    /// Fast path for [`GasMode::Unmetered`](crate::gas::GasMode::Unmetered), dynamic gas is
+    /// still recorded by the instructions but it never runs out.
+    #[inline]
+    pub fn run_unmetered<H: Host + ?Sized>(
+        &mut self,
+        instruction_table: &InstructionTable<IW, H>,
+        host: &mut H,
+    ) -> InterpreterAction {
+        while self.bytecode.is_not_end() {
+            self.step_precharged(instruction_table, host);
+        }
+        self.take_next_action()
+    }
+
+    /// Executes the interpreter until it returns or stops, charging static gas once per basic block.
+    ///
+    /// Static gas of a block is precharged and the stack height is checked when the block is
+    /// entered. The check guarantees that no instruction of the block fails on the stack, the
+    /// instructions keep their own stack bounds checks. If the gas left does not cover the block
+    /// or the stack would underflow or overflow, the block is executed instruction by instruction.
+    /// If an instruction halts in the middle of a block, the static gas of the instructions after
+    /// it is returned. Gas and outcome are the same as with [`run_plain`](Self::run_plain).
+    ///
+    /// Bytecode needs to contain [`BasicBlocks`](bytecode::BasicBlocks) computed with the static
+    /// gas of `instruction_table`, see [`static_gas_table`](crate::instructions::static_gas_table).
+    /// Falls back to [`run_plain`](Self::run_plain) if it does not. Only
+    /// [`GasMode::Metered`](crate::gas::GasMode::Metered) is supported.
+    #[inline]
+    pub fn run_basic_blocks<H: Host + ?Sized>(
+        &mut self,
+        instruction_table: &InstructionTable<IW, H>,
+        host: &mut H,
+    ) -> InterpreterAction {
+        let Some(blocks) = self.bytecode.basic_blocks() else {
+            return self.run_plain(instruction_table, host);
+        };
+
+        while self.bytecode.is_not_end() {
+            let Some(block) = blocks.block_at(self.bytecode.pc()) else {
+                self.step(instruction_table, host);
+                continue;
+            };
+
+            let stack_len = self.stack.len();
+            if stack_len >= block.min_stack_height as usize
+                && stack_len + block.max_stack_growth as usize <= self.stack.limit()
+                && self.gas.record_cost(block.static_gas)
+            {
+                let mut charged = 0;
+                for _ in 0..block.instructions {
+                    charged += self.step_precharged(instruction_table, host);
+                    if self.bytecode.is_end() {
+                        break;
+                    }
+                }
+                let unexecuted = block.static_gas.saturating_sub(charged);
+                if unexecuted != 0 {
+                    self.return_precharged_gas(unexecuted);
+                }
+            } else {
+                for _ in 0..block.instructions {
+                    self.step(instruction_table, host);
+                    if self.bytecode.is_end() {
+                        break;
+                    }
+                }
+            }
+        }
+        self.take_next_action()
+    }
+
+    /// Returns static gas that was precharged for instructions that were not executed, both to
+    /// the interpreter and to the result of the halt.
+    #[cold]
+    #[inline(never)]
+    fn return_precharged_gas(&mut self, gas: u64) {
+        self.gas.erase_cost(gas);
+        if let Some(InterpreterAction::Return(result)) = self.bytecode.action() {
+            result.gas.erase_cost(gas);
+        }
+    }
+
+    /// Executes the instruction at the current instruction pointer without charging its static
+    /// gas, which was already charged for the whole basic block.
+    ///
+    /// Returns the static gas of the instruction.
+    #[inline]
+    fn step_precharged<H: Host + ?Sized>(
+        &mut self,
+        instruction_table: &InstructionTable<IW, H>,
+        host: &mut H,
+    ) -> u64 {
+        let opcode = self.bytecode.opcode();
+        // SAFETY: See [`Interpreter::step`].
+        self.bytecode.relative_jump(1);
+        let instruction = unsafe { instruction_table.get_unchecked(opcode as usize) };
+        let static_gas = instruction.static_gas();
+        let context = InstructionContext {
+            interpreter: self,
+            host,
+        };
+        instruction.execute(context);
+        static_gas
+    }
 }
 
 /* used for cargo asm
@@ -538,3 +647,120 @@ fn test_mstore_big_offset_memory_limit_oog() {
         Some(InstructionResult::MemoryLimitOOG)
     );
 }
+
+#[test]
+fn test_run_basic_blocks_matches_run_plain() {
+    use super::*;
+    use crate::{
+        host::DummyHost,
+        instructions::{instruction_table, static_gas_table},
+    };
+    use bytecode::{opcode, Bytecode};
+    use primitives::Bytes;
+
+    // Counts down from 10 in a loop.
+    let code = Bytes::from(
+        &[
+            opcode::PUSH1,
+            0x0a,
+            opcode::JUMPDEST,
+            opcode::PUSH1,
+            0x01,
+            opcode::SWAP1,
+            opcode::SUB,
+            opcode::DUP1,
+            opcode::PUSH1,
+            0x02,
+            opcode::JUMPI,
+            opcode::STOP,
+        ][..],
+    );
+    let table = instruction_table::<EthInterpreter, DummyHost>();
+    let bytecode = Bytecode::new_raw(code).with_basic_blocks(&static_gas_table(&table));
+    assert!(bytecode.legacy_basic_blocks().is_some());
+
+    let run = |gas_limit, basic_blocks| {
+        let mut interpreter = Interpreter::<EthInterpreter>::new(
+            SharedMemory::new(),
+            ExtBytecode::new(bytecode.clone()),
+            InputsImpl::default(),
+            false,
+            SpecId::default(),
+            gas_limit,
+            GasParams::default(),
+        );
+        let action = if basic_blocks {
+            interpreter.run_basic_blocks(&table, &mut DummyHost)
+        } else {
+            interpreter.run_plain(&table, &mut DummyHost)
+        };
+        (action.instruction_result(), interpreter.gas.remaining())
+    };
+
+    // Covers running out of gas at every instruction of the loop.
+    for gas_limit in 0..300 {
+        assert_eq!(
+            run(gas_limit, true),
+            run(gas_limit, false),
+            "gas limit {gas_limit}"
+        );
+    }
+    assert_eq!(run(300, true).0, Some(InstructionResult::Stop));
+}
+
+#[test]
+fn test_run_basic_blocks_returns_gas_on_halt() {
+    use super::*;
+    use crate::{
+        host::DummyHost,
+        instructions::{instruction_table, static_gas_table},
+    };
+    use bytecode::{opcode, Bytecode};
+    use primitives::Bytes;
+
+    // PUSH0 is not activated before Shanghai and halts in the middle of the block.
+    let code = Bytes::from(
+        &[
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x02,
+            opcode::PUSH0,
+            opcode::ADD,
+            opcode::ADD,
+            opcode::STOP,
+        ][..],
+    );
+    let table = instruction_table::<EthInterpreter, DummyHost>();
+    let bytecode = Bytecode::new_raw(code).with_basic_blocks(&static_gas_table(&table));
+
+    let run = |gas_limit, basic_blocks| {
+        let mut interpreter = Interpreter::<EthInterpreter>::new(
+            SharedMemory::new(),
+            ExtBytecode::new(bytecode.clone()),
+            InputsImpl::default(),
+            false,
+            SpecId::BERLIN,
+            gas_limit,
+            GasParams::new_spec(SpecId::BERLIN),
+        );
+        let action = if basic_blocks {
+            interpreter.run_basic_blocks(&table, &mut DummyHost)
+        } else {
+            interpreter.run_plain(&table, &mut DummyHost)
+        };
+        let result = action.into_result_return().unwrap();
+        (result.result, result.gas, interpreter.gas)
+    };
+
+    for gas_limit in 0..20 {
+        assert_eq!(
+            run(gas_limit, true),
+            run(gas_limit, false),
+            "gas limit {gas_limit}"
+        );
+    }
+    let (result, gas, _) = run(100, true);
+    assert_eq!(result, InstructionResult::NotActivated);
+    assert_eq!(gas.remaining(), 100 - 3 - 3 - 2);
+}
@@ -1,8 +1,9 @@
 use super::{Immediates, Jumps, LegacyBytecode};
 use crate::{interpreter_types::LoopControl, InterpreterAction};
-use bytecode::{utils::read_u16, Bytecode};
+use bytecode::{utils::read_u16, BasicBlocks, Bytecode};
 use core::ops::Deref;
 use primitives::B256;
+use std::sync::Arc;
 
 #[cfg(feature = "serde")]
 mod serde;
@@ -23,6 +24,8 @@ pub struct ExtBytecode {
     pub action: Option<InterpreterAction>,
     /// The base bytecode.
     base: Bytecode,
+    /// Whether the instruction pointer points into the fused bytecode of the base.
+    fused: bool,
 }
 
 impl Deref for ExtBytecode {
@@ -65,6 +68,69 @@ impl ExtBytecode {
             bytecode_hash: hash,
             action: None,
             continue_execution: true,
+            fused: false,
+        }
+    }
+
+    /// Switches execution to the [fused bytecode](Bytecode::legacy_fused_bytecode) of the base,
+    /// keeping the current program counter.
+    ///
+    /// Returns `true` if the fused bytecode is used. It must be executed with the
+    /// [fused instruction table](crate::instructions::fused_instruction_table).
+    #[inline]
+    pub fn use_fused(&mut self) -> bool {
+        if self.fused {
+            return true;
+        }
+        let Some(fused) = self.base.legacy_fused_bytecode() else {
+            return false;
+        };
+        let pc = self.pc();
+        // SAFETY: Fused bytecode has the same length as the base bytecode.
+        self.instruction_pointer = unsafe { fused.as_ptr().add(pc) };
+        self.fused = true;
+        true
+    }
+
+    /// Returns `true` if the fused bytecode is used.
+    #[inline]
+    pub fn is_fused(&self) -> bool {
+        self.fused
+    }
+
+    /// Sets the [`BasicBlocks`] of the base bytecode if they are not set yet, keeping the current
+    /// program counter.
+    ///
+    /// `basic_blocks` is called with the original bytes of the bytecode and its hash, if known,
+    /// to compute the missing blocks or to get them from a cache.
+    ///
+    /// Returns `true` if the bytecode has basic blocks. Blocks that are already present are
+    /// expected to be computed with the same static gas costs. Fused bytecode is not split into
+    /// blocks.
+    pub fn ensure_basic_blocks(
+        &mut self,
+        basic_blocks: impl FnOnce(&[u8], Option<B256>) -> Arc<BasicBlocks>,
+    ) -> bool {
+        if self.base.legacy_basic_blocks().is_some() {
+            return true;
+        }
+        if self.fused || self.base.is_eip7702() {
+            return false;
+        }
+        let pc = self.pc();
+        let basic_blocks = basic_blocks(self.base.original_byte_slice(), self.bytecode_hash);
+        self.base = core::mem::take(&mut self.base).with_computed_basic_blocks(basic_blocks);
+        // SAFETY: Bytes of the bytecode are shared with the previous base and pc is in bounds.
+        self.instruction_pointer = unsafe { self.code_ptr().add(pc) };
+        true
+    }
+
+    /// Returns the pointer to the start of the executed bytes.
+    #[inline]
+    fn code_ptr(&self) -> *const u8 {
+        match self.base.legacy_fused_bytecode() {
+            Some(fused) if self.fused => fused.as_ptr(),
+            _ => self.base.bytes_ref().as_ptr(),
         }
     }
 
@@ -135,7 +201,7 @@ impl Jumps for ExtBytecode {
 
     #[inline]
     fn absolute_jump(&mut self, offset: usize) {
-        self.instruction_pointer = unsafe { self.base.bytes_ref().as_ptr().add(offset) };
+        self.instruction_pointer = unsafe { self.code_ptr().add(offset) };
     }
 
     #[inline]
@@ -158,7 +224,7 @@ impl Jumps for ExtBytecode {
         // In practice this is always true unless a caller modifies the `instruction_pointer` field manually.
         unsafe {
             self.instruction_pointer
-                .offset_from_unsigned(self.base.bytes_ref().as_ptr())
+                .offset_from_unsigned(self.code_ptr())
         }
     }
 }
@@ -199,6 +265,10 @@ impl LegacyBytecode for ExtBytecode {
     fn bytecode_slice(&self) -> &[u8] {
         self.base.original_byte_slice()
     }
+
+    fn basic_blocks(&self) -> Option<Arc<BasicBlocks>> {
+        self.base.legacy_basic_blocks().cloned()
+    }
 }
 
 #[cfg(test)]
@@ -9,6 +9,9 @@ struct ExtBytecodeSerde<'a> {
     base: Cow<'a, bytecode::Bytecode>,
     program_counter: usize,
     bytecode_hash: Option<B256>,
+    /// Fused bytecode is not serialized with the base, it is computed again on deserialization.
+    #[serde(default)]
+    fused: bool,
 }
 
 impl Serialize for ExtBytecode {
@@ -20,6 +23,7 @@ impl Serialize for ExtBytecode {
             base: Cow::Borrowed(&self.base),
             program_counter: self.pc(),
             bytecode_hash: self.bytecode_hash,
+            fused: self.fused,
         }
         .serialize(serializer)
     }
@@ -34,8 +38,13 @@ impl<'de> Deserialize<'de> for ExtBytecode {
             base,
             program_counter,
             bytecode_hash,
+            fused,
         } = ExtBytecodeSerde::deserialize(deserializer)?;
-        let mut bytecode = Self::new_with_optional_hash(base.into_owned(), bytecode_hash);
+        let mut base = base.into_owned();
+        if fused {
+            base = base.with_fusion();
+        }
+        let mut bytecode = Self::new_with_optional_hash(base, bytecode_hash);
         let len = bytecode.base.bytecode().len();
         if program_counter >= len {
             return Err(serde::de::Error::custom(format!(
@@ -43,6 +52,9 @@ impl<'de> Deserialize<'de> for ExtBytecode {
             )));
         }
         bytecode.absolute_jump(program_counter);
+        if fused && !bytecode.use_fused() {
+            return Err(serde::de::Error::custom("bytecode can't be fused"));
+        }
         Ok(bytecode)
     }
 }
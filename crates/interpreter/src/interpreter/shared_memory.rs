@@ -207,6 +207,23 @@ impl SharedMemory {
         let _ = limit;
     }
 
+    /// Returns the underlying buffer that is shared with the parent and child contexts.
+    ///
+    /// Returns `None` for the [invalid](Self::invalid) memory.
+    #[inline]
+    pub fn shared_buffer(&self) -> Option<&Rc<RefCell<Vec<u8>>>> {
+        self.buffer.as_ref()
+    }
+
+    /// Sets the underlying buffer, keeping the checkpoints of this context.
+    ///
+    /// Used to share a single buffer between contexts that were created separately, for example
+    /// after they were deserialized. The buffer needs to contain the memory of this context.
+    #[inline]
+    pub fn set_shared_buffer(&mut self, buffer: Rc<RefCell<Vec<u8>>>) {
+        self.buffer = Some(buffer);
+    }
+
     #[inline]
     fn buffer(&self) -> &Rc<RefCell<Vec<u8>>> {
         debug_assert!(self.buffer.is_some(), "cannot use SharedMemory::empty");
@@ -590,12 +607,10 @@ fn resize_memory_cold<Memory: MemoryTr>(
     gas_table: &GasParams,
     new_num_words: usize,
 ) -> Result<(), InstructionResult> {
-    let cost = gas_table.memory_cost(new_num_words);
-    let cost = unsafe {
-        gas.memory_mut()
-            .set_words_num(new_num_words, cost)
-            .unwrap_unchecked()
-    };
+    let cost = gas
+        .memory_mut()
+        .record_new_len(new_num_words, gas_table)
+        .unwrap_or_default();
 
     if !gas.record_cost(cost) {
         return Err(InstructionResult::MemoryOOG);
@@ -689,4 +704,42 @@ mod tests {
         assert_eq!(sm1.len(), 32);
         assert_eq!(sm1.buffer_ref().get(0..32), Some(&[0_u8; 32] as &[u8]));
     }
+
+    #[test]
+    fn resize_memory_with_decreasing_cost() {
+        use crate::gas::params::GasCostFns;
+
+        /// Memory of a single word is more expensive than any larger memory.
+        #[derive(Debug)]
+        struct DecreasingMemoryCost;
+
+        impl GasCostFns for DecreasingMemoryCost {
+            fn memory_cost(&self, _params: &GasParams, num_words: usize) -> u64 {
+                if num_words <= 1 {
+                    100
+                } else {
+                    10
+                }
+            }
+        }
+
+        let gas_params =
+            GasParams::default().with_cost_fns(std::sync::Arc::new(DecreasingMemoryCost));
+        let mut gas = crate::Gas::new(1000);
+        let mut memory = SharedMemory::new();
+
+        assert_eq!(
+            resize_memory(&mut gas, &mut memory, &gas_params, 0, 32),
+            Ok(())
+        );
+        assert_eq!(gas.remaining(), 900);
+        assert_eq!(
+            resize_memory(&mut gas, &mut memory, &gas_params, 0, 96),
+            Ok(())
+        );
+        assert_eq!(gas.remaining(), 900);
+        assert_eq!(memory.len(), 96);
+        assert_eq!(gas.memory().words_num, 3);
+        assert_eq!(gas.memory().expansion_cost, 100);
+    }
 }
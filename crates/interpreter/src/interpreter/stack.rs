@@ -5,15 +5,18 @@ use std::vec::Vec;
 
 use super::StackTr;
 
-/// EVM interpreter stack limit.
-pub const STACK_LIMIT: usize = 1024;
+pub use primitives::STACK_LIMIT;
 
-/// EVM stack with [STACK_LIMIT] capacity of words.
+/// EVM stack with up to [STACK_LIMIT] words of capacity reserved up front.
+///
+/// Stacks configured with a larger limit grow on demand above [STACK_LIMIT].
 #[derive(Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Stack {
     /// The underlying data of the stack.
     data: Vec<U256>,
+    /// Maximum number of words on the stack.
+    limit: usize,
 }
 
 impl fmt::Display for Stack {
@@ -38,10 +41,9 @@ impl Default for Stack {
 
 impl Clone for Stack {
     fn clone(&self) -> Self {
-        // Use `Self::new()` to ensure the cloned Stack is constructed with at least
-        // STACK_LIMIT capacity, and then copy the data. This preserves the invariant
-        // that Stack has sufficient capacity for operations that rely on it.
-        let mut new_stack = Self::new();
+        // Use `Self::with_limit()` to ensure the cloned Stack is constructed with the same
+        // up-front reservation, and then copy the data.
+        let mut new_stack = Self::with_limit(self.limit);
         new_stack.data.extend_from_slice(&self.data);
         new_stack
     }
@@ -100,22 +102,64 @@ impl StackTr for Stack {
     fn push_slice(&mut self, slice: &[u8]) -> bool {
         self.push_slice_(slice)
     }
+
+    #[inline]
+    fn limit(&self) -> usize {
+        self.limit
+    }
 }
 
 impl Stack {
     /// Instantiate a new stack with the [default stack limit][STACK_LIMIT].
     #[inline]
     pub fn new() -> Self {
+        Self::with_limit(STACK_LIMIT)
+    }
+
+    /// Instantiate a new stack that holds at most `limit` words.
+    ///
+    /// At most [STACK_LIMIT] words are reserved up front; larger stacks grow on demand.
+    #[inline]
+    pub fn with_limit(limit: usize) -> Self {
         Self {
-            // SAFETY: Expansion functions assume that capacity is `STACK_LIMIT`.
-            data: Vec::with_capacity(STACK_LIMIT),
+            data: Vec::with_capacity(limit.min(STACK_LIMIT)),
+            limit,
         }
     }
 
     /// Instantiate a new invalid Stack.
     #[inline]
     pub fn invalid() -> Self {
-        Self { data: Vec::new() }
+        Self {
+            data: Vec::new(),
+            limit: STACK_LIMIT,
+        }
+    }
+
+    /// Returns the maximum number of words on the stack.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Sets the maximum number of words on the stack.
+    ///
+    /// Capacity is reserved up to [STACK_LIMIT] words. Words above the new limit are kept.
+    #[inline]
+    pub fn set_limit(&mut self, limit: usize) {
+        if limit != self.limit {
+            self.data
+                .reserve(limit.min(STACK_LIMIT).saturating_sub(self.data.len()));
+            self.limit = limit;
+        }
+    }
+
+    /// Reserves capacity for `new_len` words when the stack grows above its up-front
+    /// reservation.
+    #[cold]
+    #[inline(never)]
+    fn grow(&mut self, new_len: usize) {
+        self.data.reserve(new_len - self.data.len());
     }
 
     /// Returns the length of the stack in words.
@@ -213,9 +257,7 @@ impl Stack {
     #[must_use]
     #[cfg_attr(debug_assertions, track_caller)]
     pub fn push(&mut self, value: U256) -> bool {
-        // In debug builds, verify we have sufficient capacity provisioned.
-        debug_assert!(self.data.capacity() >= STACK_LIMIT);
-        if self.data.len() == STACK_LIMIT {
+        if self.data.len() >= self.limit {
             return false;
         }
         self.data.push(value);
@@ -245,10 +287,13 @@ impl Stack {
     pub fn dup(&mut self, n: usize) -> bool {
         assume!(n > 0, "attempted to dup 0");
         let len = self.data.len();
-        if len < n || len + 1 > STACK_LIMIT {
+        if len < n || len + 1 > self.limit {
             false
         } else {
-            // SAFETY: Check for out of bounds is done above and it makes this safe to do.
+            if len + 1 > self.data.capacity() {
+                self.grow(len + 1);
+            }
+            // SAFETY: Check for out of bounds is done above and capacity is reserved.
             unsafe {
                 let ptr = self.data.as_mut_ptr().add(len);
                 ptr::copy_nonoverlapping(ptr.sub(n), ptr, 1);
@@ -318,14 +363,15 @@ impl Stack {
 
         let n_words = slice.len().div_ceil(32);
         let new_len = self.data.len() + n_words;
-        if new_len > STACK_LIMIT {
+        if new_len > self.limit {
             return false;
         }
 
-        // In debug builds, ensure underlying capacity is sufficient for the write.
-        debug_assert!(self.data.capacity() >= new_len);
+        if new_len > self.data.capacity() {
+            self.grow(new_len);
+        }
 
-        // SAFETY: Length checked above.
+        // SAFETY: Length checked and capacity reserved above.
         unsafe {
             let dst = self.data.as_mut_ptr().add(self.data.len()).cast::<u64>();
             self.data.set_len(new_len);
@@ -400,18 +446,32 @@ impl<'de> serde::Deserialize<'de> for Stack {
         #[derive(serde::Deserialize)]
         struct StackSerde {
             data: Vec<U256>,
+            #[serde(default = "default_limit")]
+            limit: usize,
+        }
+
+        fn default_limit() -> usize {
+            STACK_LIMIT
         }
 
         let mut stack = StackSerde::deserialize(deserializer)?;
-        if stack.data.len() > STACK_LIMIT {
+        if stack.data.len() > stack.limit {
             return Err(serde::de::Error::custom(std::format!(
                 "stack size exceeds limit: {} > {}",
                 stack.data.len(),
-                STACK_LIMIT
+                stack.limit
             )));
         }
-        stack.data.reserve(STACK_LIMIT - stack.data.len());
-        Ok(Self { data: stack.data })
+        stack.data.reserve(
+            stack
+                .limit
+                .min(STACK_LIMIT)
+                .saturating_sub(stack.data.len()),
+        );
+        Ok(Self {
+            data: stack.data,
+            limit: stack.limit,
+        })
     }
 }
 
@@ -515,4 +575,43 @@ mod tests {
         assert!(!full_stack.push(U256::from(100)));
         assert!(!cloned_full.push(U256::from(100)));
     }
+
+    #[test]
+    fn stack_limit() {
+        let mut stack = Stack::with_limit(2);
+        assert!(stack.push(U256::from(1)));
+        assert!(stack.dup(1));
+        assert!(!stack.push(U256::from(2)));
+        assert!(!stack.dup(1));
+        assert_eq!(stack.clone().limit(), 2);
+
+        stack.set_limit(4);
+        assert_eq!(stack.push_slice(&[0; 64]), Ok(()));
+        assert_eq!(
+            stack.push_slice(&[0; 1]),
+            Err(InstructionResult::StackOverflow)
+        );
+
+        stack.set_limit(1);
+        assert!(!stack.push(U256::ZERO));
+        assert_eq!(stack.len(), 4);
+    }
+
+    #[test]
+    fn stack_limit_above_default_grows_lazily() {
+        let mut stack = Stack::with_limit(usize::MAX);
+        assert_eq!(stack.data().capacity(), STACK_LIMIT);
+        for i in 0..STACK_LIMIT {
+            assert!(stack.push(U256::from(i)));
+        }
+        assert!(stack.dup(STACK_LIMIT));
+        assert_eq!(stack.push_slice(&[1; 64]), Ok(()));
+        assert_eq!(stack.len(), STACK_LIMIT + 3);
+        assert_eq!(stack.data()[STACK_LIMIT], U256::ZERO);
+
+        let mut stack = Stack::new();
+        stack.set_limit(usize::MAX);
+        assert_eq!(stack.data().capacity(), STACK_LIMIT);
+        assert_eq!(stack.clone().data().capacity(), STACK_LIMIT);
+    }
 }
@@ -1,9 +1,11 @@
-use crate::{CallInput, InstructionResult, InterpreterAction};
+use crate::{CallInput, InstructionResult, InterpreterAction, STACK_LIMIT};
+use bytecode::BasicBlocks;
 use core::{
     cell::Ref,
     ops::{Deref, Range},
 };
 use primitives::{hardfork::SpecId, Address, Bytes, B256, U256};
+use std::sync::Arc;
 
 /// Helper function to read immediates data from the bytecode
 pub trait Immediates {
@@ -62,6 +64,12 @@ pub trait LegacyBytecode {
     fn bytecode_len(&self) -> usize;
     /// Returns current bytecode original slice. Used in [`bytecode::opcode::CODECOPY`] opcode.
     fn bytecode_slice(&self) -> &[u8];
+    /// Returns basic blocks of the bytecode if they were computed.
+    ///
+    /// Used by [`Interpreter::run_basic_blocks`](crate::Interpreter::run_basic_blocks).
+    fn basic_blocks(&self) -> Option<Arc<BasicBlocks>> {
+        None
+    }
 }
 
 /// Trait for Interpreter to be able to jump
@@ -188,6 +196,11 @@ pub trait StackTr {
     /// Error is internally set in interpreter.
     fn push_slice(&mut self, slice: &[u8]) -> bool;
 
+    /// Returns the maximum number of items on the stack.
+    fn limit(&self) -> usize {
+        STACK_LIMIT
+    }
+
     /// Pushes B256 value to the stack.
     ///
     /// Internally converts B256 to U256 and then calls [`StackTr::push`].
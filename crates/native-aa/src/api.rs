@@ -0,0 +1,9 @@
+//! Native account abstraction API types.
+
+pub mod builder;
+pub mod default_ctx;
+pub mod exec;
+
+pub use builder::AaBuilder;
+pub use default_ctx::DefaultAa;
+pub use exec::{AaContextTr, AaError};
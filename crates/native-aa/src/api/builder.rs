@@ -0,0 +1,45 @@
+//! Account abstraction builder trait [`AaBuilder`] used to build [`AaEvm`].
+use crate::{evm::AaEvm, AaTxTr};
+use revm::{
+    context::Cfg,
+    context_interface::{Block, JournalTr},
+    handler::{instructions::EthInstructions, EthPrecompiles},
+    interpreter::interpreter::EthInterpreter,
+    state::EvmState,
+    Context, Database,
+};
+
+/// Type alias for default AaEvm
+pub type DefaultAaEvm<CTX, INSP = ()> =
+    AaEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, EthPrecompiles>;
+
+/// Trait that allows for an [`AaEvm`] to be built.
+pub trait AaBuilder: Sized {
+    /// Type of the context.
+    type Context;
+
+    /// Build the account abstraction EVM.
+    fn build_aa(self) -> DefaultAaEvm<Self::Context>;
+
+    /// Build the account abstraction EVM with an inspector.
+    fn build_aa_with_inspector<INSP>(self, inspector: INSP) -> DefaultAaEvm<Self::Context, INSP>;
+}
+
+impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN> AaBuilder for Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN>
+where
+    BLOCK: Block,
+    TX: AaTxTr,
+    CFG: Cfg,
+    DB: Database,
+    JOURNAL: JournalTr<Database = DB, State = EvmState>,
+{
+    type Context = Self;
+
+    fn build_aa(self) -> DefaultAaEvm<Self::Context> {
+        AaEvm::new(self, ())
+    }
+
+    fn build_aa_with_inspector<INSP>(self, inspector: INSP) -> DefaultAaEvm<Self::Context, INSP> {
+        AaEvm::new(self, inspector)
+    }
+}
@@ -0,0 +1,42 @@
+//! Contains trait [`DefaultAa`] used to create a default context.
+use crate::AaTransaction;
+use revm::{
+    context::{BlockEnv, CfgEnv, TxEnv},
+    database_interface::EmptyDB,
+    Context, Journal, MainContext,
+};
+
+/// Type alias for the default context type of the AaEvm.
+pub type AaContext<DB> = Context<BlockEnv, AaTransaction<TxEnv>, CfgEnv, DB, Journal<DB>, ()>;
+
+/// Trait that allows for a default context to be created.
+pub trait DefaultAa {
+    /// Create a default context.
+    fn aa() -> AaContext<EmptyDB>;
+}
+
+impl DefaultAa for AaContext<EmptyDB> {
+    fn aa() -> Self {
+        Context::mainnet().with_tx(AaTransaction::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::builder::AaBuilder;
+    use revm::{
+        inspector::{InspectEvm, NoOpInspector},
+        ExecuteEvm,
+    };
+
+    #[test]
+    fn default_run_aa() {
+        let ctx = Context::aa();
+        let mut evm = ctx.build_aa_with_inspector(NoOpInspector {});
+        // execute
+        let _ = evm.transact(AaTransaction::default());
+        // inspect
+        let _ = evm.inspect_one_tx(AaTransaction::default());
+    }
+}
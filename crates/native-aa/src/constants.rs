@@ -0,0 +1,36 @@
+//! Constants of the native account abstraction.
+use revm::primitives::{address, Address};
+
+/// Type of the native account abstraction transaction.
+pub const AA_TX_TYPE: u8 = 0x05;
+
+/// Caller of the validation, execution and post-operation frames.
+pub const AA_ENTRY_POINT: Address = address!("0x0000000000000000000000000000000000007560");
+
+/// Caller of the deployment frame.
+pub const AA_SENDER_CREATOR: Address = address!("0x00000000000000000000000000000000ffff7560");
+
+/// Nonce manager of RIP-7712 that stores the sequence of every nonce key of a sender.
+pub const AA_NONCE_MANAGER: Address = address!("0x0000000000000000000000000000000000007712");
+
+/// Base gas of the transaction, it replaces the 21000 base gas of Ethereum transactions.
+pub const AA_BASE_GAS: u64 = 15_000;
+
+/// Percentage of the unused execution and post-operation gas that is charged anyway.
+pub const UNUSED_GAS_PENALTY_PERCENT: u64 = 10;
+
+/// Version of the transaction passed to the validation functions.
+pub const AA_TX_VERSION: u64 = 1;
+
+/// Selector of `validateTransaction(uint256,bytes32,bytes)`.
+///
+/// An account accepts the transaction by returning it as the magic value.
+pub const VALIDATE_TRANSACTION_SELECTOR: [u8; 4] = [0xbf, 0x45, 0xc1, 0x66];
+
+/// Selector of `validatePaymasterTransaction(uint256,bytes32,bytes)`.
+///
+/// A paymaster accepts the transaction by returning it as the magic value.
+pub const VALIDATE_PAYMASTER_TRANSACTION_SELECTOR: [u8; 4] = [0xe0, 0xe6, 0x18, 0x3a];
+
+/// Selector of `postPaymasterTransaction(bool,uint256,bytes)`.
+pub const POST_PAYMASTER_TRANSACTION_SELECTOR: [u8; 4] = [0x34, 0xa4, 0xa7, 0x7c];
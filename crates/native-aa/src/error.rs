@@ -0,0 +1,81 @@
+//! Contains the [`AaTransactionError`] type.
+use core::fmt::Display;
+use revm::context_interface::{
+    result::{EVMError, InvalidTransaction},
+    transaction::TransactionError,
+};
+
+/// Native account abstraction transaction validation error.
+///
+/// A transaction that fails one of its validation frames is invalid and can't be included in a
+/// block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AaTransactionError {
+    /// Base transaction error.
+    Base(InvalidTransaction),
+    /// Sender has code but the transaction has a deployer.
+    SenderAlreadyDeployed,
+    /// Sender has no code after the deployment frame.
+    SenderNotDeployed,
+    /// Deployment frame reverted or halted.
+    DeploymentFailed,
+    /// Account validation frame reverted or halted.
+    AccountValidationFailed,
+    /// Account did not return the magic value.
+    AccountRejected,
+    /// Paymaster validation frame reverted or halted.
+    PaymasterValidationFailed,
+    /// Paymaster did not return the magic value.
+    PaymasterRejected,
+    /// Block timestamp is outside of the validity window returned by a validation frame.
+    OutsideValidityWindow {
+        /// Timestamp the transaction is valid from.
+        valid_after: u64,
+        /// Timestamp the transaction is valid until, zero if it does not expire.
+        valid_until: u64,
+    },
+}
+
+impl TransactionError for AaTransactionError {}
+
+impl Display for AaTransactionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Base(error) => error.fmt(f),
+            Self::SenderAlreadyDeployed => {
+                write!(
+                    f,
+                    "sender is already deployed but the transaction has a deployer"
+                )
+            }
+            Self::SenderNotDeployed => write!(f, "sender has no code"),
+            Self::DeploymentFailed => write!(f, "deployment frame failed"),
+            Self::AccountValidationFailed => write!(f, "account validation frame failed"),
+            Self::AccountRejected => write!(f, "account did not accept the transaction"),
+            Self::PaymasterValidationFailed => write!(f, "paymaster validation frame failed"),
+            Self::PaymasterRejected => write!(f, "paymaster did not accept the transaction"),
+            Self::OutsideValidityWindow {
+                valid_after,
+                valid_until,
+            } => write!(
+                f,
+                "block timestamp is outside of the validity window ({valid_after}, {valid_until})"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for AaTransactionError {}
+
+impl From<InvalidTransaction> for AaTransactionError {
+    fn from(value: InvalidTransaction) -> Self {
+        Self::Base(value)
+    }
+}
+
+impl<DBError> From<AaTransactionError> for EVMError<DBError, AaTransactionError> {
+    fn from(value: AaTransactionError) -> Self {
+        Self::Transaction(value)
+    }
+}
@@ -0,0 +1,207 @@
+//! Frames of the native account abstraction transaction and the encoding of their calldata.
+//!
+//! Validation frames pass the transaction to `validateTransaction(uint256,bytes32,bytes)` of the
+//! sender and `validatePaymasterTransaction(uint256,bytes32,bytes)` of the paymaster. The sender
+//! returns its [`ValidationData`], the paymaster returns `(bytes32 validationData, bytes context)`
+//! where the context is passed to its `postPaymasterTransaction(bool,uint256,bytes)`.
+use crate::{constants::AA_TX_VERSION, error::AaTransactionError};
+use revm::{
+    context_interface::{Cfg, ContextTr, LocalContextTr},
+    handler::FrameResult,
+    interpreter::{
+        interpreter_action::FrameInit, CallInput, CallInputs, CallScheme, CallValue, FrameInput,
+        SharedMemory,
+    },
+    primitives::{Address, Bytes, B256, U256},
+};
+use std::{boxed::Box, vec::Vec};
+
+/// Validation data returned by the account and the paymaster validation frames.
+///
+/// The 32 bytes word packs the magic value and the validity window:
+/// `magic (bytes4) | zero (bytes16) | validUntil (uint48) | validAfter (uint48)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValidationData {
+    /// Magic value, the selector of the validation function if the transaction is accepted.
+    pub magic: [u8; 4],
+    /// Timestamp the transaction is valid until, zero if it does not expire.
+    pub valid_until: u64,
+    /// Timestamp the transaction is valid from.
+    pub valid_after: u64,
+}
+
+impl ValidationData {
+    /// Decodes the validation data from the first word of the output.
+    pub fn decode(output: &[u8]) -> Option<Self> {
+        let word = output.get(..32)?;
+        let mut magic = [0u8; 4];
+        magic.copy_from_slice(&word[..4]);
+        let uint48 = |bytes: &[u8]| {
+            let mut buf = [0u8; 8];
+            buf[2..].copy_from_slice(bytes);
+            u64::from_be_bytes(buf)
+        };
+        Some(Self {
+            magic,
+            valid_until: uint48(&word[20..26]),
+            valid_after: uint48(&word[26..32]),
+        })
+    }
+
+    /// Encodes the validation data into a word.
+    pub fn encode(&self) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[..4].copy_from_slice(&self.magic);
+        word[20..26].copy_from_slice(&self.valid_until.to_be_bytes()[2..]);
+        word[26..32].copy_from_slice(&self.valid_after.to_be_bytes()[2..]);
+        word
+    }
+
+    /// Checks that the block timestamp is in the validity window.
+    pub fn check_validity_window(&self, timestamp: u64) -> Result<(), AaTransactionError> {
+        if timestamp < self.valid_after || (self.valid_until != 0 && timestamp > self.valid_until) {
+            return Err(AaTransactionError::OutsideValidityWindow {
+                valid_after: self.valid_after,
+                valid_until: self.valid_until,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Creates the frame of a call from `caller` to `target` at the top of the frame stack.
+///
+/// The frames of a transaction run one after the other, memory of the previous frame is dropped.
+pub fn call_frame<CTX: ContextTr>(
+    ctx: &mut CTX,
+    caller: Address,
+    target: Address,
+    input: Bytes,
+    gas_limit: u64,
+) -> FrameInit {
+    let buffer = ctx.local().shared_memory_buffer().clone();
+    buffer.borrow_mut().clear();
+    let mut memory = SharedMemory::new_with_buffer(buffer);
+    memory.set_memory_limit(ctx.cfg().memory_limit());
+
+    FrameInit {
+        depth: 0,
+        memory,
+        frame_input: FrameInput::Call(Box::new(CallInputs {
+            input: CallInput::Bytes(input),
+            return_memory_offset: 0..0,
+            gas_limit,
+            bytecode_address: target,
+            known_bytecode: None,
+            target_address: target,
+            caller,
+            value: CallValue::Transfer(U256::ZERO),
+            scheme: CallScheme::Call,
+            is_static: false,
+        })),
+    }
+}
+
+/// Returns the gas used by the frame, a halted frame uses all of its gas.
+pub fn frame_gas_used(result: &FrameResult) -> u64 {
+    let gas = result.gas();
+    if result.instruction_result().is_ok_or_revert() {
+        gas.spent()
+    } else {
+        gas.limit()
+    }
+}
+
+/// Encodes the calldata of a validation function, `(uint256 version, bytes32 txHash, bytes tx)`.
+pub fn validation_calldata(selector: [u8; 4], tx_hash: B256, encoded_tx: &[u8]) -> Bytes {
+    encode_call(
+        selector,
+        [U256::from(AA_TX_VERSION).to_be_bytes::<32>(), tx_hash.0],
+        encoded_tx,
+    )
+}
+
+/// Encodes the calldata of the post-operation, `(bool success, uint256 actualGasCost, bytes
+/// context)`.
+pub fn post_op_calldata(
+    selector: [u8; 4],
+    success: bool,
+    actual_gas_cost: U256,
+    context: &[u8],
+) -> Bytes {
+    encode_call(
+        selector,
+        [
+            U256::from(success as u8).to_be_bytes::<32>(),
+            actual_gas_cost.to_be_bytes::<32>(),
+        ],
+        context,
+    )
+}
+
+/// Decodes the `(bytes32 validationData, bytes context)` output of the paymaster validation.
+pub fn decode_paymaster_output(output: &[u8]) -> Option<(ValidationData, Bytes)> {
+    let validation_data = ValidationData::decode(output)?;
+    let word = |offset: usize| -> Option<usize> {
+        let word = U256::from_be_slice(output.get(offset..offset.checked_add(32)?)?);
+        usize::try_from(word).ok()
+    };
+    let offset = word(32)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let context = output.get(start..start.checked_add(len)?)?;
+    Some((validation_data, Bytes::copy_from_slice(context)))
+}
+
+/// Encodes a call with two static arguments followed by a dynamic `bytes` argument.
+fn encode_call(selector: [u8; 4], head: [[u8; 32]; 2], data: &[u8]) -> Bytes {
+    let padded_len = data.len().div_ceil(32) * 32;
+    let mut calldata = Vec::with_capacity(4 + 4 * 32 + padded_len);
+    calldata.extend_from_slice(&selector);
+    for word in head {
+        calldata.extend_from_slice(&word);
+    }
+    // Offset of the bytes argument, after the three head words.
+    calldata.extend_from_slice(&U256::from(3 * 32).to_be_bytes::<32>());
+    calldata.extend_from_slice(&U256::from(data.len()).to_be_bytes::<32>());
+    calldata.extend_from_slice(data);
+    calldata.resize(4 + 4 * 32 + padded_len, 0);
+    calldata.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::VALIDATE_PAYMASTER_TRANSACTION_SELECTOR;
+
+    #[test]
+    fn test_validation_data() {
+        let data = ValidationData {
+            magic: VALIDATE_PAYMASTER_TRANSACTION_SELECTOR,
+            valid_until: 2_000,
+            valid_after: 1_000,
+        };
+        assert_eq!(ValidationData::decode(&data.encode()), Some(data));
+        assert!(data.check_validity_window(1_500).is_ok());
+        assert!(data.check_validity_window(2_001).is_err());
+        assert!(data.check_validity_window(999).is_err());
+    }
+
+    #[test]
+    fn test_decode_paymaster_output() {
+        let data = ValidationData {
+            magic: VALIDATE_PAYMASTER_TRANSACTION_SELECTOR,
+            ..Default::default()
+        };
+        let mut output = data.encode().to_vec();
+        output.extend_from_slice(&U256::from(64).to_be_bytes::<32>());
+        output.extend_from_slice(&U256::from(3).to_be_bytes::<32>());
+        output.extend_from_slice(&[1, 2, 3]);
+        output.resize(output.len() + 29, 0);
+
+        let (decoded, context) = decode_paymaster_output(&output).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(context, Bytes::from_static(&[1, 2, 3]));
+        assert_eq!(decode_paymaster_output(&output[..70]), None);
+    }
+}
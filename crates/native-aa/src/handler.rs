@@ -0,0 +1,417 @@
+//! Handler of native account abstraction transactions.
+use crate::{
+    constants::{
+        AA_ENTRY_POINT, AA_SENDER_CREATOR, POST_PAYMASTER_TRANSACTION_SELECTOR,
+        UNUSED_GAS_PENALTY_PERCENT, VALIDATE_PAYMASTER_TRANSACTION_SELECTOR,
+        VALIDATE_TRANSACTION_SELECTOR,
+    },
+    frames::{
+        call_frame, decode_paymaster_output, frame_gas_used, post_op_calldata, validation_calldata,
+        ValidationData,
+    },
+    nonce::validate_and_bump_nonce,
+    AaContextTr, AaTransactionError, AaTxTr,
+};
+use revm::{
+    context::{journaled_state::account::JournaledAccountTr, result::InvalidTransaction},
+    context_interface::{
+        result::{FromStringError, HaltReason},
+        Block, Cfg, ContextTr, JournalTr, Transaction,
+    },
+    handler::{
+        evm::FrameTr, handler::EvmTrError, pre_execution::calculate_caller_fee,
+        validation::validate_priority_fee_tx, EthFrame, EvmTr, FrameResult, Handler,
+        MainnetHandler,
+    },
+    inspector::{Inspector, InspectorEvmTr, InspectorHandler},
+    interpreter::{
+        interpreter::EthInterpreter, interpreter_action::FrameInit, CallOutcome, Gas,
+        InitialAndFloorGas, InstructionResult, InterpreterResult,
+    },
+    primitives::{Bytes, U256},
+};
+
+/// Handler of native account abstraction transactions.
+///
+/// Transactions that are not account abstraction transactions are handled as on mainnet. An
+/// account abstraction transaction runs the phases of RIP-7560 on top of the [`Handler`] hooks:
+///
+/// * [`Handler::validate`] checks the fees and charges the intrinsic gas.
+/// * [`Handler::pre_execution`] validates and increments the RIP-7712 nonce and charges the
+///   maximum gas cost to the payer, the paymaster if set or the sender.
+/// * [`Handler::execution`] runs the deployment and the account validation frames within the
+///   validation gas limit, the paymaster validation frame, the execution frame and the paymaster
+///   post-operation frame, each with its own gas limit. The transaction is invalid if one of the
+///   validation frames fails, a reverted post-operation reverts the execution frame too.
+/// * [`Handler::post_execution`] refunds the unused gas to the payer, with a penalty on the unused
+///   execution and post-operation gas, and rewards the beneficiary.
+#[derive(Debug, Clone)]
+pub struct AaHandler<EVM, ERROR, FRAME> {
+    /// Mainnet handler, runs the transactions that are not account abstraction transactions and
+    /// loads the accounts of all transactions.
+    pub mainnet: MainnetHandler<EVM, ERROR, FRAME>,
+}
+
+impl<EVM, ERROR, FRAME> AaHandler<EVM, ERROR, FRAME> {
+    /// Create a new account abstraction handler.
+    pub fn new() -> Self {
+        Self {
+            mainnet: MainnetHandler::default(),
+        }
+    }
+}
+
+impl<EVM, ERROR, FRAME> Default for AaHandler<EVM, ERROR, FRAME> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<EVM, ERROR, FRAME> AaHandler<EVM, ERROR, FRAME>
+where
+    EVM: EvmTr<Context: AaContextTr, Frame = FRAME>,
+    ERROR: EvmTrError<EVM> + From<AaTransactionError> + FromStringError,
+    FRAME: FrameTr<FrameResult = FrameResult, FrameInit = FrameInit>,
+{
+    /// Runs the frames of an account abstraction transaction with `run_frame`.
+    ///
+    /// Returns the result of the execution frame with the gas of the whole transaction.
+    fn run_aa_frames<F>(&mut self, evm: &mut EVM, mut run_frame: F) -> Result<FrameResult, ERROR>
+    where
+        F: FnMut(&mut Self, &mut EVM, FrameInit) -> Result<FrameResult, ERROR>,
+    {
+        let ctx = evm.ctx_ref();
+        let tx = ctx.tx();
+        let Some(aa) = tx.aa().cloned() else {
+            return Err(ERROR::from_string(
+                "not an account abstraction transaction".into(),
+            ));
+        };
+        let sender = tx.caller();
+        let gas_limit = tx.gas_limit();
+        let call_gas_limit = tx.call_gas_limit();
+        let execution_data = tx.input().clone();
+        let gas_price = tx.effective_gas_price(ctx.block().basefee() as u128);
+        let timestamp = ctx.block().timestamp().saturating_to::<u64>();
+
+        // Deployment and account validation frames share the validation gas limit.
+        let mut validation_gas_left = aa.validation_gas_limit;
+        let sender_deployed = !evm
+            .ctx()
+            .journal_mut()
+            .load_account_with_code(sender)?
+            .info
+            .is_empty_code_hash();
+        if let Some(deployer) = aa.deployer {
+            if sender_deployed {
+                return Err(AaTransactionError::SenderAlreadyDeployed.into());
+            }
+            let frame = call_frame(
+                evm.ctx(),
+                AA_SENDER_CREATOR,
+                deployer,
+                aa.deployer_data.clone(),
+                validation_gas_left,
+            );
+            let result = run_frame(self, evm, frame)?;
+            if !result.instruction_result().is_ok() {
+                return Err(AaTransactionError::DeploymentFailed.into());
+            }
+            validation_gas_left -= frame_gas_used(&result);
+            let journal = evm.ctx().journal_mut();
+            if journal
+                .load_account_with_code(sender)?
+                .info
+                .is_empty_code_hash()
+            {
+                return Err(AaTransactionError::SenderNotDeployed.into());
+            }
+        } else if !sender_deployed {
+            return Err(AaTransactionError::SenderNotDeployed.into());
+        }
+
+        let calldata =
+            validation_calldata(VALIDATE_TRANSACTION_SELECTOR, aa.tx_hash, &aa.encoded_tx);
+        let frame = call_frame(
+            evm.ctx(),
+            AA_ENTRY_POINT,
+            sender,
+            calldata,
+            validation_gas_left,
+        );
+        let result = run_frame(self, evm, frame)?;
+        if !result.instruction_result().is_ok() {
+            return Err(AaTransactionError::AccountValidationFailed.into());
+        }
+        ValidationData::decode(&result.interpreter_result().output)
+            .filter(|data| data.magic == VALIDATE_TRANSACTION_SELECTOR)
+            .ok_or(AaTransactionError::AccountRejected)?
+            .check_validity_window(timestamp)?;
+        validation_gas_left -= frame_gas_used(&result);
+
+        let mut paymaster_gas_left = aa.paymaster_validation_gas_limit;
+        let mut paymaster_context = Bytes::new();
+        if let Some(paymaster) = aa.paymaster {
+            let calldata = validation_calldata(
+                VALIDATE_PAYMASTER_TRANSACTION_SELECTOR,
+                aa.tx_hash,
+                &aa.encoded_tx,
+            );
+            let frame = call_frame(
+                evm.ctx(),
+                AA_ENTRY_POINT,
+                paymaster,
+                calldata,
+                paymaster_gas_left,
+            );
+            let result = run_frame(self, evm, frame)?;
+            if !result.instruction_result().is_ok() {
+                return Err(AaTransactionError::PaymasterValidationFailed.into());
+            }
+            let (data, context) = decode_paymaster_output(&result.interpreter_result().output)
+                .filter(|(data, _)| data.magic == VALIDATE_PAYMASTER_TRANSACTION_SELECTOR)
+                .ok_or(AaTransactionError::PaymasterRejected)?;
+            data.check_validity_window(timestamp)?;
+            paymaster_gas_left -= frame_gas_used(&result);
+            paymaster_context = context;
+        }
+
+        // A reverted post-operation reverts the execution frame, both run under one checkpoint.
+        let checkpoint = evm.ctx().journal_mut().checkpoint();
+        let frame = call_frame(
+            evm.ctx(),
+            AA_ENTRY_POINT,
+            sender,
+            execution_data,
+            call_gas_limit,
+        );
+        let result = run_frame(self, evm, frame)?;
+        let execution_gas_used = frame_gas_used(&result);
+        let mut refunded = if result.instruction_result().is_ok() {
+            result.gas().refunded()
+        } else {
+            0
+        };
+        let InterpreterResult {
+            result: mut instruction_result,
+            mut output,
+            ..
+        } = result.into_interpreter_result();
+
+        let mut post_op_gas_used = 0;
+        let mut post_op_failed = false;
+        if let Some(paymaster) = aa.paymaster.filter(|_| !paymaster_context.is_empty()) {
+            // Cost of the gas used so far, with the penalty and the whole post-operation gas.
+            let execution_gas_left = call_gas_limit - execution_gas_used;
+            let gas_used = gas_limit
+                - validation_gas_left
+                - paymaster_gas_left
+                - (execution_gas_left - execution_gas_left * UNUSED_GAS_PENALTY_PERCENT / 100);
+            let actual_gas_cost = U256::from((gas_used as u128).saturating_mul(gas_price));
+            let calldata = post_op_calldata(
+                POST_PAYMASTER_TRANSACTION_SELECTOR,
+                instruction_result.is_ok(),
+                actual_gas_cost,
+                &paymaster_context,
+            );
+            let frame = call_frame(
+                evm.ctx(),
+                AA_ENTRY_POINT,
+                paymaster,
+                calldata,
+                aa.post_op_gas_limit,
+            );
+            let result = run_frame(self, evm, frame)?;
+            post_op_gas_used = frame_gas_used(&result);
+            if result.instruction_result().is_ok() {
+                refunded += result.gas().refunded();
+            } else {
+                post_op_failed = true;
+                instruction_result = InstructionResult::Revert;
+                output = result.into_interpreter_result().output;
+                refunded = 0;
+            }
+        }
+        if post_op_failed {
+            evm.ctx().journal_mut().checkpoint_revert(checkpoint);
+        } else {
+            evm.ctx().journal_mut().checkpoint_commit();
+        }
+
+        // Unused validation gas is returned, the unused execution and post-operation gas is
+        // returned without the penalty.
+        let unused_gas =
+            (call_gas_limit - execution_gas_used) + (aa.post_op_gas_limit - post_op_gas_used);
+        let penalty = unused_gas * UNUSED_GAS_PENALTY_PERCENT / 100;
+        let mut gas = Gas::new_spent(gas_limit);
+        gas.erase_cost(validation_gas_left + paymaster_gas_left + unused_gas - penalty);
+        gas.record_refund(refunded);
+
+        Ok(FrameResult::Call(CallOutcome::new(
+            InterpreterResult::new(instruction_result, output, gas),
+            0..0,
+        )))
+    }
+}
+
+impl<EVM, ERROR, FRAME> Handler for AaHandler<EVM, ERROR, FRAME>
+where
+    EVM: EvmTr<Context: AaContextTr, Frame = FRAME>,
+    ERROR: EvmTrError<EVM> + From<AaTransactionError> + FromStringError,
+    FRAME: FrameTr<FrameResult = FrameResult, FrameInit = FrameInit>,
+{
+    type Evm = EVM;
+    type Error = ERROR;
+    type HaltReason = HaltReason;
+
+    fn validate_env(&self, evm: &mut Self::Evm) -> Result<(), Self::Error> {
+        let ctx = evm.ctx_ref();
+        let tx = ctx.tx();
+        if !tx.is_aa() {
+            return self.mainnet.validate_env(evm);
+        }
+        let (block, cfg) = (ctx.block(), ctx.cfg());
+
+        if cfg.tx_chain_id_check() {
+            match tx.chain_id() {
+                Some(chain_id) if chain_id != cfg.chain_id() => {
+                    return Err(InvalidTransaction::InvalidChainId.into());
+                }
+                None => return Err(InvalidTransaction::MissingChainId.into()),
+                _ => {}
+            }
+        }
+
+        let base_fee = (!cfg.is_base_fee_check_disabled()).then(|| block.basefee() as u128);
+        validate_priority_fee_tx(
+            tx.max_fee_per_gas(),
+            tx.max_priority_fee_per_gas().unwrap_or_default(),
+            base_fee,
+            cfg.is_priority_fee_check_disabled(),
+        )?;
+
+        let cap = cfg.tx_gas_limit_cap();
+        if tx.gas_limit() > cap {
+            return Err(InvalidTransaction::TxGasLimitGreaterThanCap {
+                gas_limit: tx.gas_limit(),
+                cap,
+            }
+            .into());
+        }
+        if !cfg.is_block_gas_limit_disabled() && tx.gas_limit() > block.gas_limit() {
+            return Err(InvalidTransaction::CallerGasLimitMoreThanBlock.into());
+        }
+        Ok(())
+    }
+
+    fn validate_initial_tx_gas(
+        &self,
+        evm: &mut Self::Evm,
+    ) -> Result<InitialAndFloorGas, Self::Error> {
+        match evm.ctx_ref().tx().aa() {
+            // Intrinsic gas is part of the total gas limit of the transaction.
+            Some(aa) => Ok(InitialAndFloorGas::new(aa.intrinsic_gas(), 0)),
+            None => self.mainnet.validate_initial_tx_gas(evm),
+        }
+    }
+
+    fn load_accounts(&self, evm: &mut Self::Evm) -> Result<(), Self::Error> {
+        self.mainnet.load_accounts(evm)?;
+
+        // Callers and targets of the frames are loaded, sender, deployer and paymaster start warm.
+        let (tx, journal) = evm.ctx().tx_journal_mut();
+        if let Some(aa) = tx.aa() {
+            let frame_accounts = [AA_ENTRY_POINT, AA_SENDER_CREATOR, tx.caller()];
+            for address in frame_accounts
+                .into_iter()
+                .chain(aa.deployer)
+                .chain(aa.paymaster)
+            {
+                journal.load_account(address)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_against_state_and_deduct_caller(
+        &self,
+        evm: &mut Self::Evm,
+    ) -> Result<(), Self::Error> {
+        if !evm.ctx_ref().tx().is_aa() {
+            return self.mainnet.validate_against_state_and_deduct_caller(evm);
+        }
+        let (block, tx, cfg, journal, _, _) = evm.ctx().all_mut();
+        let nonce_key = tx.aa().map(|aa| aa.nonce_key).unwrap_or_default();
+
+        validate_and_bump_nonce::<_, ERROR>(
+            journal,
+            tx.caller(),
+            nonce_key,
+            tx.nonce(),
+            cfg.is_nonce_check_disabled(),
+        )?;
+
+        let mut payer = journal.load_account_mut(tx.payer())?.data;
+        let new_balance = calculate_caller_fee(*payer.balance(), tx, block, cfg)?;
+        payer.set_balance(new_balance);
+        Ok(())
+    }
+
+    fn execution(
+        &mut self,
+        evm: &mut Self::Evm,
+        init_and_floor_gas: &InitialAndFloorGas,
+    ) -> Result<FrameResult, Self::Error> {
+        if !evm.ctx_ref().tx().is_aa() {
+            return self.mainnet.execution(evm, init_and_floor_gas);
+        }
+        self.run_aa_frames(evm, |handler, evm, frame| handler.run_exec_loop(evm, frame))
+    }
+
+    fn reimburse_caller(
+        &self,
+        evm: &mut Self::Evm,
+        frame_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
+    ) -> Result<(), Self::Error> {
+        let ctx = evm.ctx();
+        let tx = ctx.tx();
+        if !tx.is_aa() {
+            return self.mainnet.reimburse_caller(evm, frame_result);
+        }
+
+        let payer = tx.payer();
+        let effective_gas_price = tx.effective_gas_price(ctx.block().basefee() as u128);
+        let gas = frame_result.gas();
+        let refund =
+            effective_gas_price.saturating_mul((gas.remaining() + gas.refunded() as u64) as u128);
+        ctx.journal_mut()
+            .load_account_mut(payer)?
+            .incr_balance(U256::from(refund));
+        Ok(())
+    }
+}
+
+impl<EVM, ERROR> InspectorHandler for AaHandler<EVM, ERROR, EthFrame<EthInterpreter>>
+where
+    EVM: InspectorEvmTr<
+        Context: AaContextTr,
+        Frame = EthFrame<EthInterpreter>,
+        Inspector: Inspector<<<Self as Handler>::Evm as EvmTr>::Context, EthInterpreter>,
+    >,
+    ERROR: EvmTrError<EVM> + From<AaTransactionError> + FromStringError,
+{
+    type IT = EthInterpreter;
+
+    fn inspect_execution(
+        &mut self,
+        evm: &mut Self::Evm,
+        init_and_floor_gas: &InitialAndFloorGas,
+    ) -> Result<FrameResult, Self::Error> {
+        if !evm.ctx_ref().tx().is_aa() {
+            return self.mainnet.inspect_execution(evm, init_and_floor_gas);
+        }
+        self.run_aa_frames(evm, |handler, evm, frame| {
+            handler.inspect_run_exec_loop(evm, frame)
+        })
+    }
+}
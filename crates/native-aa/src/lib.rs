@@ -0,0 +1,26 @@
+//! Native account abstraction variant of Revm.
+//!
+//! Adds an account abstraction transaction type ([`AaTxTr`]) in the style of RIP-7560. The
+//! sender is a smart contract account that validates the transaction in a validation frame, an
+//! optional paymaster validates it and pays for the gas, and the nonce is two dimensional as in
+//! RIP-7712. [`AaHandler`] and [`AaEvm`] run these transactions next to the mainnet ones.
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc as std;
+
+pub mod api;
+pub mod constants;
+pub mod error;
+pub mod evm;
+pub mod frames;
+pub mod handler;
+pub mod nonce;
+pub mod transaction;
+
+pub use api::{AaBuilder, AaContextTr, AaError, DefaultAa};
+pub use error::AaTransactionError;
+pub use evm::AaEvm;
+pub use handler::AaHandler;
+pub use transaction::{AaTransaction, AaTransactionParts, AaTxTr};
@@ -0,0 +1,67 @@
+//! Two dimensional nonces of RIP-7712.
+//!
+//! A nonce is made of a key and a sequence. Key zero uses the nonce of the sender account, the
+//! sequences of the other keys are kept in the storage of the [`AA_NONCE_MANAGER`].
+use crate::constants::AA_NONCE_MANAGER;
+use revm::{
+    context::journaled_state::account::JournaledAccountTr,
+    context_interface::{result::InvalidTransaction, Database, JournalTr},
+    handler::pre_execution::validate_account_nonce_and_code,
+    primitives::{keccak256, Address, U256},
+};
+
+/// Returns the slot of the nonce manager that keeps the sequence of the key of the sender.
+///
+/// The slot is `keccak256(abi.encode(sender, key))`.
+pub fn nonce_slot(sender: Address, key: U256) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[12..32].copy_from_slice(sender.as_slice());
+    preimage[32..].copy_from_slice(&key.to_be_bytes::<32>());
+    keccak256(preimage).into()
+}
+
+/// Validates the sequence of the nonce key and increments it.
+pub fn validate_and_bump_nonce<
+    JOURNAL: JournalTr,
+    ERROR: From<InvalidTransaction> + From<<JOURNAL::Database as Database>::Error>,
+>(
+    journal: &mut JOURNAL,
+    sender: Address,
+    key: U256,
+    sequence: u64,
+    is_nonce_check_disabled: bool,
+) -> Result<(), ERROR> {
+    if key.is_zero() {
+        let mut account = journal.load_account_mut(sender)?.data;
+        // Sender is a smart contract account, its code is not checked.
+        validate_account_nonce_and_code(
+            &account.account().info,
+            sequence,
+            true,
+            is_nonce_check_disabled,
+        )?;
+        account.bump_nonce();
+        return Ok(());
+    }
+
+    let slot = nonce_slot(sender, key);
+    journal.load_account(AA_NONCE_MANAGER)?;
+    let state = journal.sload(AA_NONCE_MANAGER, slot)?.data;
+    if !is_nonce_check_disabled && state != U256::from(sequence) {
+        let state = state.saturating_to();
+        return Err(if sequence > state {
+            InvalidTransaction::NonceTooHigh {
+                tx: sequence,
+                state,
+            }
+        } else {
+            InvalidTransaction::NonceTooLow {
+                tx: sequence,
+                state,
+            }
+        }
+        .into());
+    }
+    journal.sstore(AA_NONCE_MANAGER, slot, state.saturating_add(U256::ONE))?;
+    Ok(())
+}
@@ -0,0 +1,252 @@
+//! Contains the [`AaTxTr`] trait and the [`AaTransaction`] type.
+use crate::constants::{AA_BASE_GAS, AA_TX_TYPE};
+use auto_impl::auto_impl;
+use revm::{
+    context::TxEnv,
+    context_interface::transaction::Transaction,
+    handler::SystemCallTx,
+    interpreter::gas::{get_tokens_in_calldata, STANDARD_TOKEN_COST},
+    primitives::{Address, Bytes, TxKind, B256, U256},
+};
+
+/// Transaction that can be a native account abstraction transaction.
+///
+/// Fields of the base transaction keep their meaning for account abstraction transactions: the
+/// caller is the sender account, the input is the execution data, the nonce is the sequence of
+/// the nonce key and the gas limit is the gas limit of the execution frame.
+#[auto_impl(&, &mut, Box, Arc)]
+pub trait AaTxTr: Transaction {
+    /// Account abstraction parts, set if the transaction is an account abstraction transaction.
+    fn aa(&self) -> Option<&AaTransactionParts>;
+
+    /// Gas limit of the execution frame.
+    fn call_gas_limit(&self) -> u64;
+
+    /// Returns `true` if the transaction is an account abstraction transaction.
+    fn is_aa(&self) -> bool {
+        self.aa().is_some()
+    }
+
+    /// Returns the account that pays for the gas, the paymaster if set or the sender.
+    fn payer(&self) -> Address {
+        self.aa()
+            .and_then(|aa| aa.paymaster)
+            .unwrap_or_else(|| self.caller())
+    }
+}
+
+/// Account abstraction parts of an [`AaTransaction`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AaTransactionParts {
+    /// Factory that deploys the sender account in the deployment frame.
+    pub deployer: Option<Address>,
+    /// Calldata of the deployment frame.
+    pub deployer_data: Bytes,
+    /// Account that pays for the gas instead of the sender.
+    pub paymaster: Option<Address>,
+    /// Key of the nonce, the sequence of a non-zero key is kept by the nonce manager.
+    pub nonce_key: U256,
+    /// Gas limit shared by the deployment and the account validation frames.
+    pub validation_gas_limit: u64,
+    /// Gas limit of the paymaster validation frame.
+    pub paymaster_validation_gas_limit: u64,
+    /// Gas limit of the paymaster post-operation frame.
+    pub post_op_gas_limit: u64,
+    /// Hash of the transaction signed by the sender, passed to the validation frames.
+    pub tx_hash: B256,
+    /// Encoded transaction, passed to the validation frames.
+    ///
+    /// It contains the signature of the sender and the paymaster data that the validation
+    /// frames check.
+    pub encoded_tx: Bytes,
+}
+
+impl AaTransactionParts {
+    /// Returns the intrinsic gas, the base gas and the calldata cost of the encoded transaction.
+    pub fn intrinsic_gas(&self) -> u64 {
+        AA_BASE_GAS + get_tokens_in_calldata(&self.encoded_tx, true) * STANDARD_TOKEN_COST
+    }
+
+    /// Returns the total gas limit of a transaction with the given execution gas limit.
+    pub fn total_gas_limit(&self, call_gas_limit: u64) -> u64 {
+        self.intrinsic_gas()
+            .saturating_add(self.validation_gas_limit)
+            .saturating_add(self.paymaster_validation_gas_limit)
+            .saturating_add(call_gas_limit)
+            .saturating_add(self.post_op_gas_limit)
+    }
+}
+
+/// Transaction that can be a native account abstraction transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AaTransaction<T: Transaction> {
+    /// Base transaction fields.
+    pub base: T,
+    /// Account abstraction parts, set if the transaction is an account abstraction transaction.
+    pub aa: Option<AaTransactionParts>,
+}
+
+impl<T: Transaction> AsRef<T> for AaTransaction<T> {
+    fn as_ref(&self) -> &T {
+        &self.base
+    }
+}
+
+impl<T: Transaction> AaTransaction<T> {
+    /// Create a new transaction that is not an account abstraction transaction.
+    pub fn new(base: T) -> Self {
+        Self { base, aa: None }
+    }
+
+    /// Create a new account abstraction transaction.
+    pub fn new_aa(base: T, aa: AaTransactionParts) -> Self {
+        Self { base, aa: Some(aa) }
+    }
+}
+
+impl Default for AaTransaction<TxEnv> {
+    fn default() -> Self {
+        Self::new(TxEnv::default())
+    }
+}
+
+impl<TX: Transaction + SystemCallTx> SystemCallTx for AaTransaction<TX> {
+    fn new_system_tx_with_caller(
+        caller: Address,
+        system_contract_address: Address,
+        data: Bytes,
+    ) -> Self {
+        Self::new(TX::new_system_tx_with_caller(
+            caller,
+            system_contract_address,
+            data,
+        ))
+    }
+}
+
+impl<T: Transaction> Transaction for AaTransaction<T> {
+    type AccessListItem<'a>
+        = T::AccessListItem<'a>
+    where
+        T: 'a;
+    type Authorization<'a>
+        = T::Authorization<'a>
+    where
+        T: 'a;
+
+    fn tx_type(&self) -> u8 {
+        if self.aa.is_some() {
+            return AA_TX_TYPE;
+        }
+        self.base.tx_type()
+    }
+
+    fn caller(&self) -> Address {
+        self.base.caller()
+    }
+
+    fn gas_limit(&self) -> u64 {
+        match &self.aa {
+            Some(aa) => aa.total_gas_limit(self.base.gas_limit()),
+            None => self.base.gas_limit(),
+        }
+    }
+
+    fn value(&self) -> U256 {
+        // Account abstraction transactions do not transfer value.
+        if self.aa.is_some() {
+            return U256::ZERO;
+        }
+        self.base.value()
+    }
+
+    fn input(&self) -> &Bytes {
+        self.base.input()
+    }
+
+    fn nonce(&self) -> u64 {
+        self.base.nonce()
+    }
+
+    fn kind(&self) -> TxKind {
+        if self.aa.is_some() {
+            return TxKind::Call(self.base.caller());
+        }
+        self.base.kind()
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        self.base.chain_id()
+    }
+
+    fn access_list(&self) -> Option<impl Iterator<Item = Self::AccessListItem<'_>>> {
+        self.base.access_list()
+    }
+
+    fn max_priority_fee_per_gas(&self) -> Option<u128> {
+        self.base.max_priority_fee_per_gas()
+    }
+
+    fn max_fee_per_gas(&self) -> u128 {
+        self.base.max_fee_per_gas()
+    }
+
+    fn gas_price(&self) -> u128 {
+        self.base.gas_price()
+    }
+
+    fn blob_versioned_hashes(&self) -> &[B256] {
+        self.base.blob_versioned_hashes()
+    }
+
+    fn max_fee_per_blob_gas(&self) -> u128 {
+        self.base.max_fee_per_blob_gas()
+    }
+
+    fn effective_gas_price(&self, base_fee: u128) -> u128 {
+        self.base.effective_gas_price(base_fee)
+    }
+
+    fn authorization_list_len(&self) -> usize {
+        self.base.authorization_list_len()
+    }
+
+    fn authorization_list(&self) -> impl Iterator<Item = Self::Authorization<'_>> {
+        self.base.authorization_list()
+    }
+}
+
+impl<T: Transaction> AaTxTr for AaTransaction<T> {
+    fn aa(&self) -> Option<&AaTransactionParts> {
+        self.aa.as_ref()
+    }
+
+    fn call_gas_limit(&self) -> u64 {
+        self.base.gas_limit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aa_gas_limit() {
+        let aa = AaTransactionParts {
+            validation_gas_limit: 100_000,
+            paymaster_validation_gas_limit: 50_000,
+            post_op_gas_limit: 20_000,
+            encoded_tx: Bytes::from_static(&[0x05, 0x00]),
+            ..Default::default()
+        };
+        let tx = AaTransaction::new_aa(TxEnv::builder().gas_limit(30_000).build_fill(), aa);
+
+        assert_eq!(tx.tx_type(), AA_TX_TYPE);
+        assert_eq!(tx.call_gas_limit(), 30_000);
+        // 15000 base gas, 16 + 4 calldata gas and the four gas limits.
+        assert_eq!(tx.gas_limit(), 15_020 + 200_000);
+        assert_eq!(tx.payer(), tx.caller());
+    }
+}
@@ -0,0 +1,604 @@
+//! Execution of OP Stack blocks on top of [`State`].
+//!
+//! [`OpBlockExecutor`] executes the transactions of the payload attributes of an L2 block and
+//! applies the block level rules of the OP Stack:
+//!
+//! - The first transaction is the L1 attributes deposit and all deposits, including the network
+//!   upgrade transactions, come before the other transactions.
+//! - The create2deployer is deployed at the start of the first Canyon block.
+//! - The [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) parent beacon block root is stored
+//!   from Ecotone and the [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) parent block hash
+//!   from Isthmus.
+//! - Receipts of deposits contain the deposit nonce from Regolith and the deposit receipt version
+//!   from Canyon. Receipts of other transactions contain the L1 fee.
+//! - The DA footprint of the non deposit transactions is counted against the block gas limit from
+//!   Jovian.
+//!
+//! The database of the EVM needs to be a [`State`] built with
+//! [`StateBuilder::with_bundle_update`](revm::database::StateBuilder::with_bundle_update) so the
+//! [`BundleState`] of the block can be produced.
+use crate::{
+    api::exec::OpContextTr,
+    constants::CREATE_2_DEPLOYER,
+    l1_attributes::{L1AttributesError, L1BlockAttributes},
+    transaction::RollupTxTr,
+    L1BlockInfo, OpHaltReason, OpSpecId, OpTransaction, OpTransactionError,
+};
+use core::fmt;
+use revm::{
+    builder::{Receipt, BEACON_ROOTS_ADDRESS, HISTORY_STORAGE_ADDRESS},
+    context::{BlockEnv, Cfg, ContextTr, TxEnv},
+    context_interface::{
+        block::BlobExcessGasAndPrice,
+        result::{EVMError, ExecutionResult},
+        JournalTr, Transaction,
+    },
+    database::{states::bundle_state::BundleRetention, BundleState, State},
+    database_interface::Database,
+    handler::{system_call::SystemCallEvm, EvmTr},
+    primitives::{Address, Bytes, B256, U256},
+    state::{Account, Bytecode, EvmState},
+    DatabaseCommit, ExecuteCommitEvm,
+};
+use rollup::L1DataFee;
+use std::vec::Vec;
+
+/// Version of the deposit receipts from Canyon.
+pub const DEPOSIT_RECEIPT_VERSION: u64 = 1;
+
+/// Attributes of the L2 block to execute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpPayloadAttributes {
+    /// Block timestamp.
+    pub timestamp: u64,
+    /// Block prevrandao.
+    pub prev_randao: B256,
+    /// Block beneficiary.
+    pub suggested_fee_recipient: Address,
+    /// Parent beacon block root, required from Ecotone.
+    pub parent_beacon_block_root: Option<B256>,
+    /// Block gas limit.
+    pub gas_limit: u64,
+    /// Block base fee, calculated from the parent block.
+    pub base_fee: u64,
+    /// Transactions of the block, starting with the L1 attributes deposit.
+    pub transactions: Vec<OpTransaction<TxEnv>>,
+}
+
+impl OpPayloadAttributes {
+    /// Returns the environment of the block with the given number.
+    ///
+    /// Excess blob gas of OP Stack blocks is always zero from Ecotone.
+    pub fn block_env(&self, number: u64, spec: OpSpecId) -> BlockEnv {
+        BlockEnv {
+            number: U256::from(number),
+            beneficiary: self.suggested_fee_recipient,
+            timestamp: U256::from(self.timestamp),
+            gas_limit: self.gas_limit,
+            basefee: self.base_fee,
+            difficulty: U256::ZERO,
+            prevrandao: Some(self.prev_randao),
+            blob_excess_gas_and_price: spec
+                .is_enabled_in(OpSpecId::ECOTONE)
+                .then(|| BlobExcessGasAndPrice::new_with_spec(0, spec.into_eth_spec())),
+        }
+    }
+}
+
+/// Parent of the L2 block to execute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OpParentBlock {
+    /// Block number.
+    pub number: u64,
+    /// Block hash.
+    pub hash: B256,
+    /// Spec of the block, used to detect the hardfork activation blocks.
+    pub spec: OpSpecId,
+}
+
+/// Receipt of a transaction of an OP Stack block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpReceipt {
+    /// Fields shared with Ethereum receipts.
+    pub inner: Receipt,
+    /// Nonce of the deposit sender before the deposit, set for deposits from Regolith.
+    pub deposit_nonce: Option<u64>,
+    /// Receipt version, set for deposits from Canyon.
+    pub deposit_receipt_version: Option<u64>,
+    /// L1 data fee, set for non deposit transactions.
+    pub l1_fee: Option<U256>,
+}
+
+/// Output of [`OpBlockExecutor::execute_block`].
+#[derive(Debug)]
+pub struct OpBlockExecutionOutput {
+    /// Environment of the executed block.
+    pub block: BlockEnv,
+    /// Execution results of the transactions.
+    pub results: Vec<ExecutionResult<OpHaltReason>>,
+    /// Receipts of the transactions.
+    pub receipts: Vec<OpReceipt>,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// DA footprint of the block from Jovian, zero before.
+    pub da_footprint_used: u64,
+    /// State changes of the block, including the pre-execution changes.
+    pub bundle_state: BundleState,
+}
+
+/// Errors of the OP Stack block execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpBlockError<EvmError> {
+    /// Block has no transactions or the first one is not a deposit.
+    MissingL1Attributes,
+    /// Calldata of the L1 attributes deposit is invalid.
+    L1Attributes(L1AttributesError),
+    /// Deposit comes after a non deposit transaction.
+    DepositAfterUserTransaction {
+        /// Index of the deposit.
+        index: usize,
+    },
+    /// Parent beacon block root is not set from Ecotone.
+    MissingParentBeaconBlockRoot,
+    /// Code of the create2deployer is needed in the Canyon activation block.
+    MissingCreate2DeployerCode,
+    /// Transaction gas limit exceeds the gas left in the block.
+    BlockGasLimitReached {
+        /// Index of the transaction.
+        index: usize,
+    },
+    /// DA footprint of the transactions exceeds the block gas limit, from Jovian.
+    DaFootprintLimitReached {
+        /// Index of the transaction.
+        index: usize,
+    },
+    /// Transaction failed validation or execution.
+    Transaction {
+        /// Index of the transaction.
+        index: usize,
+        /// Error returned by the EVM.
+        error: EvmError,
+    },
+    /// Pre-execution changes failed.
+    Evm(EvmError),
+}
+
+impl<EvmError: fmt::Display> fmt::Display for OpBlockError<EvmError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingL1Attributes => write!(f, "missing L1 attributes deposit"),
+            Self::L1Attributes(error) => write!(f, "invalid L1 attributes deposit: {error}"),
+            Self::DepositAfterUserTransaction { index } => {
+                write!(f, "tx {index}: deposit after a non deposit transaction")
+            }
+            Self::MissingParentBeaconBlockRoot => write!(f, "missing parent beacon block root"),
+            Self::MissingCreate2DeployerCode => write!(f, "missing create2deployer code"),
+            Self::BlockGasLimitReached { index } => {
+                write!(f, "tx {index}: block gas limit reached")
+            }
+            Self::DaFootprintLimitReached { index } => {
+                write!(f, "tx {index}: block DA footprint limit reached")
+            }
+            Self::Transaction { index, error } => write!(f, "tx {index}: {error}"),
+            Self::Evm(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl<EvmError> core::error::Error for OpBlockError<EvmError> where
+    EvmError: fmt::Debug + fmt::Display
+{
+}
+
+impl<EvmError> From<L1AttributesError> for OpBlockError<EvmError> {
+    fn from(value: L1AttributesError) -> Self {
+        Self::L1Attributes(value)
+    }
+}
+
+/// Executes OP Stack blocks.
+///
+/// Spec of the block is taken from the configuration of the EVM and needs to be set by the caller.
+#[derive(Debug)]
+pub struct OpBlockExecutor<'a, EVM> {
+    evm: &'a mut EVM,
+    create2_deployer_code: Option<Bytecode>,
+}
+
+impl<'a, EVM> OpBlockExecutor<'a, EVM> {
+    /// Creates a new executor.
+    pub fn new(evm: &'a mut EVM) -> Self {
+        Self {
+            evm,
+            create2_deployer_code: None,
+        }
+    }
+
+    /// Sets the code of the create2deployer that is deployed in the Canyon activation block.
+    ///
+    /// It is only needed if the Canyon activation block is executed.
+    pub fn with_create2_deployer_code(mut self, code: Bytecode) -> Self {
+        self.create2_deployer_code = Some(code);
+        self
+    }
+}
+
+impl<EVM, DB> OpBlockExecutor<'_, EVM>
+where
+    EVM: ExecuteCommitEvm<
+            Block = BlockEnv,
+            Tx = OpTransaction<TxEnv>,
+            ExecutionResult = ExecutionResult<OpHaltReason>,
+            Error = EVMError<DB::Error, OpTransactionError>,
+        > + SystemCallEvm
+        + EvmTr<Context: OpContextTr<Db = State<DB>>>,
+    DB: Database,
+{
+    /// Executes the block following `parent` and merges its transitions into the returned
+    /// [`BundleState`].
+    pub fn execute_block(
+        &mut self,
+        parent: &OpParentBlock,
+        attributes: OpPayloadAttributes,
+    ) -> Result<OpBlockExecutionOutput, OpBlockError<EVM::Error>> {
+        let spec = self.evm.ctx_ref().cfg().spec();
+        validate_transaction_order(&attributes.transactions)?;
+        if spec.is_enabled_in(OpSpecId::ECOTONE) && attributes.parent_beacon_block_root.is_none() {
+            return Err(OpBlockError::MissingParentBeaconBlockRoot);
+        }
+
+        let block = attributes.block_env(parent.number + 1, spec);
+        self.evm.set_block(block.clone());
+        // L1 block info is loaded again after the L1 attributes deposit.
+        *self.evm.ctx().chain_mut() = L1BlockInfo::default();
+        self.apply_pre_execution_changes(parent, &attributes, spec)?;
+
+        let mut gas_used = 0;
+        let mut da_footprint_used = 0u64;
+        let mut results = Vec::with_capacity(attributes.transactions.len());
+        let mut receipts = Vec::with_capacity(attributes.transactions.len());
+        for (index, tx) in attributes.transactions.into_iter().enumerate() {
+            if tx.gas_limit() > block.gas_limit.saturating_sub(gas_used) {
+                return Err(OpBlockError::BlockGasLimitReached { index });
+            }
+            let is_deposit = tx.is_deposit();
+            let deposit_nonce = if is_deposit && spec.is_enabled_in(OpSpecId::REGOLITH) {
+                let caller = self
+                    .evm
+                    .ctx()
+                    .db_mut()
+                    .basic(tx.caller())
+                    .map_err(|error| OpBlockError::Evm(EVMError::Database(error)))?;
+                Some(caller.map(|info| info.nonce).unwrap_or_default())
+            } else {
+                None
+            };
+            let tx_type = tx.tx_type();
+            let enveloped_tx = tx.enveloped_tx.clone();
+            if let Some(enveloped_tx) = enveloped_tx
+                .as_ref()
+                .filter(|_| !is_deposit && spec.is_enabled_in(OpSpecId::JOVIAN))
+            {
+                // DA footprint only depends on the envelope and the L1 block info, so it is
+                // checked before the transaction is committed.
+                let (_, _, _, journal, chain, _) = self.evm.ctx().all_mut();
+                chain
+                    .load(journal.db_mut(), block.number, spec)
+                    .map_err(|error| OpBlockError::Evm(EVMError::Database(error)))?;
+                da_footprint_used =
+                    da_footprint_used.saturating_add(chain.da_footprint(enveloped_tx));
+                if da_footprint_used > block.gas_limit {
+                    return Err(OpBlockError::DaFootprintLimitReached { index });
+                }
+            }
+
+            let result = self
+                .evm
+                .transact_commit(tx)
+                .map_err(|error| OpBlockError::Transaction { index, error })?;
+
+            gas_used += result.gas_used();
+            let l1_fee = match enveloped_tx {
+                Some(enveloped_tx) if !is_deposit => Some(
+                    self.evm
+                        .ctx()
+                        .chain_mut()
+                        .calculate_tx_l1_cost(&enveloped_tx, spec),
+                ),
+                _ => None,
+            };
+            receipts.push(OpReceipt {
+                inner: Receipt {
+                    tx_type,
+                    success: result.is_success(),
+                    cumulative_gas_used: gas_used,
+                    logs: result.logs().to_vec(),
+                },
+                deposit_nonce,
+                deposit_receipt_version: (is_deposit && spec.is_enabled_in(OpSpecId::CANYON))
+                    .then_some(DEPOSIT_RECEIPT_VERSION),
+                l1_fee,
+            });
+            results.push(result);
+        }
+
+        let db = self.evm.ctx().db_mut();
+        db.merge_transitions(BundleRetention::Reverts);
+        Ok(OpBlockExecutionOutput {
+            block,
+            results,
+            receipts,
+            gas_used,
+            da_footprint_used,
+            bundle_state: db.take_bundle(),
+        })
+    }
+
+    /// Applies the changes made before the transactions of the block.
+    fn apply_pre_execution_changes(
+        &mut self,
+        parent: &OpParentBlock,
+        attributes: &OpPayloadAttributes,
+        spec: OpSpecId,
+    ) -> Result<(), OpBlockError<EVM::Error>> {
+        if spec.is_enabled_in(OpSpecId::CANYON) && !parent.spec.is_enabled_in(OpSpecId::CANYON) {
+            let code = self
+                .create2_deployer_code
+                .clone()
+                .ok_or(OpBlockError::MissingCreate2DeployerCode)?;
+            let db = self.evm.ctx().db_mut();
+            let info = db
+                .basic(CREATE_2_DEPLOYER)
+                .map_err(|error| OpBlockError::Evm(EVMError::Database(error)))?
+                .unwrap_or_default();
+            let mut account = Account::from(info.with_code(code));
+            account.mark_touch();
+            db.commit(EvmState::from_iter([(CREATE_2_DEPLOYER, account)]));
+        }
+        if spec.is_enabled_in(OpSpecId::ISTHMUS) {
+            self.system_call(HISTORY_STORAGE_ADDRESS, parent.hash.into())?;
+        }
+        if let Some(root) = attributes.parent_beacon_block_root {
+            if spec.is_enabled_in(OpSpecId::ECOTONE) {
+                self.system_call(BEACON_ROOTS_ADDRESS, root.into())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes and commits a system call.
+    fn system_call(
+        &mut self,
+        address: Address,
+        data: Bytes,
+    ) -> Result<(), OpBlockError<EVM::Error>> {
+        let output = self
+            .evm
+            .system_call(address, data)
+            .map_err(OpBlockError::Evm)?;
+        self.evm.commit(output.state);
+        Ok(())
+    }
+}
+
+/// Checks that the block starts with the L1 attributes deposit and that deposits come first.
+fn validate_transaction_order<EvmError>(
+    transactions: &[OpTransaction<TxEnv>],
+) -> Result<(), OpBlockError<EvmError>> {
+    let Some(l1_attributes) = transactions.first().filter(|tx| tx.is_deposit()) else {
+        return Err(OpBlockError::MissingL1Attributes);
+    };
+    L1BlockAttributes::decode(l1_attributes.input())?;
+
+    if let Some(first_user_tx) = transactions.iter().position(|tx| !tx.is_deposit()) {
+        if let Some(offset) = transactions[first_user_tx..]
+            .iter()
+            .position(|tx| tx.is_deposit())
+        {
+            return Err(OpBlockError::DepositAfterUserTransaction {
+                index: first_user_tx + offset,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::{
+            DA_FOOTPRINT_GAS_SCALAR_OFFSET, DA_FOOTPRINT_GAS_SCALAR_SLOT, L1_BLOCK_CONTRACT,
+        },
+        DefaultOp, OpBuilder,
+    };
+    use revm::{
+        context::CfgEnv,
+        database::InMemoryDB,
+        primitives::{address, bytes},
+        state::AccountInfo,
+        Context,
+    };
+
+    const DEPOSITOR: Address = address!("0xDeaDDEaDDeAdDeAdDEAdDEaddeAddEAdDEAd0001");
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const RECEIVER: Address = address!("0x2000000000000000000000000000000000000002");
+
+    fn l1_attributes_deposit() -> OpTransaction<TxEnv> {
+        let calldata = L1BlockAttributes::default().encode();
+        OpTransaction::builder()
+            .base(
+                TxEnv::builder()
+                    .caller(DEPOSITOR)
+                    .call(L1_BLOCK_CONTRACT)
+                    .data(calldata)
+                    .gas_limit(1_000_000),
+            )
+            .source_hash(B256::from([1; 32]))
+            .build_fill()
+    }
+
+    fn transfer() -> OpTransaction<TxEnv> {
+        OpTransaction::builder()
+            .base(
+                TxEnv::builder()
+                    .caller(CALLER)
+                    .call(RECEIVER)
+                    .value(U256::from(1))
+                    .gas_limit(21_000),
+            )
+            .enveloped_tx(Some(bytes!("0x02")))
+            .build_fill()
+    }
+
+    fn attributes(transactions: Vec<OpTransaction<TxEnv>>) -> OpPayloadAttributes {
+        OpPayloadAttributes {
+            timestamp: 2,
+            gas_limit: 30_000_000,
+            transactions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn execute_canyon_activation_block() {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(CALLER, AccountInfo::default().with_balance(U256::from(100)));
+        let state = State::builder()
+            .with_database(db)
+            .with_bundle_update()
+            .build();
+        let mut evm = Context::op()
+            .with_db(state)
+            .with_cfg(CfgEnv::new_with_spec(OpSpecId::CANYON))
+            .build_op();
+        let parent = OpParentBlock {
+            number: 1,
+            hash: B256::ZERO,
+            spec: OpSpecId::REGOLITH,
+        };
+        let deployer_code = Bytecode::new_legacy(bytes!("0x00"));
+
+        let mut executor = OpBlockExecutor::new(&mut evm);
+        assert_eq!(
+            executor
+                .execute_block(&parent, attributes(vec![l1_attributes_deposit()]))
+                .unwrap_err(),
+            OpBlockError::MissingCreate2DeployerCode
+        );
+
+        let mut executor = executor.with_create2_deployer_code(deployer_code.clone());
+        let output = executor
+            .execute_block(
+                &parent,
+                attributes(vec![l1_attributes_deposit(), transfer()]),
+            )
+            .unwrap();
+
+        assert_eq!(output.block.number, U256::from(2));
+        let [deposit, transfer] = &output.receipts[..] else {
+            panic!("two receipts");
+        };
+        assert_eq!(deposit.deposit_nonce, Some(0));
+        assert_eq!(deposit.deposit_receipt_version, Some(1));
+        assert_eq!(deposit.l1_fee, None);
+        assert_eq!(transfer.deposit_nonce, None);
+        assert_eq!(transfer.l1_fee, Some(U256::ZERO));
+        assert_eq!(
+            transfer.inner.cumulative_gas_used,
+            deposit.inner.cumulative_gas_used + 21_000
+        );
+        assert_eq!(output.gas_used, transfer.inner.cumulative_gas_used);
+
+        let deployer = output.bundle_state.account(&CREATE_2_DEPLOYER).unwrap();
+        assert_eq!(
+            deployer.info.as_ref().map(|info| info.code_hash),
+            Some(deployer_code.hash_slow())
+        );
+        let receiver = output.bundle_state.account(&RECEIVER).unwrap();
+        assert_eq!(
+            receiver.info.as_ref().map(|info| info.balance),
+            Some(U256::from(1))
+        );
+    }
+
+    #[test]
+    fn invalid_transaction_order() {
+        let mut evm = Context::op()
+            .with_db(State::builder().with_bundle_update().build())
+            .with_cfg(CfgEnv::new_with_spec(OpSpecId::REGOLITH))
+            .build_op();
+        let parent = OpParentBlock {
+            number: 1,
+            hash: B256::ZERO,
+            spec: OpSpecId::REGOLITH,
+        };
+        let mut executor = OpBlockExecutor::new(&mut evm);
+
+        assert_eq!(
+            executor
+                .execute_block(&parent, attributes(vec![transfer()]))
+                .unwrap_err(),
+            OpBlockError::MissingL1Attributes
+        );
+        assert_eq!(
+            executor
+                .execute_block(
+                    &parent,
+                    attributes(vec![
+                        l1_attributes_deposit(),
+                        transfer(),
+                        l1_attributes_deposit()
+                    ])
+                )
+                .unwrap_err(),
+            OpBlockError::DepositAfterUserTransaction { index: 2 }
+        );
+    }
+
+    #[test]
+    fn da_footprint_limit_jovian() {
+        let parent = OpParentBlock {
+            number: 1,
+            hash: B256::ZERO,
+            spec: OpSpecId::JOVIAN,
+        };
+        let execute = |da_footprint_gas_scalar: u16| {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(CALLER, AccountInfo::default().with_balance(U256::from(100)));
+            let mut scalars = [0u8; 32];
+            scalars[DA_FOOTPRINT_GAS_SCALAR_OFFSET..DA_FOOTPRINT_GAS_SCALAR_OFFSET + 2]
+                .copy_from_slice(&da_footprint_gas_scalar.to_be_bytes());
+            db.insert_account_storage(
+                L1_BLOCK_CONTRACT,
+                DA_FOOTPRINT_GAS_SCALAR_SLOT,
+                U256::from_be_bytes(scalars),
+            )
+            .unwrap();
+            let state = State::builder()
+                .with_database(db)
+                .with_bundle_update()
+                .build();
+            let mut evm = Context::op()
+                .with_db(state)
+                .with_cfg(CfgEnv::new_with_spec(OpSpecId::JOVIAN))
+                .build_op();
+            OpBlockExecutor::new(&mut evm).execute_block(
+                &parent,
+                OpPayloadAttributes {
+                    parent_beacon_block_root: Some(B256::ZERO),
+                    gas_limit: 2_000_000,
+                    ..attributes(vec![l1_attributes_deposit(), transfer()])
+                },
+            )
+        };
+
+        // Minimal compressed size of 100 bytes times the scalar.
+        let output = execute(400).unwrap();
+        assert_eq!(output.da_footprint_used, 40_000);
+        assert_eq!(
+            execute(u16::MAX).unwrap_err(),
+            OpBlockError::DaFootprintLimitReached { index: 1 }
+        );
+    }
+}
@@ -66,3 +66,12 @@ pub const BASE_FEE_RECIPIENT: Address = address!("0x4200000000000000000000000000
 
 /// The address of the L1Block contract.
 pub const L1_BLOCK_CONTRACT: Address = address!("0x4200000000000000000000000000000000000015");
+
+/// The address of the GasPriceOracle predeploy.
+pub const GAS_PRICE_ORACLE: Address = address!("0x420000000000000000000000000000000000000F");
+
+/// The address of the CrossL2Inbox predeploy that validates interop executing messages.
+pub const CROSS_L2_INBOX: Address = address!("0x4200000000000000000000000000000000000022");
+
+/// The address of the create2deployer contract that is deployed in the Canyon upgrade.
+pub const CREATE_2_DEPLOYER: Address = address!("0x13b0D85CcB8bf860b6b79AF3029fCA081AE9beF2");
@@ -0,0 +1,401 @@
+//! Estimation of the full cost of an L2 transaction.
+//!
+//! [`EstimateFeeEvm::estimate_fee`] finds the gas limit the L2 execution needs and prices the
+//! transaction with the [`L1BlockInfo`] of the current block. Fully enveloped transactions are
+//! priced with their enveloped bytes. Unsigned requests are priced with an EIP-1559 envelope
+//! that carries a placeholder signature, see [`estimation_envelope`].
+use crate::{
+    api::exec::OpContextTr,
+    constants::NON_ZERO_BYTE_COST,
+    handler::IsTxError,
+    transaction::{estimate_tx_compressed_size, RollupTxTr},
+    L1BlockInfo, OpHaltReason, OpSpecId, OpTransaction,
+};
+use core::fmt;
+use revm::{
+    context::{Block, Cfg, ContextTr, TxEnv},
+    context_interface::{result::ExecutionResult, JournalTr, Transaction},
+    handler::{ContextTrDbError, EvmTr},
+    primitives::{keccak256, Bytes, TxKind, U256},
+    ExecuteEvm,
+};
+use rollup::L1DataFee;
+use std::vec::Vec;
+
+/// Cost breakdown of an L2 transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpFeeEstimate {
+    /// Gas limit needed by the L2 execution.
+    pub l2_gas: u64,
+    /// L2 execution fee, `l2_gas` times the effective gas price.
+    pub l2_fee: U256,
+    /// L1 data fee.
+    pub l1_data_fee: U256,
+    /// Part of the L1 data fee that pays for the L1 base fee.
+    pub l1_base_fee_component: U256,
+    /// Part of the L1 data fee that pays for the L1 blob base fee. Zero before Ecotone.
+    pub l1_blob_fee_component: U256,
+    /// Operator fee charged for `l2_gas`. Zero before Isthmus.
+    pub operator_fee: U256,
+    /// DA footprint of the transaction. Zero before Jovian.
+    pub da_footprint: u64,
+}
+
+impl OpFeeEstimate {
+    /// Returns the sum of the L2 execution fee, L1 data fee and operator fee.
+    pub fn total_fee(&self) -> U256 {
+        self.l2_fee
+            .saturating_add(self.l1_data_fee)
+            .saturating_add(self.operator_fee)
+    }
+}
+
+/// Errors of the fee estimation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EstimateFeeError<EvmError> {
+    /// Transaction reverted or halted with the maximum gas limit.
+    Failed(ExecutionResult<OpHaltReason>),
+    /// Transaction is invalid with the maximum gas limit or loading the state failed.
+    Evm(EvmError),
+}
+
+impl<EvmError: fmt::Display> fmt::Display for EstimateFeeError<EvmError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(ExecutionResult::Revert { .. }) => write!(f, "execution reverted"),
+            Self::Failed(_) => write!(f, "execution halted"),
+            Self::Evm(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl<EvmError> core::error::Error for EstimateFeeError<EvmError> where
+    EvmError: fmt::Debug + fmt::Display
+{
+}
+
+/// Estimate the cost of L2 transactions.
+pub trait EstimateFeeEvm: ExecuteEvm {
+    /// Estimates the gas and the fees of the transaction in the current block.
+    ///
+    /// Gas limit of the transaction, capped by the block gas limit, is the upper bound of the
+    /// gas search. Executions are not committed.
+    fn estimate_fee(
+        &mut self,
+        tx: Self::Tx,
+    ) -> Result<OpFeeEstimate, EstimateFeeError<Self::Error>>;
+}
+
+impl<EVM> EstimateFeeEvm for EVM
+where
+    EVM: ExecuteEvm<Tx = OpTransaction<TxEnv>, ExecutionResult = ExecutionResult<OpHaltReason>>
+        + EvmTr<Context: OpContextTr>,
+    EVM::Error: IsTxError + From<ContextTrDbError<EVM::Context>>,
+{
+    fn estimate_fee(
+        &mut self,
+        mut tx: Self::Tx,
+    ) -> Result<OpFeeEstimate, EstimateFeeError<Self::Error>> {
+        let is_deposit = tx.is_deposit();
+        let is_unsigned = tx.enveloped_tx.is_none() && !is_deposit;
+        let block_gas_limit = self.ctx_ref().block().gas_limit();
+        tx.base.gas_limit = tx.base.gas_limit.min(block_gas_limit);
+        if is_unsigned {
+            tx.enveloped_tx = Some(estimation_envelope(&tx.base));
+        }
+
+        let l2_gas = estimate_gas(self, tx.clone())?;
+        let mut estimate = OpFeeEstimate {
+            l2_gas,
+            l2_fee: U256::from(l2_gas)
+                * U256::from(tx.effective_gas_price(self.ctx_ref().block().basefee() as u128)),
+            ..Default::default()
+        };
+        if is_deposit {
+            return Ok(estimate);
+        }
+        tx.base.gas_limit = l2_gas;
+        let enveloped_tx = if is_unsigned {
+            estimation_envelope(&tx.base)
+        } else {
+            tx.enveloped_tx.unwrap_or_default()
+        };
+
+        let spec = self.ctx_ref().cfg().spec();
+        let (block, _, _, journal, chain, _) = self.ctx().all_mut();
+        chain
+            .load(journal.db_mut(), block.number(), spec)
+            .map_err(|error| EstimateFeeError::Evm(error.into()))?;
+        let mut l1_block_info = chain.clone();
+        l1_block_info.clear_tx_l1_cost();
+
+        estimate.l1_data_fee = l1_block_info.calculate_tx_l1_cost(&enveloped_tx, spec);
+        (
+            estimate.l1_base_fee_component,
+            estimate.l1_blob_fee_component,
+        ) = l1_fee_components(&l1_block_info, &enveloped_tx, spec, estimate.l1_data_fee);
+        if spec.is_enabled_in(OpSpecId::ISTHMUS) {
+            estimate.operator_fee =
+                l1_block_info.operator_fee_charge(&enveloped_tx, U256::from(l2_gas), spec);
+        }
+        if spec.is_enabled_in(OpSpecId::JOVIAN) {
+            estimate.da_footprint = l1_block_info.da_footprint(&enveloped_tx);
+        }
+        Ok(estimate)
+    }
+}
+
+/// Binary searches the lowest gas limit the transaction succeeds with.
+fn estimate_gas<EVM>(
+    evm: &mut EVM,
+    mut tx: OpTransaction<TxEnv>,
+) -> Result<u64, EstimateFeeError<EVM::Error>>
+where
+    EVM: ExecuteEvm<Tx = OpTransaction<TxEnv>, ExecutionResult = ExecutionResult<OpHaltReason>>,
+    EVM::Error: IsTxError,
+{
+    let result = evm
+        .transact(tx.clone())
+        .map_err(EstimateFeeError::Evm)?
+        .result;
+    if !result.is_success() {
+        return Err(EstimateFeeError::Failed(result));
+    }
+
+    // Gas limit can't be lower than the gas used.
+    let mut lowest_failing = result.gas_used().saturating_sub(1);
+    let mut highest_succeeding = tx.base.gas_limit;
+    while lowest_failing + 1 < highest_succeeding {
+        let gas_limit = lowest_failing + (highest_succeeding - lowest_failing) / 2;
+        tx.base.gas_limit = gas_limit;
+        match evm.transact(tx.clone()) {
+            Ok(output) if output.result.is_success() => highest_succeeding = gas_limit,
+            Ok(_) => lowest_failing = gas_limit,
+            Err(error) if error.is_tx_error() => lowest_failing = gas_limit,
+            Err(error) => return Err(EstimateFeeError::Evm(error)),
+        }
+    }
+    Ok(highest_succeeding)
+}
+
+/// Splits the L1 data fee into the parts paid for the L1 base fee and the L1 blob base fee.
+///
+/// Parts are rounded down separately so their sum can be lower than `l1_data_fee`.
+fn l1_fee_components(
+    l1_block_info: &L1BlockInfo,
+    enveloped_tx: &[u8],
+    spec: OpSpecId,
+    l1_data_fee: U256,
+) -> (U256, U256) {
+    if !spec.is_enabled_in(OpSpecId::ECOTONE) || l1_block_info.empty_ecotone_scalars {
+        return (l1_data_fee, U256::ZERO);
+    }
+    let base_fee_scaled = l1_block_info
+        .l1_base_fee
+        .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
+        .saturating_mul(l1_block_info.l1_base_fee_scalar);
+    let blob_fee_scaled = l1_block_info
+        .l1_blob_base_fee
+        .unwrap_or_default()
+        .saturating_mul(l1_block_info.l1_blob_base_fee_scalar.unwrap_or_default());
+
+    let (size, divisor) = if spec.is_enabled_in(OpSpecId::FJORD) {
+        (
+            U256::from(estimate_tx_compressed_size(enveloped_tx)),
+            U256::from(1_000_000_000_000u64),
+        )
+    } else {
+        (
+            l1_block_info.data_gas(enveloped_tx, spec),
+            U256::from(1_000_000 * NON_ZERO_BYTE_COST),
+        )
+    };
+    (
+        size.saturating_mul(base_fee_scaled) / divisor,
+        size.saturating_mul(blob_fee_scaled) / divisor,
+    )
+}
+
+/// Returns the EIP-1559 envelope of the transaction with a placeholder signature.
+///
+/// Signature values are derived from the hash of the transaction so they don't compress better
+/// than a real signature.
+pub fn estimation_envelope(tx: &TxEnv) -> Bytes {
+    let mut fields = Vec::new();
+    rlp_uint(&mut fields, &tx.chain_id.unwrap_or_default().to_be_bytes());
+    rlp_uint(&mut fields, &tx.nonce.to_be_bytes());
+    rlp_uint(
+        &mut fields,
+        &tx.gas_priority_fee.unwrap_or_default().to_be_bytes(),
+    );
+    rlp_uint(&mut fields, &tx.gas_price.to_be_bytes());
+    rlp_uint(&mut fields, &tx.gas_limit.to_be_bytes());
+    match tx.kind {
+        TxKind::Call(to) => rlp_bytes(&mut fields, to.as_slice()),
+        TxKind::Create => rlp_bytes(&mut fields, &[]),
+    }
+    rlp_uint(&mut fields, &tx.value.to_be_bytes::<32>());
+    rlp_bytes(&mut fields, &tx.data);
+    let mut access_list = Vec::new();
+    for item in tx.access_list.iter() {
+        let mut entry = Vec::new();
+        rlp_bytes(&mut entry, item.address.as_slice());
+        let mut storage_keys = Vec::new();
+        for key in &item.storage_keys {
+            rlp_bytes(&mut storage_keys, key.as_slice());
+        }
+        rlp_list(&mut entry, &storage_keys);
+        rlp_list(&mut access_list, &entry);
+    }
+    rlp_list(&mut fields, &access_list);
+
+    let r = keccak256(&fields);
+    let s = keccak256(r);
+    rlp_uint(&mut fields, &[1]);
+    rlp_uint(&mut fields, r.as_slice());
+    rlp_uint(&mut fields, s.as_slice());
+
+    let mut envelope = std::vec![2];
+    rlp_list(&mut envelope, &fields);
+    envelope.into()
+}
+
+/// Appends the RLP encoding of a big endian integer.
+fn rlp_uint(out: &mut Vec<u8>, be_bytes: &[u8]) {
+    let start = be_bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(be_bytes.len());
+    rlp_bytes(out, &be_bytes[start..]);
+}
+
+/// Appends the RLP encoding of a byte string.
+fn rlp_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    if let [byte] = bytes {
+        if *byte < 0x80 {
+            out.push(*byte);
+            return;
+        }
+    }
+    rlp_header(out, 0x80, bytes.len());
+    out.extend_from_slice(bytes);
+}
+
+/// Appends the RLP encoding of a list with the encoded `payload`.
+fn rlp_list(out: &mut Vec<u8>, payload: &[u8]) {
+    rlp_header(out, 0xc0, payload.len());
+    out.extend_from_slice(payload);
+}
+
+fn rlp_header(out: &mut Vec<u8>, offset: u8, len: usize) {
+    if len < 56 {
+        out.push(offset + len as u8);
+        return;
+    }
+    let len_bytes = (len as u64).to_be_bytes();
+    let start = len_bytes.iter().position(|byte| *byte != 0).unwrap_or(7);
+    out.push(offset + 55 + (8 - start) as u8);
+    out.extend_from_slice(&len_bytes[start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DefaultOp, OpBuilder};
+    use revm::{
+        context::CfgEnv,
+        database::InMemoryDB,
+        primitives::{address, bytes, hex, Address},
+        state::AccountInfo,
+        Context,
+    };
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const RECEIVER: Address = address!("0x2000000000000000000000000000000000000002");
+
+    #[test]
+    fn envelope_encoding() {
+        let tx = TxEnv::builder()
+            .chain_id(Some(10))
+            .nonce(1)
+            .gas_limit(21_000)
+            .call(RECEIVER)
+            .value(U256::from(1))
+            .build_fill();
+        let envelope = estimation_envelope(&tx);
+        // 0x02 || rlp([10, 1, 0, 0, 21000, RECEIVER, 1, "", [], 1, r, s])
+        assert_eq!(
+            envelope[..34],
+            hex!("02f8620a0180808252089420000000000000000000000000000000000000020180c0")
+        );
+        assert_eq!(envelope[34], 0x01);
+        assert_eq!(envelope.len(), 3 + 0x62);
+    }
+
+    #[test]
+    fn estimate_transfer_fee() {
+        let l1_block_info = L1BlockInfo {
+            l2_block: Some(U256::ZERO),
+            l1_base_fee: U256::from(1_000),
+            l1_base_fee_scalar: U256::from(1_000_000),
+            l1_blob_base_fee: Some(U256::from(10)),
+            l1_blob_base_fee_scalar: Some(U256::from(1_000_000)),
+            operator_fee_scalar: Some(U256::from(1_000_000)),
+            operator_fee_constant: Some(U256::from(5)),
+            ..Default::default()
+        };
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            CALLER,
+            AccountInfo::default().with_balance(U256::from(10u64.pow(18))),
+        );
+        let mut evm = Context::op()
+            .with_db(db)
+            .with_cfg(CfgEnv::new_with_spec(OpSpecId::ISTHMUS))
+            .with_chain(l1_block_info.clone())
+            .build_op();
+        let tx = OpTransaction::builder()
+            .base(
+                TxEnv::builder()
+                    .caller(CALLER)
+                    .call(RECEIVER)
+                    .value(U256::from(1))
+                    .gas_limit(1_000_000),
+            )
+            .build_fill();
+
+        let unsigned = OpTransaction {
+            enveloped_tx: None,
+            ..tx.clone()
+        };
+        let estimate = evm.estimate_fee(unsigned).unwrap();
+        // Compressed size of the envelope is below the minimum of 100 bytes.
+        assert_eq!(
+            estimate,
+            OpFeeEstimate {
+                l2_gas: 21_000,
+                l2_fee: U256::ZERO,
+                l1_data_fee: U256::from(1601),
+                l1_base_fee_component: U256::from(1600),
+                l1_blob_fee_component: U256::from(1),
+                operator_fee: U256::from(21_005),
+                da_footprint: 0,
+            }
+        );
+        assert_eq!(estimate.total_fee(), U256::from(22_606));
+
+        let enveloped = OpTransaction {
+            enveloped_tx: Some(bytes!("0x02")),
+            ..tx
+        };
+        let estimate = evm.estimate_fee(enveloped).unwrap();
+        assert_eq!(estimate.l2_gas, 21_000);
+        assert_eq!(
+            estimate.l1_data_fee,
+            l1_block_info
+                .clone()
+                .calculate_tx_l1_cost(&[0x02], OpSpecId::ISTHMUS)
+        );
+    }
+}
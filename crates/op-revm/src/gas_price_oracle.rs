@@ -0,0 +1,437 @@
+//! Native emulation of the `GasPriceOracle` predeploy.
+//!
+//! Dapps call the [`GAS_PRICE_ORACLE`][crate::constants::GAS_PRICE_ORACLE] predeploy to estimate
+//! the L1 fee and the operator fee of a transaction. When op-revm runs against a minimal or
+//! synthetic state the predeploy code and the `L1Block` storage are often missing, so
+//! [`GasPriceOraclePrecompiles`][crate::precompiles::GasPriceOraclePrecompiles] can answer these
+//! calls from the in-memory [`L1BlockInfo`] instead.
+//!
+//! Results match the outputs of the Solidity contract for the given [`OpSpecId`]:
+//! <https://github.com/ethereum-optimism/optimism/blob/develop/packages/contracts-bedrock/src/L2/GasPriceOracle.sol>
+use crate::{
+    constants::{NON_ZERO_BYTE_COST, OPERATOR_FEE_JOVIAN_MULTIPLIER, OPERATOR_FEE_SCALAR_DECIMAL},
+    fast_lz::flz_compress_len,
+    transaction::estimate_compressed_size,
+    L1BlockInfo, OpSpecId,
+};
+use revm::{
+    precompile::{PrecompileError, PrecompileOutput, PrecompileResult},
+    primitives::{Bytes, U256},
+};
+
+/// Selector of `getL1Fee(bytes)`.
+pub const GET_L1_FEE_SELECTOR: [u8; 4] = [0x49, 0x94, 0x8e, 0x0e];
+/// Selector of `getL1GasUsed(bytes)`.
+pub const GET_L1_GAS_USED_SELECTOR: [u8; 4] = [0xde, 0x26, 0xc4, 0xa1];
+/// Selector of `getOperatorFee(uint256)`.
+pub const GET_OPERATOR_FEE_SELECTOR: [u8; 4] = [0x27, 0x5a, 0xed, 0xd2];
+
+/// Base gas of a call, approximates the `L1Block` storage reads of the contract.
+pub const BASE_GAS: u64 = 10_000;
+/// Gas per byte of the transaction data passed to `getL1Fee` and `getL1GasUsed`.
+pub const DATA_BYTE_GAS: u64 = 16;
+
+/// Size of the signature the contract adds to the unsigned transaction, in bytes.
+const SIGNATURE_SIZE: u64 = 68;
+
+/// Runs a call to the `GasPriceOracle` predeploy.
+///
+/// Unknown selectors and malformed arguments revert with empty output, like the contract.
+pub fn run(
+    l1_block_info: &L1BlockInfo,
+    spec: OpSpecId,
+    input: &[u8],
+    gas_limit: u64,
+) -> PrecompileResult {
+    let Some((selector, args)) = input.split_first_chunk::<4>() else {
+        return revert(gas_limit);
+    };
+
+    let (gas_used, result) = match *selector {
+        GET_L1_FEE_SELECTOR | GET_L1_GAS_USED_SELECTOR => {
+            let Some(data) = decode_bytes(args) else {
+                return revert(gas_limit);
+            };
+            let gas_used = (data.len() as u64)
+                .saturating_mul(DATA_BYTE_GAS)
+                .saturating_add(BASE_GAS);
+            if *selector == GET_L1_FEE_SELECTOR {
+                (gas_used, l1_fee(l1_block_info, data, spec))
+            } else {
+                (gas_used, l1_gas_used(l1_block_info, data, spec))
+            }
+        }
+        GET_OPERATOR_FEE_SELECTOR => {
+            let Some(gas) = args.first_chunk::<32>() else {
+                return revert(gas_limit);
+            };
+            (
+                BASE_GAS,
+                operator_fee(l1_block_info, U256::from_be_bytes(*gas), spec),
+            )
+        }
+        _ => return revert(gas_limit),
+    };
+
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    Ok(PrecompileOutput::new(
+        gas_used,
+        Bytes::copy_from_slice(&result.to_be_bytes::<32>()),
+    ))
+}
+
+/// Returns the L1 fee of the unsigned transaction `data`, as `getL1Fee(bytes)`.
+pub fn l1_fee(l1_block_info: &L1BlockInfo, data: &[u8], spec: OpSpecId) -> U256 {
+    if spec.is_enabled_in(OpSpecId::FJORD) {
+        return fjord_estimated_size(data)
+            .saturating_mul(l1_block_info.calculate_l1_fee_scaled_ecotone())
+            .wrapping_div(U256::from(1_000_000_000_000u64));
+    }
+
+    let calldata_gas = calldata_gas(l1_block_info, data);
+    if spec.is_enabled_in(OpSpecId::ECOTONE) {
+        return calldata_gas
+            .saturating_mul(l1_block_info.calculate_l1_fee_scaled_ecotone())
+            .wrapping_div(U256::from(1_000_000 * NON_ZERO_BYTE_COST));
+    }
+
+    calldata_gas
+        .saturating_add(l1_block_info.l1_fee_overhead.unwrap_or_default())
+        .saturating_mul(l1_block_info.l1_base_fee)
+        .saturating_mul(l1_block_info.l1_base_fee_scalar)
+        .wrapping_div(U256::from(1_000_000))
+}
+
+/// Returns the L1 gas used by the unsigned transaction `data`, as `getL1GasUsed(bytes)`.
+pub fn l1_gas_used(l1_block_info: &L1BlockInfo, data: &[u8], spec: OpSpecId) -> U256 {
+    if spec.is_enabled_in(OpSpecId::FJORD) {
+        return fjord_estimated_size(data)
+            .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
+            .wrapping_div(U256::from(1_000_000));
+    }
+
+    let calldata_gas = calldata_gas(l1_block_info, data);
+    if spec.is_enabled_in(OpSpecId::ECOTONE) {
+        return calldata_gas;
+    }
+
+    calldata_gas.saturating_add(l1_block_info.l1_fee_overhead.unwrap_or_default())
+}
+
+/// Returns the operator fee of `gas_used`, as `getOperatorFee(uint256)`.
+///
+/// Zero prior to Isthmus.
+pub fn operator_fee(l1_block_info: &L1BlockInfo, gas_used: U256, spec: OpSpecId) -> U256 {
+    if !spec.is_enabled_in(OpSpecId::ISTHMUS) {
+        return U256::ZERO;
+    }
+
+    let scalar = l1_block_info.operator_fee_scalar.unwrap_or_default();
+    let constant = l1_block_info.operator_fee_constant.unwrap_or_default();
+    let product = if spec.is_enabled_in(OpSpecId::JOVIAN) {
+        gas_used
+            .saturating_mul(scalar)
+            .saturating_mul(U256::from(OPERATOR_FEE_JOVIAN_MULTIPLIER))
+    } else {
+        gas_used.saturating_mul(scalar) / U256::from(OPERATOR_FEE_SCALAR_DECIMAL)
+    };
+
+    product.saturating_add(constant)
+}
+
+/// Calldata gas of the transaction with its signature, 16 gas per non-zero byte and 4 gas per
+/// zero byte. The signature is counted as non-zero bytes, as before Regolith.
+fn calldata_gas(l1_block_info: &L1BlockInfo, data: &[u8]) -> U256 {
+    l1_block_info.data_gas(data, OpSpecId::BEDROCK)
+}
+
+/// Estimated compressed size of the transaction with its signature, scaled by 1e6.
+fn fjord_estimated_size(data: &[u8]) -> U256 {
+    U256::from(estimate_compressed_size(
+        flz_compress_len(data) as u64 + SIGNATURE_SIZE,
+    ))
+}
+
+/// Decodes the ABI encoded `bytes` argument of a call.
+fn decode_bytes(args: &[u8]) -> Option<&[u8]> {
+    let offset = usize::try_from(U256::from_be_bytes(*args.first_chunk::<32>()?)).ok()?;
+    let data = args.get(offset..)?;
+    let len = usize::try_from(U256::from_be_bytes(*data.first_chunk::<32>()?)).ok()?;
+    data.get(32..)?.get(..len)
+}
+
+/// Reverts the call with empty output.
+fn revert(gas_limit: u64) -> PrecompileResult {
+    if BASE_GAS > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    Ok(PrecompileOutput::new_reverted(BASE_GAS, Bytes::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::{
+            GAS_PRICE_ORACLE, L1_BLOCK_CONTRACT, OPERATOR_FEE_CONSTANT_OFFSET,
+            OPERATOR_FEE_SCALARS_SLOT, OPERATOR_FEE_SCALAR_OFFSET,
+        },
+        precompiles::{GasPriceOraclePrecompiles, OpPrecompiles},
+        DefaultOp, OpBuilder, OpTransaction,
+    };
+    use alloy_sol_types::{sol, SolCall};
+    use revm::{
+        context::{Context, TxEnv},
+        context_interface::result::ExecutionResult,
+        database::InMemoryDB,
+        primitives::{hex, TxKind, B256},
+        ExecuteEvm,
+    };
+    use std::vec::Vec;
+
+    sol! {
+        interface IGasPriceOracle {
+            function getL1Fee(bytes memory _data) external view returns (uint256);
+            function getL1GasUsed(bytes memory _data) external view returns (uint256);
+            function getOperatorFee(uint256 _gasUsed) external view returns (uint256);
+        }
+    }
+
+    fn call(l1_block_info: &L1BlockInfo, spec: OpSpecId, input: &[u8]) -> U256 {
+        let output = run(l1_block_info, spec, input, u64::MAX).unwrap();
+        assert!(!output.reverted);
+        U256::from_be_slice(&output.bytes)
+    }
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(IGasPriceOracle::getL1FeeCall::SELECTOR, GET_L1_FEE_SELECTOR);
+        assert_eq!(
+            IGasPriceOracle::getL1GasUsedCall::SELECTOR,
+            GET_L1_GAS_USED_SELECTOR
+        );
+        assert_eq!(
+            IGasPriceOracle::getOperatorFeeCall::SELECTOR,
+            GET_OPERATOR_FEE_SELECTOR
+        );
+    }
+
+    #[test]
+    fn test_bedrock() {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1_000),
+            l1_fee_overhead: Some(U256::from(188)),
+            l1_base_fee_scalar: U256::from(684_000),
+            ..Default::default()
+        };
+        // 0xFACADE = 3 non-zero bytes, plus the 68 bytes of the signature.
+        let data = hex!("FACADE00");
+        let calldata_gas = 3 * 16 + 4 + 68 * 16;
+
+        assert_eq!(
+            call(
+                &l1_block_info,
+                OpSpecId::REGOLITH,
+                &IGasPriceOracle::getL1GasUsedCall::new((Bytes::copy_from_slice(&data),))
+                    .abi_encode(),
+            ),
+            U256::from(calldata_gas + 188)
+        );
+        assert_eq!(
+            call(
+                &l1_block_info,
+                OpSpecId::REGOLITH,
+                &IGasPriceOracle::getL1FeeCall::new((Bytes::copy_from_slice(&data),)).abi_encode(),
+            ),
+            U256::from((calldata_gas + 188) * 1_000 * 684_000 / 1_000_000)
+        );
+    }
+
+    #[test]
+    fn test_ecotone() {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1_000),
+            l1_base_fee_scalar: U256::from(1_000),
+            l1_blob_base_fee: Some(U256::from(1_000)),
+            l1_blob_base_fee_scalar: Some(U256::from(1_000)),
+            ..Default::default()
+        };
+        let data = hex!("FACADE00");
+        let calldata_gas = 3 * 16 + 4 + 68 * 16;
+
+        assert_eq!(
+            l1_gas_used(&l1_block_info, &data, OpSpecId::ECOTONE),
+            U256::from(calldata_gas)
+        );
+        assert_eq!(
+            l1_fee(&l1_block_info, &data, OpSpecId::ECOTONE),
+            U256::from(calldata_gas * (16 * 1_000 * 1_000 + 1_000 * 1_000) / 16_000_000)
+        );
+    }
+
+    #[test]
+    fn test_fjord() {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1_000_000_000),
+            l1_base_fee_scalar: U256::from(7_600),
+            l1_blob_base_fee: Some(U256::from(1_000_000_000)),
+            l1_blob_base_fee_scalar: Some(U256::from(862_000)),
+            ..Default::default()
+        };
+
+        // Short transactions are priced at the minimum size of 100 bytes.
+        let data = hex!("FACADE");
+        assert_eq!(
+            l1_gas_used(&l1_block_info, &data, OpSpecId::FJORD),
+            U256::from(1_600)
+        );
+        assert_eq!(
+            l1_fee(&l1_block_info, &data, OpSpecId::FJORD),
+            U256::from(100u64 * (7_600 * 16 + 862_000) * 1_000_000_000 / 1_000_000)
+        );
+
+        // Long transactions are priced with the linear regression on the FastLZ size.
+        let data: Vec<u8> = (0..1_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        let size = (flz_compress_len(&data) as u64 + 68) * 836_500 - 42_585_600;
+        assert!(size > 100_000_000);
+        assert_eq!(
+            l1_gas_used(&l1_block_info, &data, OpSpecId::ISTHMUS),
+            U256::from(size * 16 / 1_000_000)
+        );
+        assert_eq!(
+            l1_fee(&l1_block_info, &data, OpSpecId::ISTHMUS),
+            U256::from(size) * U256::from((7_600u64 * 16 + 862_000) * 1_000_000_000)
+                / U256::from(1_000_000_000_000u64)
+        );
+    }
+
+    #[test]
+    fn test_operator_fee() {
+        let l1_block_info = L1BlockInfo {
+            operator_fee_scalar: Some(U256::from(2_000_000)),
+            operator_fee_constant: Some(U256::from(50)),
+            ..Default::default()
+        };
+        let input = IGasPriceOracle::getOperatorFeeCall::new((U256::from(21_000),)).abi_encode();
+
+        assert_eq!(call(&l1_block_info, OpSpecId::HOLOCENE, &input), U256::ZERO);
+        assert_eq!(
+            call(&l1_block_info, OpSpecId::ISTHMUS, &input),
+            U256::from(21_000 * 2 + 50)
+        );
+        assert_eq!(
+            call(&l1_block_info, OpSpecId::JOVIAN, &input),
+            U256::from(21_000 * 2_000_000 * 100 + 50)
+        );
+        // Missing operator fee parameters read as zero, like empty storage.
+        assert_eq!(
+            call(&L1BlockInfo::default(), OpSpecId::JOVIAN, &input),
+            U256::ZERO
+        );
+    }
+
+    #[test]
+    fn test_invalid_calls() {
+        let l1_block_info = L1BlockInfo::default();
+        for input in [
+            &[][..],
+            &hex!("12345678")[..],
+            &GET_OPERATOR_FEE_SELECTOR[..],
+            &IGasPriceOracle::getL1FeeCall::new((Bytes::from_static(&[1; 40]),)).abi_encode()[..40],
+        ] {
+            let output = run(&l1_block_info, OpSpecId::JOVIAN, input, u64::MAX).unwrap();
+            assert!(output.reverted);
+            assert!(output.bytes.is_empty());
+        }
+
+        let input = IGasPriceOracle::getOperatorFeeCall::new((U256::ZERO,)).abi_encode();
+        assert_eq!(
+            run(&l1_block_info, OpSpecId::JOVIAN, &input, BASE_GAS - 1),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn test_emulated_predeploy() {
+        let input = IGasPriceOracle::getOperatorFeeCall::new((U256::from(1_000),)).abi_encode();
+        let ctx = Context::op()
+            .modify_cfg_chained(|cfg| cfg.spec = OpSpecId::JOVIAN)
+            .with_chain(L1BlockInfo {
+                l2_block: Some(U256::ZERO),
+                operator_fee_scalar: Some(U256::from(3)),
+                operator_fee_constant: Some(U256::from(7)),
+                ..Default::default()
+            });
+        let tx = OpTransaction::builder()
+            .base(
+                TxEnv::builder()
+                    .kind(TxKind::Call(GAS_PRICE_ORACLE))
+                    .data(input.into()),
+            )
+            .enveloped_tx(Some(Bytes::default()))
+            .build_fill();
+
+        // Without the emulation the predeploy has no code in the empty state.
+        let result = ctx.clone().build_op().transact_one(tx.clone()).unwrap();
+        assert!(result.is_success());
+        assert!(result.output().unwrap().is_empty());
+
+        let result = ctx
+            .build_op()
+            .with_precompiles(GasPriceOraclePrecompiles::new(OpPrecompiles::default()))
+            .transact_one(tx)
+            .unwrap();
+        assert!(matches!(result, ExecutionResult::Success { .. }));
+        assert_eq!(
+            U256::from_be_slice(result.output().unwrap()),
+            U256::from(1_000 * 3 * 100 + 7)
+        );
+    }
+
+    #[test]
+    fn test_emulated_predeploy_deposit() {
+        // Deposits do not load the L1 block info before execution, the emulation loads it from
+        // the `L1Block` storage.
+        let mut scalars = [0u8; 32];
+        scalars[OPERATOR_FEE_SCALAR_OFFSET..OPERATOR_FEE_SCALAR_OFFSET + 4]
+            .copy_from_slice(&3u32.to_be_bytes());
+        scalars[OPERATOR_FEE_CONSTANT_OFFSET..OPERATOR_FEE_CONSTANT_OFFSET + 8]
+            .copy_from_slice(&7u64.to_be_bytes());
+        let mut db = InMemoryDB::default();
+        db.insert_account_storage(
+            L1_BLOCK_CONTRACT,
+            OPERATOR_FEE_SCALARS_SLOT,
+            U256::from_be_bytes(scalars),
+        )
+        .unwrap();
+
+        let input = IGasPriceOracle::getOperatorFeeCall::new((U256::from(1_000),)).abi_encode();
+        let mut evm = Context::op()
+            .with_db(db)
+            .modify_cfg_chained(|cfg| cfg.spec = OpSpecId::JOVIAN)
+            .build_op()
+            .with_precompiles(GasPriceOraclePrecompiles::new(OpPrecompiles::default()));
+        let result = evm
+            .transact_one(
+                OpTransaction::builder()
+                    .base(
+                        TxEnv::builder()
+                            .kind(TxKind::Call(GAS_PRICE_ORACLE))
+                            .data(input.into()),
+                    )
+                    .source_hash(B256::from([1u8; 32]))
+                    .build_fill(),
+            )
+            .unwrap();
+
+        assert!(matches!(result, ExecutionResult::Success { .. }));
+        assert_eq!(
+            U256::from_be_slice(result.output().unwrap()),
+            U256::from(1_000 * 3 * 100 + 7)
+        );
+    }
+}
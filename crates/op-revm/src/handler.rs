@@ -1,75 +1,101 @@
 //!Handler related to Optimism chain
 use crate::{
     api::exec::OpContextTr,
-    constants::{BASE_FEE_RECIPIENT, L1_FEE_RECIPIENT, OPERATOR_FEE_RECIPIENT},
+    constants::CROSS_L2_INBOX,
+    interop::{DeclaredMessage, InteropError, MessageValidator},
     transaction::{deposit::DEPOSIT_TRANSACTION_TYPE, OpTransactionError, OpTxTr},
-    L1BlockInfo, OpHaltReason, OpSpecId,
+    OpHaltReason, OpSpecId,
 };
 use revm::{
-    context::{
-        journaled_state::{account::JournaledAccountTr, JournalCheckpoint},
-        result::InvalidTransaction,
-        LocalContextTr,
-    },
+    context::LocalContextTr,
     context_interface::{
         context::ContextError,
-        result::{EVMError, ExecutionResult, FromStringError},
+        result::{ExecutionResult, FromStringError},
+        transaction::AccessListItemTr,
         Block, Cfg, ContextTr, JournalTr, Transaction,
     },
     handler::{
-        evm::FrameTr,
-        handler::EvmTrError,
-        post_execution::{self, reimburse_caller},
-        pre_execution::{calculate_caller_fee, validate_account_nonce_and_code_with_components},
-        EthFrame, EvmTr, FrameResult, Handler, MainnetHandler,
+        evm::FrameTr, handler::EvmTrError, post_execution, EthFrame, EvmTr, FrameResult, Handler,
     },
     inspector::{Inspector, InspectorEvmTr, InspectorHandler},
     interpreter::{interpreter::EthInterpreter, interpreter_action::FrameInit, Gas},
-    primitives::{hardfork::SpecId, U256},
+    primitives::hardfork::SpecId,
 };
-use std::boxed::Box;
+use rollup::{deposit::persist_failed_deposit, RollupHandler, RollupTransactionError};
+use std::sync::Arc;
+
+pub use rollup::IsTxError;
 
 /// Optimism handler extends the [`Handler`] with Optimism specific logic.
+///
+/// Fees, deposits and the fee vaults are handled by the [`RollupHandler`]. The Optimism handler
+/// only overrides the hooks with rules of the OP Stack hardforks: the gas reported by deposits
+/// before Regolith and the interop executing messages.
 #[derive(Debug, Clone)]
 pub struct OpHandler<EVM, ERROR, FRAME> {
-    /// Mainnet handler allows us to use functions from the mainnet handler inside optimism handler.
-    /// So we dont duplicate the logic
-    pub mainnet: MainnetHandler<EVM, ERROR, FRAME>,
+    /// Rollup handler that runs the hooks shared by all rollups.
+    pub rollup: RollupHandler<EVM, ERROR, FRAME>,
+    /// Validator of the interop executing messages declared in the access list.
+    ///
+    /// If `None`, declared messages are only checked to be well formed and not expired.
+    message_validator: Option<Arc<dyn MessageValidator>>,
 }
 
 impl<EVM, ERROR, FRAME> OpHandler<EVM, ERROR, FRAME> {
     /// Create a new Optimism handler.
     pub fn new() -> Self {
         Self {
-            mainnet: MainnetHandler::default(),
+            rollup: RollupHandler::default(),
+            message_validator: None,
         }
     }
-}
 
-impl<EVM, ERROR, FRAME> Default for OpHandler<EVM, ERROR, FRAME> {
-    fn default() -> Self {
-        Self::new()
+    /// Sets the validator of the interop executing messages.
+    pub fn with_message_validator(mut self, message_validator: Arc<dyn MessageValidator>) -> Self {
+        self.message_validator = Some(message_validator);
+        self
     }
-}
 
-/// Trait to check if the error is a transaction error.
-///
-/// Used in cache_error handler to catch deposit transaction that was halted.
-pub trait IsTxError {
-    /// Check if the error is a transaction error.
-    fn is_tx_error(&self) -> bool;
+    /// Returns the validator of the interop executing messages.
+    pub fn message_validator(&self) -> Option<&Arc<dyn MessageValidator>> {
+        self.message_validator.as_ref()
+    }
+
+    /// Validates the interop executing messages declared in the access list of the transaction.
+    pub fn validate_executing_messages<CTX: ContextTr>(
+        &self,
+        ctx: &CTX,
+    ) -> Result<(), InteropError> {
+        let Some(access_list) = ctx.tx().access_list() else {
+            return Ok(());
+        };
+        let block_timestamp = ctx.block().timestamp().saturating_to::<u64>();
+        for item in access_list.filter(|item| *item.address() == CROSS_L2_INBOX) {
+            for message in DeclaredMessage::parse_access_list(item.storage_slots())? {
+                message.validate_timestamp(block_timestamp)?;
+                if let Some(message_validator) = &self.message_validator {
+                    message_validator.validate_message(&message)?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
-impl<DB, TX> IsTxError for EVMError<DB, TX> {
-    fn is_tx_error(&self) -> bool {
-        matches!(self, EVMError::Transaction(_))
+impl<EVM, ERROR, FRAME> Default for OpHandler<EVM, ERROR, FRAME> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl<EVM, ERROR, FRAME> Handler for OpHandler<EVM, ERROR, FRAME>
 where
     EVM: EvmTr<Context: OpContextTr, Frame = FRAME>,
-    ERROR: EvmTrError<EVM> + From<OpTransactionError> + FromStringError + IsTxError,
+    ERROR: EvmTrError<EVM>
+        + From<OpTransactionError>
+        + From<RollupTransactionError>
+        + FromStringError
+        + IsTxError,
     // TODO `FrameResult` should be a generic trait.
     // TODO `FrameInit` should be a generic.
     FRAME: FrameTr<FrameResult = FrameResult, FrameInit = FrameInit>,
@@ -79,107 +105,29 @@ where
     type HaltReason = OpHaltReason;
 
     fn validate_env(&self, evm: &mut Self::Evm) -> Result<(), Self::Error> {
-        // Do not perform any extra validation for deposit transactions, they are pre-verified on L1.
-        let ctx = evm.ctx();
+        let ctx = evm.ctx_ref();
         let tx = ctx.tx();
-        let tx_type = tx.tx_type();
-        if tx_type == DEPOSIT_TRANSACTION_TYPE {
-            // Do not allow for a system transaction to be processed if Regolith is enabled.
-            if tx.is_system_transaction()
-                && evm.ctx().cfg().spec().is_enabled_in(OpSpecId::REGOLITH)
-            {
+        if tx.is_deposit() {
+            // System deposits are only allowed before Regolith.
+            if tx.is_system_transaction() && ctx.cfg().spec().is_enabled_in(OpSpecId::REGOLITH) {
                 return Err(OpTransactionError::DepositSystemTxPostRegolith.into());
             }
             return Ok(());
         }
 
-        // Check that non-deposit transactions have enveloped_tx set
-        if tx.enveloped_tx().is_none() {
-            return Err(OpTransactionError::MissingEnvelopedTx.into());
+        if ctx.cfg().spec().is_enabled_in(OpSpecId::INTEROP) {
+            self.validate_executing_messages(ctx)
+                .map_err(OpTransactionError::from)?;
         }
 
-        self.mainnet.validate_env(evm)
+        self.rollup.validate_env(evm)
     }
 
     fn validate_against_state_and_deduct_caller(
         &self,
         evm: &mut Self::Evm,
     ) -> Result<(), Self::Error> {
-        let (block, tx, cfg, journal, chain, _) = evm.ctx().all_mut();
-        let spec = cfg.spec();
-
-        if tx.tx_type() == DEPOSIT_TRANSACTION_TYPE {
-            let basefee = block.basefee() as u128;
-            let blob_price = block.blob_gasprice().unwrap_or_default();
-            // deposit skips max fee check and just deducts the effective balance spending.
-
-            let mut caller = journal.load_account_with_code_mut(tx.caller())?.data;
-
-            let effective_balance_spending = tx
-                .effective_balance_spending(basefee, blob_price)
-                .expect("Deposit transaction effective balance spending overflow")
-                - tx.value();
-
-            // Mind value should be added first before subtracting the effective balance spending.
-            let mut new_balance = caller
-                .balance()
-                .saturating_add(U256::from(tx.mint().unwrap_or_default()))
-                .saturating_sub(effective_balance_spending);
-
-            if cfg.is_balance_check_disabled() {
-                // Make sure the caller's balance is at least the value of the transaction.
-                // this is not consensus critical, and it is used in testing.
-                new_balance = new_balance.max(tx.value());
-            }
-
-            // set the new balance and bump the nonce if it is a call
-            caller.set_balance(new_balance);
-            if tx.kind().is_call() {
-                caller.bump_nonce();
-            }
-
-            return Ok(());
-        }
-
-        // L1 block info is stored in the context for later use.
-        // and it will be reloaded from the database if it is not for the current block.
-        if chain.l2_block != Some(block.number()) {
-            *chain = L1BlockInfo::try_fetch(journal.db_mut(), block.number(), spec)?;
-        }
-
-        let mut caller_account = journal.load_account_with_code_mut(tx.caller())?.data;
-
-        // validates account nonce and code
-        validate_account_nonce_and_code_with_components(&caller_account.account().info, tx, cfg)?;
-
-        // check additional cost and deduct it from the caller's balances
-        let mut balance = caller_account.account().info.balance;
-
-        if !cfg.is_fee_charge_disabled() {
-            let Some(additional_cost) = chain.tx_cost_with_tx(tx, spec) else {
-                return Err(ERROR::from_string(
-                    "[OPTIMISM] Failed to load enveloped transaction.".into(),
-                ));
-            };
-            let Some(new_balance) = balance.checked_sub(additional_cost) else {
-                return Err(InvalidTransaction::LackOfFundForMaxFee {
-                    fee: Box::new(additional_cost),
-                    balance: Box::new(balance),
-                }
-                .into());
-            };
-            balance = new_balance
-        }
-
-        let balance = calculate_caller_fee(balance, tx, block, cfg)?;
-
-        // make changes to the account
-        caller_account.set_balance(balance);
-        if tx.kind().is_call() {
-            caller_account.bump_nonce();
-        }
-
-        Ok(())
+        self.rollup.validate_against_state_and_deduct_caller(evm)
     }
 
     fn last_frame_result(
@@ -197,9 +145,11 @@ where
         let gas = frame_result.gas_mut();
         let remaining = gas.remaining();
         let refunded = gas.refunded();
+        let overrun = gas.overrun();
 
         // Spend the gas limit. Gas is reimbursed when the tx returns successfully.
         *gas = Gas::new_spent(tx_gas_limit);
+        gas.record_overrun(overrun);
 
         if instruction_result.is_ok() {
             // On Optimism, deposit transactions report gas usage uniquely to other
@@ -250,19 +200,7 @@ where
         evm: &mut Self::Evm,
         frame_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
     ) -> Result<(), Self::Error> {
-        let mut additional_refund = U256::ZERO;
-
-        if evm.ctx().tx().tx_type() != DEPOSIT_TRANSACTION_TYPE
-            && !evm.ctx().cfg().is_fee_charge_disabled()
-        {
-            let spec = evm.ctx().cfg().spec();
-            additional_refund = evm
-                .ctx()
-                .chain()
-                .operator_fee_refund(frame_result.gas(), spec);
-        }
-
-        reimburse_caller(evm.ctx(), frame_result.gas(), additional_refund).map_err(From::from)
+        self.rollup.reimburse_caller(evm, frame_result)
     }
 
     fn refund(
@@ -294,51 +232,7 @@ where
         evm: &mut Self::Evm,
         frame_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
     ) -> Result<(), Self::Error> {
-        let is_deposit = evm.ctx().tx().tx_type() == DEPOSIT_TRANSACTION_TYPE;
-
-        // Transfer fee to coinbase/beneficiary.
-        if is_deposit {
-            return Ok(());
-        }
-
-        self.mainnet.reward_beneficiary(evm, frame_result)?;
-        let basefee = evm.ctx().block().basefee() as u128;
-
-        // If the transaction is not a deposit transaction, fees are paid out
-        // to both the Base Fee Vault as well as the L1 Fee Vault.
-        let ctx = evm.ctx();
-        let enveloped = ctx.tx().enveloped_tx().cloned();
-        let spec = ctx.cfg().spec();
-        let l1_block_info = ctx.chain_mut();
-
-        let Some(enveloped_tx) = &enveloped else {
-            return Err(ERROR::from_string(
-                "[OPTIMISM] Failed to load enveloped transaction.".into(),
-            ));
-        };
-
-        let l1_cost = l1_block_info.calculate_tx_l1_cost(enveloped_tx, spec);
-        let operator_fee_cost = if spec.is_enabled_in(OpSpecId::ISTHMUS) {
-            l1_block_info.operator_fee_charge(
-                enveloped_tx,
-                U256::from(frame_result.gas().used()),
-                spec,
-            )
-        } else {
-            U256::ZERO
-        };
-        let base_fee_amount = U256::from(basefee.saturating_mul(frame_result.gas().used() as u128));
-
-        // Send fees to their respective recipients
-        for (recipient, amount) in [
-            (L1_FEE_RECIPIENT, l1_cost),
-            (BASE_FEE_RECIPIENT, base_fee_amount),
-            (OPERATOR_FEE_RECIPIENT, operator_fee_cost),
-        ] {
-            ctx.journal_mut().balance_incr(recipient, amount)?;
-        }
-
-        Ok(())
+        self.rollup.reward_beneficiary(evm, frame_result)
     }
 
     fn execution_result(
@@ -390,11 +284,6 @@ where
             let mint = tx.mint();
             let is_system_tx = tx.is_system_transaction();
             let gas_limit = tx.gas_limit();
-            let journal = evm.ctx().journal_mut();
-
-            // discard all changes of this transaction
-            // Default JournalCheckpoint is the first checkpoint and will wipe all changes.
-            journal.checkpoint_revert(JournalCheckpoint::default());
 
             // If the transaction is a deposit transaction and it failed
             // for any reason, the caller nonce must be bumped, and the
@@ -403,16 +292,10 @@ where
             // easily distinguish between a failed deposit and a failed
             // normal transaction.
 
-            // Increment sender nonce and account balance for the mint amount. Deposits
-            // always persist the mint amount, even if the transaction fails.
-            let mut acc = journal.load_account_mut(caller)?;
-            acc.bump_nonce();
-            acc.incr_balance(U256::from(mint.unwrap_or_default()));
-
-            drop(acc); // Drop acc to avoid borrow checker issues.
-
-            // We can now commit the changes.
-            journal.commit_tx();
+            // Discard all changes of this transaction, then increment sender nonce and
+            // account balance for the mint amount. Deposits always persist the mint amount,
+            // even if the transaction fails.
+            persist_failed_deposit(evm.ctx().journal_mut(), caller, mint)?;
 
             // The gas used of a failed deposit post-regolith is the gas
             // limit of the transaction. pre-regolith, it is the gas limit
@@ -446,7 +329,11 @@ where
         Frame = EthFrame<EthInterpreter>,
         Inspector: Inspector<<<Self as Handler>::Evm as EvmTr>::Context, EthInterpreter>,
     >,
-    ERROR: EvmTrError<EVM> + From<OpTransactionError> + FromStringError + IsTxError,
+    ERROR: EvmTrError<EVM>
+        + From<OpTransactionError>
+        + From<RollupTransactionError>
+        + FromStringError
+        + IsTxError,
 {
     type IT = EthInterpreter;
 }
@@ -460,17 +347,17 @@ mod tests {
             BASE_FEE_SCALAR_OFFSET, ECOTONE_L1_BLOB_BASE_FEE_SLOT, ECOTONE_L1_FEE_SCALARS_SLOT,
             L1_BASE_FEE_SLOT, L1_BLOCK_CONTRACT, OPERATOR_FEE_SCALARS_SLOT,
         },
-        DefaultOp, OpBuilder, OpTransaction,
+        DefaultOp, L1BlockInfo, OpBuilder, OpTransaction,
     };
     use alloy_primitives::uint;
     use revm::{
         context::{BlockEnv, Context, TxEnv},
-        context_interface::result::InvalidTransaction,
+        context_interface::result::{EVMError, InvalidTransaction},
         database::InMemoryDB,
         database_interface::EmptyDB,
         handler::EthFrame,
         interpreter::{CallOutcome, InstructionResult, InterpreterResult},
-        primitives::{bytes, Address, Bytes, B256},
+        primitives::{bytes, Address, Bytes, B256, U256},
         state::AccountInfo,
     };
     use rstest::rstest;
@@ -1463,4 +1350,79 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_validate_interop_executing_messages() {
+        use crate::interop::{message_hash, InitiatingMessages};
+        use revm::{
+            context_interface::transaction::{AccessList, AccessListItem, TransactionType},
+            primitives::Log,
+        };
+
+        let log = Log::new_unchecked(
+            Address::with_last_byte(1),
+            vec![B256::with_last_byte(1)],
+            bytes!("C0FFEE"),
+        );
+        let mut messages = InitiatingMessages::new();
+        let id = messages.insert_log(U256::from(10), 5, 1_000, 0, &log);
+        let storage_keys = id.access_list_keys(message_hash(&log.data));
+
+        let ctx = Context::op()
+            .modify_tx_chained(|tx| {
+                tx.enveloped_tx = Some(bytes!("FACADE"));
+                tx.base.tx_type = TransactionType::Eip2930 as u8;
+                tx.base.access_list = AccessList(vec![AccessListItem {
+                    address: CROSS_L2_INBOX,
+                    storage_keys,
+                }]);
+            })
+            .modify_block_chained(|block| block.timestamp = U256::from(1_000))
+            .modify_cfg_chained(|cfg| cfg.spec = OpSpecId::INTEROP);
+        let mut evm = ctx.build_op();
+
+        // Without a validator declared messages are only checked to be well formed.
+        let handler =
+            OpHandler::<_, EVMError<_, OpTransactionError>, EthFrame<EthInterpreter>>::new();
+        assert!(handler.validate_env(&mut evm).is_ok());
+
+        let handler = handler.with_message_validator(Arc::new(messages));
+        assert!(handler.validate_env(&mut evm).is_ok());
+
+        evm.ctx()
+            .modify_block(|block| block.timestamp = U256::from(999));
+        assert_eq!(
+            handler.validate_env(&mut evm),
+            Err(EVMError::Transaction(OpTransactionError::Interop(
+                InteropError::MessageFromFuture
+            )))
+        );
+        evm.ctx()
+            .modify_block(|block| block.timestamp = U256::from(1_000));
+
+        // Checksum of another message.
+        evm.ctx()
+            .modify_tx(|tx| tx.base.access_list.0[0].storage_keys[1].0[31] ^= 1);
+        assert_eq!(
+            handler.validate_env(&mut evm),
+            Err(EVMError::Transaction(OpTransactionError::Interop(
+                InteropError::InvalidChecksum
+            )))
+        );
+
+        // Missing checksum.
+        evm.ctx().modify_tx(|tx| {
+            tx.base.access_list.0[0].storage_keys.pop();
+        });
+        assert_eq!(
+            handler.validate_env(&mut evm),
+            Err(EVMError::Transaction(OpTransactionError::Interop(
+                InteropError::InvalidAccessList
+            )))
+        );
+
+        // Access list is not checked before interop.
+        evm.ctx().modify_cfg(|cfg| cfg.spec = OpSpecId::JOVIAN);
+        assert!(handler.validate_env(&mut evm).is_ok());
+    }
 }
@@ -0,0 +1,413 @@
+//! OP Stack interop executing-message validation.
+//!
+//! Transactions execute messages initiated on other chains by calling `validateMessage` of the
+//! [`CROSS_L2_INBOX`] predeploy. Every executed message has to be declared in the access list of
+//! the transaction under the inbox address. The predeploy checks that the checksum of the message
+//! is declared and emits an `ExecutingMessage` log, while declared messages are checked against
+//! the initiating messages of their source chains by a [`MessageValidator`] before the execution.
+//!
+//! Messages are declared with up to three storage keys:
+//! - lookup identity: `0x01 ++ [3]byte{} ++ chainId[low 8 bytes] ++ blockNumber ++ timestamp ++ logIndex`,
+//! - optional chain id extension: `0x02 ++ [7]byte{} ++ chainId[high 24 bytes]`,
+//! - checksum: `0x03 ++ checksum[1..]`, see [`MessageIdentifier::checksum`].
+use crate::constants::CROSS_L2_INBOX;
+use auto_impl::auto_impl;
+use core::fmt;
+use revm::primitives::{b256, keccak256, Address, HashMap, Log, LogData, B256, U256};
+use std::vec::Vec;
+
+/// Topic of the `ExecutingMessage(bytes32,(address,uint256,uint256,uint256,uint256))` event
+/// emitted by the [`CROSS_L2_INBOX`].
+pub const EXECUTING_MESSAGE_TOPIC: B256 =
+    b256!("0x5c37832d2e8d10e346e55ad62071a6a2f9fa5130614ef2ec6617555c6f467ba7");
+
+/// Time in seconds after which an initiating message can't be executed anymore.
+pub const MESSAGE_EXPIRY_WINDOW: u64 = 604_800;
+
+/// Type of the access list key with the lookup identity of a message.
+const LOOKUP_ENTRY: u8 = 0x01;
+/// Type of the access list key with the high bytes of the chain id.
+const CHAIN_ID_EXTENSION_ENTRY: u8 = 0x02;
+/// Type of the access list key with the checksum of a message.
+const CHECKSUM_ENTRY: u8 = 0x03;
+
+/// Identifier of an initiating message, the log on the source chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageIdentifier {
+    /// Address that emitted the log.
+    pub origin: Address,
+    /// Block number of the log.
+    pub block_number: u64,
+    /// Index of the log in the block.
+    pub log_index: u32,
+    /// Timestamp of the block.
+    pub timestamp: u64,
+    /// Chain id of the source chain.
+    pub chain_id: U256,
+}
+
+impl MessageIdentifier {
+    /// Returns the checksum of the message that is declared in the access list.
+    pub fn checksum(&self, msg_hash: B256) -> B256 {
+        let mut buf = [0u8; 64];
+        buf[..20].copy_from_slice(self.origin.as_slice());
+        buf[20..52].copy_from_slice(msg_hash.as_slice());
+        let log_hash = keccak256(&buf[..52]);
+
+        let mut id_packed = [0u8; 32];
+        id_packed[12..20].copy_from_slice(&self.block_number.to_be_bytes());
+        id_packed[20..28].copy_from_slice(&self.timestamp.to_be_bytes());
+        id_packed[28..].copy_from_slice(&self.log_index.to_be_bytes());
+        buf[..32].copy_from_slice(log_hash.as_slice());
+        buf[32..].copy_from_slice(&id_packed);
+        let id_log_hash = keccak256(buf);
+
+        buf[..32].copy_from_slice(id_log_hash.as_slice());
+        buf[32..].copy_from_slice(&self.chain_id.to_be_bytes::<32>());
+        let mut checksum = keccak256(buf);
+        checksum[0] = CHECKSUM_ENTRY;
+        checksum
+    }
+
+    /// Returns the access list keys that declare the message.
+    pub fn access_list_keys(&self, msg_hash: B256) -> Vec<B256> {
+        let chain_id = self.chain_id.to_be_bytes::<32>();
+        let mut lookup = B256::ZERO;
+        lookup[0] = LOOKUP_ENTRY;
+        lookup[4..12].copy_from_slice(&chain_id[24..]);
+        lookup[12..20].copy_from_slice(&self.block_number.to_be_bytes());
+        lookup[20..28].copy_from_slice(&self.timestamp.to_be_bytes());
+        lookup[28..].copy_from_slice(&self.log_index.to_be_bytes());
+
+        let mut keys = Vec::with_capacity(3);
+        keys.push(lookup);
+        if self.chain_id > U256::from(u64::MAX) {
+            let mut extension = B256::ZERO;
+            extension[0] = CHAIN_ID_EXTENSION_ENTRY;
+            extension[8..].copy_from_slice(&chain_id[..24]);
+            keys.push(extension);
+        }
+        keys.push(self.checksum(msg_hash));
+        keys
+    }
+}
+
+/// Hash of the log that is referenced by executing messages, `keccak256(topics ++ data)`.
+pub fn message_hash(log: &LogData) -> B256 {
+    let mut payload = Vec::with_capacity(log.topics().len() * 32 + log.data.len());
+    for topic in log.topics() {
+        payload.extend_from_slice(topic.as_slice());
+    }
+    payload.extend_from_slice(&log.data);
+    keccak256(payload)
+}
+
+/// Message executed with the [`CROSS_L2_INBOX`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecutingMessage {
+    /// Identifier of the initiating message.
+    pub id: MessageIdentifier,
+    /// Hash of the initiating message, see [`message_hash`].
+    pub msg_hash: B256,
+}
+
+impl ExecutingMessage {
+    /// Decodes the `ExecutingMessage` log emitted by the [`CROSS_L2_INBOX`].
+    ///
+    /// Returns `None` for other logs.
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let [topic, msg_hash] = log.topics() else {
+            return None;
+        };
+        if log.address != CROSS_L2_INBOX || *topic != EXECUTING_MESSAGE_TOPIC {
+            return None;
+        }
+        // ABI encoded `Identifier` struct.
+        let data = log.data.data.as_ref();
+        if data.len() != 5 * 32 || data[..12] != [0; 12] {
+            return None;
+        }
+        let word = |i: usize| U256::from_be_slice(&data[i * 32..(i + 1) * 32]);
+        let id = MessageIdentifier {
+            origin: Address::from_slice(&data[12..32]),
+            block_number: word(1).try_into().ok()?,
+            log_index: word(2).try_into().ok()?,
+            timestamp: word(3).try_into().ok()?,
+            chain_id: word(4),
+        };
+        Some(Self {
+            id,
+            msg_hash: *msg_hash,
+        })
+    }
+
+    /// Returns the checksum of the message, see [`MessageIdentifier::checksum`].
+    pub fn checksum(&self) -> B256 {
+        self.id.checksum(self.msg_hash)
+    }
+}
+
+/// Returns the messages executed with the [`CROSS_L2_INBOX`] in the logs of a transaction.
+pub fn executing_messages(logs: &[Log]) -> impl Iterator<Item = ExecutingMessage> + '_ {
+    logs.iter().filter_map(ExecutingMessage::from_log)
+}
+
+/// Message declared in the access list of a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeclaredMessage {
+    /// Chain id of the source chain.
+    pub chain_id: U256,
+    /// Block number of the initiating message.
+    pub block_number: u64,
+    /// Timestamp of the initiating message.
+    pub timestamp: u64,
+    /// Index of the initiating message in the block.
+    pub log_index: u32,
+    /// Checksum of the message, see [`MessageIdentifier::checksum`].
+    pub checksum: B256,
+}
+
+impl DeclaredMessage {
+    /// Parses the messages declared with the access list keys of the [`CROSS_L2_INBOX`].
+    pub fn parse_access_list<'a>(
+        keys: impl IntoIterator<Item = &'a B256>,
+    ) -> Result<Vec<Self>, InteropError> {
+        let mut keys = keys.into_iter();
+        let mut messages = Vec::new();
+        while let Some(lookup) = keys.next() {
+            if lookup[0] != LOOKUP_ENTRY || lookup[1..4] != [0; 3] {
+                return Err(InteropError::InvalidAccessList);
+            }
+            let mut chain_id = [0u8; 32];
+            chain_id[24..].copy_from_slice(&lookup[4..12]);
+
+            let mut key = keys.next().ok_or(InteropError::InvalidAccessList)?;
+            if key[0] == CHAIN_ID_EXTENSION_ENTRY {
+                if key[1..8] != [0; 7] {
+                    return Err(InteropError::InvalidAccessList);
+                }
+                chain_id[..24].copy_from_slice(&key[8..]);
+                key = keys.next().ok_or(InteropError::InvalidAccessList)?;
+            }
+            if key[0] != CHECKSUM_ENTRY {
+                return Err(InteropError::InvalidAccessList);
+            }
+
+            messages.push(Self {
+                chain_id: U256::from_be_bytes(chain_id),
+                block_number: u64::from_be_bytes(lookup[12..20].try_into().unwrap()),
+                timestamp: u64::from_be_bytes(lookup[20..28].try_into().unwrap()),
+                log_index: u32::from_be_bytes(lookup[28..].try_into().unwrap()),
+                checksum: *key,
+            });
+        }
+        Ok(messages)
+    }
+
+    /// Checks that the initiating message can be executed in a block with the given timestamp.
+    pub fn validate_timestamp(&self, block_timestamp: u64) -> Result<(), InteropError> {
+        if self.timestamp > block_timestamp {
+            return Err(InteropError::MessageFromFuture);
+        }
+        if self.timestamp.saturating_add(MESSAGE_EXPIRY_WINDOW) < block_timestamp {
+            return Err(InteropError::MessageExpired);
+        }
+        Ok(())
+    }
+}
+
+/// Validates declared messages against the initiating messages of the source chains.
+///
+/// On a node this is done by the supervisor. [`InitiatingMessages`] is an in-memory
+/// implementation that can be used in tests and devnets.
+#[auto_impl(&, Box, Arc)]
+pub trait MessageValidator: fmt::Debug + Send + Sync {
+    /// Checks that the declared message matches an initiating message.
+    fn validate_message(&self, message: &DeclaredMessage) -> Result<(), InteropError>;
+}
+
+/// In-memory set of initiating messages.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InitiatingMessages {
+    /// Messages keyed by the chain id, block number and log index.
+    messages: HashMap<(U256, u64, u32), ExecutingMessage>,
+}
+
+impl InitiatingMessages {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the initiating message.
+    pub fn insert(&mut self, id: MessageIdentifier, msg_hash: B256) {
+        self.messages.insert(
+            (id.chain_id, id.block_number, id.log_index),
+            ExecutingMessage { id, msg_hash },
+        );
+    }
+
+    /// Inserts the log as initiating message and returns its identifier.
+    pub fn insert_log(
+        &mut self,
+        chain_id: U256,
+        block_number: u64,
+        timestamp: u64,
+        log_index: u32,
+        log: &Log,
+    ) -> MessageIdentifier {
+        let id = MessageIdentifier {
+            origin: log.address,
+            block_number,
+            log_index,
+            timestamp,
+            chain_id,
+        };
+        self.insert(id, message_hash(&log.data));
+        id
+    }
+
+    /// Returns the number of messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if there are no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+impl MessageValidator for InitiatingMessages {
+    fn validate_message(&self, message: &DeclaredMessage) -> Result<(), InteropError> {
+        let initiating = self
+            .messages
+            .get(&(message.chain_id, message.block_number, message.log_index))
+            .ok_or(InteropError::UnknownMessage)?;
+        if initiating.id.timestamp != message.timestamp || initiating.checksum() != message.checksum
+        {
+            return Err(InteropError::InvalidChecksum);
+        }
+        Ok(())
+    }
+}
+
+/// Interop validation error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InteropError {
+    /// Access list keys of the [`CROSS_L2_INBOX`] do not declare messages.
+    InvalidAccessList,
+    /// There is no initiating message with the declared identifier.
+    UnknownMessage,
+    /// Checksum does not match the initiating message.
+    InvalidChecksum,
+    /// Initiating message is newer than the executing block.
+    MessageFromFuture,
+    /// Initiating message is older than the [`MESSAGE_EXPIRY_WINDOW`].
+    MessageExpired,
+}
+
+impl core::error::Error for InteropError {}
+
+impl fmt::Display for InteropError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAccessList => write!(f, "invalid cross L2 inbox access list"),
+            Self::UnknownMessage => write!(f, "unknown initiating message"),
+            Self::InvalidChecksum => write!(f, "invalid executing message checksum"),
+            Self::MessageFromFuture => write!(f, "initiating message is from the future"),
+            Self::MessageExpired => write!(f, "initiating message has expired"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{address, Bytes};
+
+    #[test]
+    fn access_list_roundtrip() {
+        let log = Log::new_unchecked(
+            address!("0x1000000000000000000000000000000000000001"),
+            vec![B256::with_last_byte(1)],
+            Bytes::from_static(b"hello"),
+        );
+        let mut messages = InitiatingMessages::new();
+        let small = messages.insert_log(U256::from(10), 5, 1_000, 2, &log);
+        let large = messages.insert_log(U256::MAX, 6, 1_000, 0, &log);
+        let msg_hash = message_hash(&log.data);
+
+        let keys = [
+            small.access_list_keys(msg_hash),
+            large.access_list_keys(msg_hash),
+        ]
+        .concat();
+        assert_eq!(keys.len(), 5);
+        let declared = DeclaredMessage::parse_access_list(&keys).unwrap();
+        assert_eq!(declared.len(), 2);
+        assert_eq!(declared[0].chain_id, U256::from(10));
+        assert_eq!(declared[1].chain_id, U256::MAX);
+        assert_eq!(declared[1].checksum, large.checksum(msg_hash));
+        for message in &declared {
+            messages.validate_message(message).unwrap();
+            message.validate_timestamp(1_000).unwrap();
+        }
+        assert_eq!(
+            declared[0].validate_timestamp(999),
+            Err(InteropError::MessageFromFuture)
+        );
+        assert_eq!(
+            declared[0].validate_timestamp(1_001 + MESSAGE_EXPIRY_WINDOW),
+            Err(InteropError::MessageExpired)
+        );
+
+        // Different message hash.
+        let other = small.access_list_keys(B256::ZERO);
+        let declared = DeclaredMessage::parse_access_list(&other).unwrap();
+        assert_eq!(
+            messages.validate_message(&declared[0]),
+            Err(InteropError::InvalidChecksum)
+        );
+        // Missing checksum.
+        assert_eq!(
+            DeclaredMessage::parse_access_list(&keys[..1]),
+            Err(InteropError::InvalidAccessList)
+        );
+    }
+
+    #[test]
+    fn decode_executing_message() {
+        let id = MessageIdentifier {
+            origin: address!("0x1000000000000000000000000000000000000001"),
+            block_number: 5,
+            log_index: 2,
+            timestamp: 1_000,
+            chain_id: U256::from(10),
+        };
+        let msg_hash = B256::with_last_byte(0xaa);
+        let mut data = [0u8; 5 * 32];
+        data[12..32].copy_from_slice(id.origin.as_slice());
+        data[63] = 5;
+        data[95] = 2;
+        data[126..128].copy_from_slice(&1_000u16.to_be_bytes());
+        data[159] = 10;
+        let log = Log::new_unchecked(
+            CROSS_L2_INBOX,
+            vec![EXECUTING_MESSAGE_TOPIC, msg_hash],
+            Bytes::copy_from_slice(&data),
+        );
+        assert_eq!(
+            executing_messages(core::slice::from_ref(&log)).collect::<Vec<_>>(),
+            [ExecutingMessage { id, msg_hash }]
+        );
+
+        let mut other = log.clone();
+        other.address = Address::ZERO;
+        assert_eq!(ExecutingMessage::from_log(&other), None);
+    }
+}
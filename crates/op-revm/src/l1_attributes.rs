@@ -0,0 +1,423 @@
+//! Contains the `[L1BlockAttributes]` type, the calldata of the L1 attributes deposit transaction.
+use crate::{L1BlockInfo, OpSpecId};
+use core::fmt;
+use revm::primitives::{Bytes, B256, U256};
+use std::vec::Vec;
+
+/// Selector of `setL1BlockValues(uint64,uint64,uint256,bytes32,uint64,bytes32,uint256,uint256)`.
+pub const L1_INFO_BEDROCK_SELECTOR: [u8; 4] = [0x01, 0x5d, 0x8e, 0xb9];
+/// Selector of `setL1BlockValuesEcotone()`.
+pub const L1_INFO_ECOTONE_SELECTOR: [u8; 4] = [0x44, 0x0a, 0x5e, 0x20];
+/// Selector of `setL1BlockValuesIsthmus()`.
+pub const L1_INFO_ISTHMUS_SELECTOR: [u8; 4] = [0x09, 0x89, 0x99, 0xbe];
+/// Selector of `setL1BlockValuesJovian()`.
+pub const L1_INFO_JOVIAN_SELECTOR: [u8; 4] = [0x3d, 0xb6, 0xbe, 0x2b];
+
+/// Layout of the L1 attributes calldata.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1AttributesFormat {
+    /// ABI encoded `setL1BlockValues` call.
+    #[default]
+    Bedrock,
+    /// Packed `setL1BlockValuesEcotone` call.
+    Ecotone,
+    /// Ecotone layout with the operator fee parameters.
+    Isthmus,
+    /// Isthmus layout with the DA footprint gas scalar.
+    Jovian,
+}
+
+impl L1AttributesFormat {
+    /// Returns the format used by blocks of the spec.
+    ///
+    /// Note that the first block of a hardfork still uses the format of the previous hardfork.
+    pub const fn for_spec(spec: OpSpecId) -> Self {
+        if spec.is_enabled_in(OpSpecId::JOVIAN) {
+            Self::Jovian
+        } else if spec.is_enabled_in(OpSpecId::ISTHMUS) {
+            Self::Isthmus
+        } else if spec.is_enabled_in(OpSpecId::ECOTONE) {
+            Self::Ecotone
+        } else {
+            Self::Bedrock
+        }
+    }
+
+    /// Returns the function selector.
+    pub const fn selector(&self) -> [u8; 4] {
+        match self {
+            Self::Bedrock => L1_INFO_BEDROCK_SELECTOR,
+            Self::Ecotone => L1_INFO_ECOTONE_SELECTOR,
+            Self::Isthmus => L1_INFO_ISTHMUS_SELECTOR,
+            Self::Jovian => L1_INFO_JOVIAN_SELECTOR,
+        }
+    }
+
+    /// Returns the length of the calldata.
+    pub const fn calldata_len(&self) -> usize {
+        match self {
+            Self::Bedrock => 4 + 8 * 32,
+            Self::Ecotone => 164,
+            Self::Isthmus => 176,
+            Self::Jovian => 178,
+        }
+    }
+
+    /// Returns the format with the given selector.
+    pub fn from_selector(selector: [u8; 4]) -> Option<Self> {
+        [Self::Bedrock, Self::Ecotone, Self::Isthmus, Self::Jovian]
+            .into_iter()
+            .find(|format| format.selector() == selector)
+    }
+}
+
+/// Values set by the L1 attributes deposit transaction, the first transaction of every L2 block.
+///
+/// Fields that are not part of the [format](L1AttributesFormat) are zero and are ignored when
+/// encoding.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct L1BlockAttributes {
+    /// Layout of the calldata.
+    pub format: L1AttributesFormat,
+    /// L1 origin block number.
+    pub number: u64,
+    /// L1 origin block timestamp.
+    pub timestamp: u64,
+    /// L1 origin block base fee.
+    pub base_fee: U256,
+    /// L1 origin block hash.
+    pub block_hash: B256,
+    /// Number of L2 blocks since the start of the epoch.
+    pub sequence_number: u64,
+    /// Versioned hash of the batcher address.
+    pub batcher_hash: B256,
+    /// L1 fee overhead, Bedrock only.
+    pub l1_fee_overhead: U256,
+    /// L1 base fee scalar, encoded as `uint32` post-Ecotone.
+    pub base_fee_scalar: U256,
+    /// L1 origin block blob base fee, post-Ecotone.
+    pub blob_base_fee: U256,
+    /// L1 blob base fee scalar, post-Ecotone.
+    pub blob_base_fee_scalar: u32,
+    /// Operator fee scalar, post-Isthmus.
+    pub operator_fee_scalar: u32,
+    /// Operator fee constant, post-Isthmus.
+    pub operator_fee_constant: u64,
+    /// DA footprint gas scalar, post-Jovian.
+    pub da_footprint_gas_scalar: u16,
+}
+
+/// Error of decoding the L1 attributes calldata.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1AttributesError {
+    /// Selector does not match any format.
+    UnknownSelector([u8; 4]),
+    /// Calldata length does not match the format.
+    InvalidLength {
+        /// Expected length.
+        expected: usize,
+        /// Length of the calldata.
+        got: usize,
+    },
+    /// ABI encoded integer does not fit in its type.
+    InvalidValue,
+}
+
+impl core::error::Error for L1AttributesError {}
+
+impl fmt::Display for L1AttributesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSelector(selector) => {
+                write!(f, "unknown L1 attributes selector 0x")?;
+                selector.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+            Self::InvalidLength { expected, got } => {
+                write!(f, "invalid L1 attributes length {got}, expected {expected}")
+            }
+            Self::InvalidValue => write!(f, "L1 attributes value out of range"),
+        }
+    }
+}
+
+/// Reads big endian values from the calldata.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        bytes.try_into().unwrap()
+    }
+
+    fn u16(&mut self) -> u16 {
+        u16::from_be_bytes(self.bytes())
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_be_bytes(self.bytes())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_be_bytes(self.bytes())
+    }
+
+    fn u256(&mut self) -> U256 {
+        U256::from_be_bytes(self.bytes::<32>())
+    }
+
+    fn b256(&mut self) -> B256 {
+        B256::new(self.bytes())
+    }
+
+    /// ABI encoded `uint64`.
+    fn abi_u64(&mut self) -> Result<u64, L1AttributesError> {
+        self.u256()
+            .try_into()
+            .map_err(|_| L1AttributesError::InvalidValue)
+    }
+}
+
+impl L1BlockAttributes {
+    /// Decodes the calldata of the L1 attributes deposit transaction.
+    pub fn decode(calldata: &[u8]) -> Result<Self, L1AttributesError> {
+        let selector: [u8; 4] = calldata
+            .get(..4)
+            .ok_or(L1AttributesError::InvalidLength {
+                expected: 4,
+                got: calldata.len(),
+            })?
+            .try_into()
+            .unwrap();
+        let format = L1AttributesFormat::from_selector(selector)
+            .ok_or(L1AttributesError::UnknownSelector(selector))?;
+        if calldata.len() != format.calldata_len() {
+            return Err(L1AttributesError::InvalidLength {
+                expected: format.calldata_len(),
+                got: calldata.len(),
+            });
+        }
+
+        let mut r = Reader(&calldata[4..]);
+        let mut out = Self {
+            format,
+            ..Default::default()
+        };
+        if format == L1AttributesFormat::Bedrock {
+            out.number = r.abi_u64()?;
+            out.timestamp = r.abi_u64()?;
+            out.base_fee = r.u256();
+            out.block_hash = r.b256();
+            out.sequence_number = r.abi_u64()?;
+            out.batcher_hash = r.b256();
+            out.l1_fee_overhead = r.u256();
+            out.base_fee_scalar = r.u256();
+            return Ok(out);
+        }
+
+        out.base_fee_scalar = U256::from(r.u32());
+        out.blob_base_fee_scalar = r.u32();
+        out.sequence_number = r.u64();
+        out.timestamp = r.u64();
+        out.number = r.u64();
+        out.base_fee = r.u256();
+        out.blob_base_fee = r.u256();
+        out.block_hash = r.b256();
+        out.batcher_hash = r.b256();
+        if format == L1AttributesFormat::Ecotone {
+            return Ok(out);
+        }
+
+        out.operator_fee_scalar = r.u32();
+        out.operator_fee_constant = r.u64();
+        if format == L1AttributesFormat::Jovian {
+            out.da_footprint_gas_scalar = r.u16();
+        }
+        Ok(out)
+    }
+
+    /// Encodes the calldata of the L1 attributes deposit transaction.
+    pub fn encode(&self) -> Bytes {
+        let mut out = Vec::with_capacity(self.format.calldata_len());
+        out.extend_from_slice(&self.format.selector());
+        if self.format == L1AttributesFormat::Bedrock {
+            out.extend_from_slice(&U256::from(self.number).to_be_bytes::<32>());
+            out.extend_from_slice(&U256::from(self.timestamp).to_be_bytes::<32>());
+            out.extend_from_slice(&self.base_fee.to_be_bytes::<32>());
+            out.extend_from_slice(self.block_hash.as_slice());
+            out.extend_from_slice(&U256::from(self.sequence_number).to_be_bytes::<32>());
+            out.extend_from_slice(self.batcher_hash.as_slice());
+            out.extend_from_slice(&self.l1_fee_overhead.to_be_bytes::<32>());
+            out.extend_from_slice(&self.base_fee_scalar.to_be_bytes::<32>());
+            return out.into();
+        }
+
+        out.extend_from_slice(&self.base_fee_scalar.saturating_to::<u32>().to_be_bytes());
+        out.extend_from_slice(&self.blob_base_fee_scalar.to_be_bytes());
+        out.extend_from_slice(&self.sequence_number.to_be_bytes());
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        out.extend_from_slice(&self.number.to_be_bytes());
+        out.extend_from_slice(&self.base_fee.to_be_bytes::<32>());
+        out.extend_from_slice(&self.blob_base_fee.to_be_bytes::<32>());
+        out.extend_from_slice(self.block_hash.as_slice());
+        out.extend_from_slice(self.batcher_hash.as_slice());
+        if matches!(
+            self.format,
+            L1AttributesFormat::Isthmus | L1AttributesFormat::Jovian
+        ) {
+            out.extend_from_slice(&self.operator_fee_scalar.to_be_bytes());
+            out.extend_from_slice(&self.operator_fee_constant.to_be_bytes());
+        }
+        if self.format == L1AttributesFormat::Jovian {
+            out.extend_from_slice(&self.da_footprint_gas_scalar.to_be_bytes());
+        }
+        out.into()
+    }
+
+    /// Returns the L1 block info of the L2 block.
+    ///
+    /// Ecotone calldata does not contain the L1 fee overhead. It is only used by the first Ecotone
+    /// blocks that have empty fee scalars, for them it needs to be set from the L1Block contract.
+    pub fn l1_block_info(&self, l2_block: U256) -> L1BlockInfo {
+        let mut info = L1BlockInfo {
+            l2_block: Some(l2_block),
+            l1_base_fee: self.base_fee,
+            l1_base_fee_scalar: self.base_fee_scalar,
+            ..Default::default()
+        };
+        if self.format == L1AttributesFormat::Bedrock {
+            info.l1_fee_overhead = Some(self.l1_fee_overhead);
+            return info;
+        }
+
+        info.l1_blob_base_fee = Some(self.blob_base_fee);
+        info.l1_blob_base_fee_scalar = Some(U256::from(self.blob_base_fee_scalar));
+        info.empty_ecotone_scalars =
+            self.base_fee_scalar.is_zero() && self.blob_base_fee_scalar == 0;
+        if self.format == L1AttributesFormat::Ecotone {
+            return info;
+        }
+
+        info.operator_fee_scalar = Some(U256::from(self.operator_fee_scalar));
+        info.operator_fee_constant = Some(U256::from(self.operator_fee_constant));
+        if self.format == L1AttributesFormat::Jovian {
+            info.da_footprint_gas_scalar = Some(self.da_footprint_gas_scalar);
+        }
+        info
+    }
+}
+
+impl L1BlockInfo {
+    /// Decodes the L1 block info from the calldata of the L1 attributes deposit transaction of
+    /// the L2 block.
+    ///
+    /// See [`L1BlockAttributes::l1_block_info`].
+    pub fn from_l1_attributes(calldata: &[u8], l2_block: U256) -> Result<Self, L1AttributesError> {
+        Ok(L1BlockAttributes::decode(calldata)?.l1_block_info(l2_block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::hex;
+
+    fn attributes(format: L1AttributesFormat) -> L1BlockAttributes {
+        let mut attributes = L1BlockAttributes {
+            format,
+            number: 20_000_000,
+            timestamp: 1_700_000_000,
+            base_fee: U256::from(7_000_000_000u64),
+            block_hash: B256::repeat_byte(0x11),
+            sequence_number: 3,
+            batcher_hash: B256::repeat_byte(0x22),
+            base_fee_scalar: U256::from(1_368),
+            ..Default::default()
+        };
+        match format {
+            L1AttributesFormat::Bedrock => {
+                attributes.l1_fee_overhead = U256::from(188);
+                attributes.base_fee_scalar = U256::from(684_000);
+            }
+            L1AttributesFormat::Jovian => attributes.da_footprint_gas_scalar = 400,
+            _ => {}
+        }
+        if format != L1AttributesFormat::Bedrock {
+            attributes.blob_base_fee = U256::from(1);
+            attributes.blob_base_fee_scalar = 810_949;
+        }
+        if matches!(
+            format,
+            L1AttributesFormat::Isthmus | L1AttributesFormat::Jovian
+        ) {
+            attributes.operator_fee_scalar = 1_000;
+            attributes.operator_fee_constant = 50;
+        }
+        attributes
+    }
+
+    #[test]
+    fn roundtrip() {
+        for format in [
+            L1AttributesFormat::Bedrock,
+            L1AttributesFormat::Ecotone,
+            L1AttributesFormat::Isthmus,
+            L1AttributesFormat::Jovian,
+        ] {
+            let attributes = attributes(format);
+            let calldata = attributes.encode();
+            assert_eq!(calldata.len(), format.calldata_len());
+            assert_eq!(calldata[..4], format.selector());
+            assert_eq!(L1BlockAttributes::decode(&calldata), Ok(attributes));
+        }
+    }
+
+    #[test]
+    fn decode_ecotone() {
+        // Ecotone L1 attributes calldata.
+        let calldata = hex!("440a5e2000000558000c5fc500000000000000030000000065f0b86b000000000130b8a9000000000000000000000000000000000000000000000000000000013b0e4f83000000000000000000000000000000000000000000000000000000000000000167a7a3ab4d5d9ff7d6f8fca66b1c16cec4d4da0f3a0e0cc92a0a82c5bc5ee3a20000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f32985");
+        let attributes = L1BlockAttributes::decode(&calldata).unwrap();
+        assert_eq!(attributes.format, L1AttributesFormat::Ecotone);
+        assert_eq!(attributes.base_fee_scalar, U256::from(1_368));
+        assert_eq!(attributes.blob_base_fee_scalar, 810_949);
+        assert_eq!(attributes.sequence_number, 3);
+        assert_eq!(attributes.timestamp, 0x65f0b86b);
+        assert_eq!(attributes.number, 0x0130b8a9);
+        assert_eq!(attributes.base_fee, U256::from(0x013b0e4f83u64));
+        assert_eq!(attributes.blob_base_fee, U256::from(1));
+        assert_eq!(attributes.encode(), calldata[..]);
+
+        let info = L1BlockInfo::from_l1_attributes(&calldata, U256::from(1)).unwrap();
+        assert_eq!(info.l2_block, Some(U256::from(1)));
+        assert_eq!(info.l1_base_fee, attributes.base_fee);
+        assert_eq!(info.l1_blob_base_fee_scalar, Some(U256::from(810_949)));
+        assert_eq!(info.operator_fee_scalar, None);
+        assert!(!info.empty_ecotone_scalars);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let calldata = attributes(L1AttributesFormat::Isthmus).encode();
+        assert_eq!(
+            L1BlockAttributes::decode(&calldata[..100]),
+            Err(L1AttributesError::InvalidLength {
+                expected: 176,
+                got: 100
+            })
+        );
+        assert_eq!(
+            L1BlockAttributes::decode(&[0xaa; 176]),
+            Err(L1AttributesError::UnknownSelector([0xaa; 4]))
+        );
+        let mut bedrock = attributes(L1AttributesFormat::Bedrock).encode().to_vec();
+        // Block number that does not fit in `uint64`.
+        bedrock[4] = 1;
+        assert_eq!(
+            L1BlockAttributes::decode(&bedrock),
+            Err(L1AttributesError::InvalidValue)
+        );
+    }
+}
@@ -1,11 +1,13 @@
 //! Contains the `[L1BlockInfo]` type and its implementation.
 use crate::{
     constants::{
-        BASE_FEE_SCALAR_OFFSET, BLOB_BASE_FEE_SCALAR_OFFSET, DA_FOOTPRINT_GAS_SCALAR_OFFSET,
-        DA_FOOTPRINT_GAS_SCALAR_SLOT, ECOTONE_L1_BLOB_BASE_FEE_SLOT, ECOTONE_L1_FEE_SCALARS_SLOT,
-        EMPTY_SCALARS, L1_BASE_FEE_SLOT, L1_BLOCK_CONTRACT, L1_OVERHEAD_SLOT, L1_SCALAR_SLOT,
+        BASE_FEE_RECIPIENT, BASE_FEE_SCALAR_OFFSET, BLOB_BASE_FEE_SCALAR_OFFSET,
+        DA_FOOTPRINT_GAS_SCALAR_OFFSET, DA_FOOTPRINT_GAS_SCALAR_SLOT,
+        ECOTONE_L1_BLOB_BASE_FEE_SLOT, ECOTONE_L1_FEE_SCALARS_SLOT, EMPTY_SCALARS,
+        L1_BASE_FEE_SLOT, L1_BLOCK_CONTRACT, L1_FEE_RECIPIENT, L1_OVERHEAD_SLOT, L1_SCALAR_SLOT,
         NON_ZERO_BYTE_COST, OPERATOR_FEE_CONSTANT_OFFSET, OPERATOR_FEE_JOVIAN_MULTIPLIER,
-        OPERATOR_FEE_SCALARS_SLOT, OPERATOR_FEE_SCALAR_DECIMAL, OPERATOR_FEE_SCALAR_OFFSET,
+        OPERATOR_FEE_RECIPIENT, OPERATOR_FEE_SCALARS_SLOT, OPERATOR_FEE_SCALAR_DECIMAL,
+        OPERATOR_FEE_SCALAR_OFFSET,
     },
     transaction::{estimate_tx_compressed_size, OpTxTr},
     OpSpecId,
@@ -18,6 +20,7 @@ use revm::{
     },
     primitives::U256,
 };
+use rollup::{FeeVaultAddresses, FeeVaults, L1DataFee};
 
 /// L1 block info
 ///
@@ -253,6 +256,19 @@ impl L1BlockInfo {
         U256::from(estimate_tx_compressed_size(input))
     }
 
+    /// Calculates the DA footprint of the transaction, counted against the block gas limit from
+    /// Jovian.
+    ///
+    /// DA footprint is the estimated compressed size of the transaction scaled by the DA footprint
+    /// gas scalar.
+    pub fn da_footprint(&self, input: &[u8]) -> u64 {
+        if input.is_empty() || input.first() == Some(&0x7E) {
+            return 0;
+        }
+        (estimate_tx_compressed_size(input) / 1_000_000)
+            .saturating_mul(self.da_footprint_gas_scalar.unwrap_or_default() as u64)
+    }
+
     /// Clears the cached L1 cost of the transaction.
     pub fn clear_tx_l1_cost(&mut self) {
         self.tx_l1_cost = None;
@@ -357,7 +373,7 @@ impl L1BlockInfo {
     }
 
     // l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar
-    fn calculate_l1_fee_scaled_ecotone(&self) -> U256 {
+    pub(crate) fn calculate_l1_fee_scaled_ecotone(&self) -> U256 {
         let calldata_cost_per_byte = self
             .l1_base_fee
             .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
@@ -371,6 +387,48 @@ impl L1BlockInfo {
     }
 }
 
+impl L1DataFee for L1BlockInfo {
+    type Spec = OpSpecId;
+
+    fn load<DB: Database>(
+        &mut self,
+        db: &mut DB,
+        l2_block: U256,
+        spec: OpSpecId,
+    ) -> Result<(), DB::Error> {
+        // L1 block info is reloaded from the database if it is not for the current block.
+        if self.l2_block != Some(l2_block) {
+            *self = L1BlockInfo::try_fetch(db, l2_block, spec)?;
+        }
+        Ok(())
+    }
+
+    fn l1_data_fee(&mut self, enveloped_tx: &[u8], spec: OpSpecId) -> U256 {
+        self.calculate_tx_l1_cost(enveloped_tx, spec)
+    }
+
+    fn operator_fee(&self, enveloped_tx: &[u8], gas: u64, spec: OpSpecId) -> U256 {
+        if !spec.is_enabled_in(OpSpecId::ISTHMUS) {
+            return U256::ZERO;
+        }
+        self.operator_fee_charge(enveloped_tx, U256::from(gas), spec)
+    }
+
+    fn clear_tx_cache(&mut self) {
+        self.clear_tx_l1_cost();
+    }
+}
+
+impl FeeVaults for L1BlockInfo {
+    fn fee_vaults(&self) -> FeeVaultAddresses {
+        FeeVaultAddresses {
+            base_fee_vault: BASE_FEE_RECIPIENT,
+            l1_fee_vault: L1_FEE_RECIPIENT,
+            operator_fee_vault: OPERATOR_FEE_RECIPIENT,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,10 +6,15 @@
 extern crate alloc as std;
 
 pub mod api;
+pub mod block;
 pub mod constants;
+pub mod estimate;
 pub mod evm;
 pub mod fast_lz;
+pub mod gas_price_oracle;
 pub mod handler;
+pub mod interop;
+pub mod l1_attributes;
 pub mod l1block;
 pub mod precompiles;
 pub mod result;
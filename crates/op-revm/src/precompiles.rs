@@ -1,16 +1,17 @@
 //! Contains Optimism specific precompiles.
-use crate::OpSpecId;
+use crate::{constants::GAS_PRICE_ORACLE, gas_price_oracle, L1BlockInfo, OpSpecId};
 use revm::{
     context::Cfg,
-    context_interface::ContextTr,
+    context_interface::{Block, ContextTr, JournalTr},
     handler::{EthPrecompiles, PrecompileProvider},
-    interpreter::{CallInputs, InterpreterResult},
+    interpreter::{CallInputs, Gas, InstructionResult, InterpreterResult},
     precompile::{
         self, bn254, secp256r1, Precompile, PrecompileError, PrecompileId, PrecompileResult,
         Precompiles,
     },
-    primitives::{hardfork::SpecId, Address, OnceLock},
+    primitives::{hardfork::SpecId, Address, Bytes, OnceLock},
 };
+use rollup::L1DataFee;
 use std::{boxed::Box, string::String};
 
 /// Optimism precompile provider
@@ -155,6 +156,104 @@ where
     }
 }
 
+/// Precompile provider that answers calls to the [`GAS_PRICE_ORACLE`] predeploy natively from the
+/// [`L1BlockInfo`] of the context, instead of running the predeploy code.
+///
+/// Other calls go to the inner provider. Useful when running against a state that does not
+/// contain the predeploy or the `L1Block` storage, see [`gas_price_oracle`].
+#[derive(Debug, Clone, Default)]
+pub struct GasPriceOraclePrecompiles<P = OpPrecompiles> {
+    /// Inner precompile provider.
+    inner: P,
+}
+
+impl<P> GasPriceOraclePrecompiles<P> {
+    /// Creates a new provider on top of the inner provider.
+    #[inline]
+    pub fn new(inner: P) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the inner precompile provider.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+}
+
+impl<CTX, P> PrecompileProvider<CTX> for GasPriceOraclePrecompiles<P>
+where
+    CTX: ContextTr<Cfg: Cfg<Spec = OpSpecId>, Chain = L1BlockInfo>,
+    P: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    type Output = InterpreterResult;
+
+    #[inline]
+    fn set_spec(&mut self, spec: <CTX::Cfg as Cfg>::Spec) -> bool {
+        self.inner.set_spec(spec)
+    }
+
+    #[inline]
+    fn run(
+        &mut self,
+        context: &mut CTX,
+        inputs: &CallInputs,
+    ) -> Result<Option<Self::Output>, String> {
+        if inputs.bytecode_address == GAS_PRICE_ORACLE {
+            return Ok(Some(run_gas_price_oracle(context, inputs)));
+        }
+        self.inner.run(context, inputs)
+    }
+
+    #[inline]
+    fn warm_addresses(&self) -> Box<impl Iterator<Item = Address>> {
+        self.inner.warm_addresses()
+    }
+
+    #[inline]
+    fn contains(&self, address: &Address) -> bool {
+        self.inner.contains(address)
+    }
+}
+
+/// Runs a call to the GasPriceOracle predeploy with [`gas_price_oracle::run`].
+///
+/// L1 block info is loaded first, deposits do not load it before execution.
+fn run_gas_price_oracle<CTX>(context: &mut CTX, inputs: &CallInputs) -> InterpreterResult
+where
+    CTX: ContextTr<Cfg: Cfg<Spec = OpSpecId>, Chain = L1BlockInfo>,
+{
+    let mut result = InterpreterResult {
+        result: InstructionResult::Return,
+        gas: Gas::new(inputs.gas_limit).with_mode(context.cfg().gas_mode()),
+        output: Bytes::new(),
+    };
+
+    let spec = context.cfg().spec();
+    let (block, _, _, journal, chain, _) = context.all_mut();
+    if let Err(error) = chain.load(journal.db_mut(), block.number(), spec) {
+        *context.error() = Err(error.into());
+        result.result = InstructionResult::FatalExternalError;
+        return result;
+    }
+
+    let input = inputs.input.bytes(context);
+    match gas_price_oracle::run(context.chain(), spec, &input, result.gas.available()) {
+        Ok(output) => {
+            let underflow = result.gas.record_cost(output.gas_used);
+            assert!(underflow, "Gas underflow is not possible");
+            result.result = if output.reverted {
+                InstructionResult::Revert
+            } else {
+                InstructionResult::Return
+            };
+            result.output = output.bytes;
+        }
+        Err(_) => result.result = InstructionResult::PrecompileOOG,
+    }
+    result
+}
+
 impl Default for OpPrecompiles {
     fn default() -> Self {
         Self::new_with_spec(OpSpecId::JOVIAN)
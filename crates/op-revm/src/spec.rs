@@ -1,6 +1,7 @@
 //! Contains the `[OpSpecId]` type and its implementation.
 use core::str::FromStr;
 use revm::primitives::hardfork::{name as eth_name, SpecId, UnknownHardfork};
+use rollup::RollupSpec;
 
 /// Optimism spec id.
 #[repr(u8)]
@@ -51,6 +52,8 @@ impl OpSpecId {
     }
 }
 
+impl RollupSpec for OpSpecId {}
+
 impl From<OpSpecId> for SpecId {
     fn from(spec: OpSpecId) -> Self {
         spec.into_eth_spec()
@@ -10,27 +10,16 @@ use revm::{
     handler::SystemCallTx,
     primitives::{Address, Bytes, TxKind, B256, U256},
 };
+use rollup::RollupTxTr;
 use std::vec;
 
 /// Optimism Transaction trait.
+///
+/// Extends the [`RollupTxTr`] with the source hash of OP Stack deposits.
 #[auto_impl(&, &mut, Box, Arc)]
-pub trait OpTxTr: Transaction {
-    /// Enveloped transaction bytes.
-    fn enveloped_tx(&self) -> Option<&Bytes>;
-
+pub trait OpTxTr: RollupTxTr {
     /// Source hash of the deposit transaction.
     fn source_hash(&self) -> Option<B256>;
-
-    /// Mint of the deposit transaction
-    fn mint(&self) -> Option<u128>;
-
-    /// Whether the transaction is a system transaction
-    fn is_system_transaction(&self) -> bool;
-
-    /// Returns `true` if transaction is of type [`DEPOSIT_TRANSACTION_TYPE`].
-    fn is_deposit(&self) -> bool {
-        self.tx_type() == DEPOSIT_TRANSACTION_TYPE
-    }
 }
 
 /// Optimism transaction.
@@ -189,18 +178,11 @@ impl<T: Transaction> Transaction for OpTransaction<T> {
     }
 }
 
-impl<T: Transaction> OpTxTr for OpTransaction<T> {
+impl<T: Transaction> RollupTxTr for OpTransaction<T> {
     fn enveloped_tx(&self) -> Option<&Bytes> {
         self.enveloped_tx.as_ref()
     }
 
-    fn source_hash(&self) -> Option<B256> {
-        if self.tx_type() != DEPOSIT_TRANSACTION_TYPE {
-            return None;
-        }
-        Some(self.deposit.source_hash)
-    }
-
     fn mint(&self) -> Option<u128> {
         self.deposit.mint
     }
@@ -208,6 +190,20 @@ impl<T: Transaction> OpTxTr for OpTransaction<T> {
     fn is_system_transaction(&self) -> bool {
         self.deposit.is_system_transaction
     }
+
+    /// Returns `true` if transaction is of type [`DEPOSIT_TRANSACTION_TYPE`].
+    fn is_deposit(&self) -> bool {
+        self.tx_type() == DEPOSIT_TRANSACTION_TYPE
+    }
+}
+
+impl<T: Transaction> OpTxTr for OpTransaction<T> {
+    fn source_hash(&self) -> Option<B256> {
+        if self.tx_type() != DEPOSIT_TRANSACTION_TYPE {
+            return None;
+        }
+        Some(self.deposit.source_hash)
+    }
 }
 
 /// Builder for constructing [`OpTransaction`] instances
@@ -1,9 +1,11 @@
 //! Contains the `[OpTransactionError]` type.
+use crate::interop::InteropError;
 use core::fmt::Display;
 use revm::context_interface::{
     result::{EVMError, InvalidTransaction},
     transaction::TransactionError,
 };
+use rollup::RollupTransactionError;
 
 /// Optimism transaction validation error.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -46,6 +48,8 @@ pub enum OpTransactionError {
     /// Non-deposit transactions on Optimism must have `enveloped_tx` field set
     /// to properly calculate L1 costs.
     MissingEnvelopedTx,
+    /// Interop executing messages declared in the access list are invalid.
+    Interop(InteropError),
 }
 
 impl TransactionError for OpTransactionError {}
@@ -72,6 +76,7 @@ impl Display for OpTransactionError {
                     "missing enveloped transaction bytes for non-deposit transaction"
                 )
             }
+            Self::Interop(error) => error.fmt(f),
         }
     }
 }
@@ -84,6 +89,23 @@ impl From<InvalidTransaction> for OpTransactionError {
     }
 }
 
+impl From<RollupTransactionError> for OpTransactionError {
+    fn from(value: RollupTransactionError) -> Self {
+        match value {
+            RollupTransactionError::Base(error) => Self::Base(error),
+            RollupTransactionError::DepositSystemTx => Self::DepositSystemTxPostRegolith,
+            RollupTransactionError::HaltedDeposit => Self::HaltedDepositPostRegolith,
+            RollupTransactionError::MissingEnvelopedTx => Self::MissingEnvelopedTx,
+        }
+    }
+}
+
+impl From<InteropError> for OpTransactionError {
+    fn from(value: InteropError) -> Self {
+        Self::Interop(value)
+    }
+}
+
 impl<DBError> From<OpTransactionError> for EVMError<DBError, OpTransactionError> {
     fn from(value: OpTransactionError) -> Self {
         Self::Transaction(value)
@@ -114,6 +136,10 @@ mod test {
             OpTransactionError::MissingEnvelopedTx.to_string(),
             "missing enveloped transaction bytes for non-deposit transaction"
         );
+        assert_eq!(
+            OpTransactionError::Interop(InteropError::UnknownMessage).to_string(),
+            "unknown initiating message"
+        );
     }
 
     #[cfg(feature = "serde")]
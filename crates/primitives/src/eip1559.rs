@@ -0,0 +1,18 @@
+//! EIP-1559: Fee market change for ETH 1.0 chain
+//!
+//! Constants for base fee and gas limit calculation introduced in the London hard fork.
+
+/// Maximum rate at which the base fee can change between blocks is `1 / BASE_FEE_MAX_CHANGE_DENOMINATOR`.
+pub const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Gas target of a block is its gas limit divided by the elasticity multiplier.
+pub const ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// Base fee of the first London block.
+pub const INITIAL_BASE_FEE: u64 = 1_000_000_000;
+
+/// Gas limit can change by less than `parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR` between blocks.
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+
+/// Minimum gas limit of a block.
+pub const MIN_GAS_LIMIT: u64 = 5000;
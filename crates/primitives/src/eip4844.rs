@@ -42,3 +42,21 @@ pub const TARGET_BLOB_GAS_PER_BLOCK_PRAGUE: u64 =
 
 /// Controls the maximum rate of change for blob gas price. Hex 0x4c6964.
 pub const BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE: u64 = 5_007_716;
+
+/// Target number of the blob per block of the first blob parameter only fork (BPO1).
+pub const TARGET_BLOB_NUMBER_PER_BLOCK_BPO1: u64 = 10;
+
+/// Max number of blobs per block of the first blob parameter only fork (BPO1).
+pub const MAX_BLOB_NUMBER_PER_BLOCK_BPO1: u64 = 15;
+
+/// Controls the maximum rate of change for blob gas price in BPO1.
+pub const BLOB_BASE_FEE_UPDATE_FRACTION_BPO1: u64 = 8_346_193;
+
+/// Target number of the blob per block of the second blob parameter only fork (BPO2).
+pub const TARGET_BLOB_NUMBER_PER_BLOCK_BPO2: u64 = 14;
+
+/// Max number of blobs per block of the second blob parameter only fork (BPO2).
+pub const MAX_BLOB_NUMBER_PER_BLOCK_BPO2: u64 = 21;
+
+/// Controls the maximum rate of change for blob gas price in BPO2.
+pub const BLOB_BASE_FEE_UPDATE_FRACTION_BPO2: u64 = 11_684_671;
@@ -0,0 +1,6 @@
+//! EIP-7918: Blob base fee bounded by execution cost
+//!
+//! Introduces a reserve price for blobs that is tied to the execution base fee.
+
+/// Execution gas cost used to bound the blob base fee from below.
+pub const BLOB_BASE_COST: u64 = 1 << 13;
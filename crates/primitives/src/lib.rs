@@ -16,6 +16,7 @@
 extern crate alloc as std;
 
 pub mod constants;
+pub mod eip1559;
 pub mod eip170;
 pub mod eip3860;
 pub mod eip4844;
@@ -23,6 +24,7 @@ pub mod eip7702;
 pub mod eip7823;
 pub mod eip7825;
 pub mod eip7907;
+pub mod eip7918;
 pub mod hardfork;
 pub mod hints_util;
 mod once_lock;
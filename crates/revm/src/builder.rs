@@ -0,0 +1,556 @@
+//! Block building on top of [`State`].
+//!
+//! [`BlockBuilder`] takes a block template and packs candidate transactions into it. Transactions
+//! that fail validation or do not fit in the remaining block gas or blob gas are skipped.
+//! System calls of the block are applied by the builder: the
+//! [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) and
+//! [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) calls in [`BlockBuilder::new`] and the
+//! [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) and
+//! [EIP-7251](https://eips.ethereum.org/EIPS/eip-7251) request calls in [`BlockBuilder::finish`].
+//!
+//! The database of the EVM needs to be a [`State`] built with
+//! [`StateBuilder::with_bundle_update`](crate::database::StateBuilder::with_bundle_update) so the
+//! [`BundleState`] of the block can be produced by [`BlockBuilder::finish`].
+use crate::{
+    context::{BlockEnv, Cfg, ContextTr},
+    context_interface::{
+        block::BlobParams,
+        result::{EVMError, ExecutionResult, HaltReasonTr},
+        Transaction,
+    },
+    database::{states::bundle_state::BundleRetention, BundleState, State},
+    database_interface::Database,
+    handler::EvmTr,
+    primitives::{address, hardfork::SpecId, Address, Bytes, Log, B256},
+    ExecuteCommitEvm, ExecuteEvm, SystemCallCommitEvm,
+};
+use std::{format, vec::Vec};
+
+/// Intrinsic gas of the cheapest transaction.
+const MIN_TX_GAS: u64 = 21_000;
+
+/// The address of the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) beacon roots contract.
+pub const BEACON_ROOTS_ADDRESS: Address = address!("0x000F3df6D732807Ef1319fB7B8bB8522d0Beac02");
+
+/// The address of the [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) history storage
+/// contract.
+pub const HISTORY_STORAGE_ADDRESS: Address = address!("0x0000F90827F1C53a10cb7A02335B175320002935");
+
+/// The address of the [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) withdrawal request
+/// contract.
+pub const WITHDRAWAL_REQUEST_ADDRESS: Address =
+    address!("0x00000961Ef480Eb55e80D19ad83579A64c007002");
+
+/// The address of the [EIP-7251](https://eips.ethereum.org/EIPS/eip-7251) consolidation request
+/// contract.
+pub const CONSOLIDATION_REQUEST_ADDRESS: Address =
+    address!("0x0000BBdDc7CE488642fb579F8B00f3a590007251");
+
+/// Header template of the block to build.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockTemplate {
+    /// Environment of the block.
+    pub env: BlockEnv,
+    /// Hash of the parent block, stored in the history storage contract from Prague.
+    pub parent_hash: Option<B256>,
+    /// Parent beacon block root, stored in the beacon roots contract from Cancun.
+    pub parent_beacon_block_root: Option<B256>,
+}
+
+impl From<BlockEnv> for BlockTemplate {
+    fn from(env: BlockEnv) -> Self {
+        Self {
+            env,
+            ..Default::default()
+        }
+    }
+}
+
+/// Receipt of a transaction included in a built block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Receipt {
+    /// Transaction type.
+    pub tx_type: u8,
+    /// Whether the transaction was successful.
+    pub success: bool,
+    /// Gas used by the block up to and including this transaction.
+    pub cumulative_gas_used: u64,
+    /// Logs emitted by the transaction.
+    pub logs: Vec<Log>,
+}
+
+/// Reason a transaction was not included in the block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason<Error> {
+    /// Transaction gas limit exceeds the gas left in the block.
+    BlockGasLimitReached,
+    /// Transaction blob gas exceeds the blob gas left in the block.
+    BlobGasLimitReached,
+    /// Transaction failed validation.
+    Invalid(Error),
+}
+
+/// Output of [`BlockBuilder::finish`].
+#[derive(Debug)]
+pub struct BuiltBlock<TX, R, Error> {
+    /// Environment of the built block.
+    pub block: BlockEnv,
+    /// Included transactions.
+    pub transactions: Vec<TX>,
+    /// Execution results of included transactions.
+    pub results: Vec<R>,
+    /// Receipts of included transactions.
+    pub receipts: Vec<Receipt>,
+    /// Skipped transactions with the reason they were skipped.
+    pub skipped: Vec<(TX, SkipReason<Error>)>,
+    /// Gas used by included transactions.
+    pub gas_used: u64,
+    /// Blob gas used by included transactions.
+    pub blob_gas_used: u64,
+    /// Withdrawal requests returned by the withdrawal request contract, empty before Prague.
+    pub withdrawal_requests: Bytes,
+    /// Consolidation requests returned by the consolidation request contract, empty before
+    /// Prague.
+    pub consolidation_requests: Bytes,
+    /// State changes of the block, including system calls.
+    pub bundle_state: BundleState,
+}
+
+/// Builds a block by packing transactions into a block template.
+#[derive(Debug)]
+pub struct BlockBuilder<'a, EVM: ExecuteEvm> {
+    evm: &'a mut EVM,
+    block: BlockEnv,
+    max_blob_gas: u64,
+    gas_used: u64,
+    blob_gas_used: u64,
+    transactions: Vec<EVM::Tx>,
+    results: Vec<EVM::ExecutionResult>,
+    receipts: Vec<Receipt>,
+    skipped: Vec<(EVM::Tx, SkipReason<EVM::Error>)>,
+}
+
+impl<'a, EVM, DB, H, DBError, TxError> BlockBuilder<'a, EVM>
+where
+    EVM: ExecuteCommitEvm<
+            Block = BlockEnv,
+            ExecutionResult = ExecutionResult<H>,
+            Error = EVMError<DBError, TxError>,
+        > + SystemCallCommitEvm
+        + EvmTr<Context: ContextTr<Db = State<DB>>>,
+    EVM::Tx: Clone,
+    DB: Database,
+    State<DB>: Database<Error = DBError>,
+    H: HaltReasonTr,
+{
+    /// Creates a new builder, sets the block of the template as the block of the EVM and applies
+    /// the system calls made before the transactions.
+    ///
+    /// The parent hash is stored from Prague and the parent beacon block root from Cancun, if they
+    /// are set in the template. As specified by the EIPs, the calls are skipped in the genesis
+    /// block and if the system contract has no code.
+    ///
+    /// Maximum blob gas of the block defaults to the maximum of the [`BlobParams`] of the current
+    /// spec. Blob parameter only forks are not specs, their parameters are set with
+    /// [`BlockBuilder::with_blob_params`]. The maximum number of blobs of a transaction,
+    /// [`Cfg::max_blobs_per_tx`], is checked by the transaction validation.
+    pub fn new(evm: &'a mut EVM, template: BlockTemplate) -> Result<Self, EVM::Error> {
+        let spec: SpecId = evm.ctx_ref().cfg().spec().into();
+        let max_blob_gas = if spec.is_enabled_in(SpecId::CANCUN) {
+            BlobParams::from_spec(spec).max_blob_gas_per_block
+        } else {
+            0
+        };
+        let BlockTemplate {
+            env: block,
+            parent_hash,
+            parent_beacon_block_root,
+        } = template;
+        evm.set_block(block.clone());
+        let mut builder = Self {
+            evm,
+            block,
+            max_blob_gas,
+            gas_used: 0,
+            blob_gas_used: 0,
+            transactions: Vec::new(),
+            results: Vec::new(),
+            receipts: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        if !builder.block.number.is_zero() {
+            if let Some(parent_hash) = parent_hash.filter(|_| spec.is_enabled_in(SpecId::PRAGUE)) {
+                builder.apply_pre_block_call(HISTORY_STORAGE_ADDRESS, parent_hash)?;
+            }
+            if let Some(root) =
+                parent_beacon_block_root.filter(|_| spec.is_enabled_in(SpecId::CANCUN))
+            {
+                builder.apply_pre_block_call(BEACON_ROOTS_ADDRESS, root)?;
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Sets the maximum blob gas of the block.
+    pub fn with_max_blob_gas(mut self, max_blob_gas: u64) -> Self {
+        self.max_blob_gas = max_blob_gas;
+        self
+    }
+
+    /// Sets the maximum blob gas of the block from the blob parameters of the block.
+    pub fn with_blob_params(self, blob_params: BlobParams) -> Self {
+        self.with_max_blob_gas(blob_params.max_blob_gas_per_block)
+    }
+
+    /// Returns the gas used by included transactions.
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    /// Returns the blob gas used by included transactions.
+    pub fn blob_gas_used(&self) -> u64 {
+        self.blob_gas_used
+    }
+
+    /// Executes and commits an additional system call, for chains that have system contracts
+    /// other than the ones applied by the builder.
+    ///
+    /// System calls do not count towards the gas of the block.
+    pub fn apply_system_call(
+        &mut self,
+        system_contract_address: Address,
+        data: Bytes,
+    ) -> Result<ExecutionResult<H>, EVM::Error> {
+        self.evm.system_call_commit(system_contract_address, data)
+    }
+
+    /// Tries to include the transaction in the block.
+    ///
+    /// Returns `Ok(false)` if the transaction was skipped, either because it does not fit in the
+    /// block or because it failed validation. Any other error is returned.
+    pub fn add_transaction(&mut self, tx: EVM::Tx) -> Result<bool, EVM::Error> {
+        if tx.gas_limit() > self.block.gas_limit.saturating_sub(self.gas_used) {
+            self.skipped.push((tx, SkipReason::BlockGasLimitReached));
+            return Ok(false);
+        }
+        let blob_gas = tx.total_blob_gas();
+        if blob_gas > self.max_blob_gas.saturating_sub(self.blob_gas_used) {
+            self.skipped.push((tx, SkipReason::BlobGasLimitReached));
+            return Ok(false);
+        }
+
+        let result = match self.evm.transact_commit(tx.clone()) {
+            Ok(result) => result,
+            Err(error @ EVMError::Transaction(_)) => {
+                self.skipped.push((tx, SkipReason::Invalid(error)));
+                return Ok(false);
+            }
+            Err(error) => return Err(error),
+        };
+
+        self.gas_used += result.gas_used();
+        self.blob_gas_used += blob_gas;
+        self.receipts.push(Receipt {
+            tx_type: tx.tx_type(),
+            success: result.is_success(),
+            cumulative_gas_used: self.gas_used,
+            logs: result.logs().to_vec(),
+        });
+        self.transactions.push(tx);
+        self.results.push(result);
+        Ok(true)
+    }
+
+    /// Tries to include all candidate transactions in order.
+    ///
+    /// Stops early once the block has no gas left for the cheapest possible transaction.
+    pub fn add_transactions(
+        &mut self,
+        txs: impl IntoIterator<Item = EVM::Tx>,
+    ) -> Result<(), EVM::Error> {
+        for tx in txs {
+            self.add_transaction(tx)?;
+            if self.block.gas_limit.saturating_sub(self.gas_used) < MIN_TX_GAS {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the system calls made after the transactions and finishes the block, merging all
+    /// transitions into the returned [`BundleState`].
+    ///
+    /// From Prague the withdrawal and consolidation request contracts are called. The block is
+    /// invalid if a request contract has no code or its call fails, this is returned as
+    /// [`EVMError::Custom`]. Deposit requests are not collected, they are read from the logs of
+    /// the deposit contract of the chain.
+    pub fn finish(
+        mut self,
+    ) -> Result<BuiltBlock<EVM::Tx, ExecutionResult<H>, EVM::Error>, EVM::Error> {
+        let spec: SpecId = self.evm.ctx_ref().cfg().spec().into();
+        let (withdrawal_requests, consolidation_requests) = if spec.is_enabled_in(SpecId::PRAGUE) {
+            (
+                self.apply_request_call(WITHDRAWAL_REQUEST_ADDRESS)?,
+                self.apply_request_call(CONSOLIDATION_REQUEST_ADDRESS)?,
+            )
+        } else {
+            Default::default()
+        };
+
+        let db = self.evm.ctx().db_mut();
+        db.merge_transitions(BundleRetention::Reverts);
+        let bundle_state = db.take_bundle();
+
+        Ok(BuiltBlock {
+            block: self.block,
+            transactions: self.transactions,
+            results: self.results,
+            receipts: self.receipts,
+            skipped: self.skipped,
+            gas_used: self.gas_used,
+            blob_gas_used: self.blob_gas_used,
+            withdrawal_requests,
+            consolidation_requests,
+            bundle_state,
+        })
+    }
+
+    /// Returns `true` if the system contract has code.
+    fn has_code(&mut self, address: Address) -> Result<bool, EVM::Error> {
+        let info = self
+            .evm
+            .ctx()
+            .db_mut()
+            .basic(address)
+            .map_err(EVMError::Database)?;
+        Ok(info.is_some_and(|info| !info.is_empty_code_hash()))
+    }
+
+    /// Applies a system call made before the transactions, skipping it if the contract has no
+    /// code.
+    fn apply_pre_block_call(&mut self, address: Address, data: B256) -> Result<(), EVM::Error> {
+        if self.has_code(address)? {
+            self.apply_system_call(address, data.into())?;
+        }
+        Ok(())
+    }
+
+    /// Applies a request contract call made after the transactions and returns its output.
+    fn apply_request_call(&mut self, address: Address) -> Result<Bytes, EVM::Error> {
+        if !self.has_code(address)? {
+            return Err(EVMError::Custom(format!(
+                "request contract {address} has no code"
+            )));
+        }
+        match self.apply_system_call(address, Bytes::new())? {
+            ExecutionResult::Success { output, .. } => Ok(output.into_data()),
+            result => Err(EVMError::Custom(format!(
+                "request contract {address} call failed: {result:?}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bytecode::opcode,
+        context::TxEnv,
+        database::InMemoryDB,
+        primitives::{address, b256, eip4844::GAS_PER_BLOB, B256, U256},
+        state::{AccountInfo, Bytecode},
+        Context, MainBuilder, MainContext,
+    };
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const RECEIVER: Address = address!("0x2000000000000000000000000000000000000002");
+    const BLOB_HASH: B256 =
+        b256!("0x0100000000000000000000000000000000000000000000000000000000000001");
+
+    /// Returns a database with request contracts that return a single byte.
+    fn db() -> InMemoryDB {
+        let mut db = InMemoryDB::default();
+        let request_code = Bytecode::new_legacy(Bytes::from_static(&[
+            opcode::PUSH1,
+            0xab,
+            opcode::PUSH0,
+            opcode::MSTORE8,
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH0,
+            opcode::RETURN,
+        ]));
+        for address in [WITHDRAWAL_REQUEST_ADDRESS, CONSOLIDATION_REQUEST_ADDRESS] {
+            db.insert_account_info(
+                address,
+                AccountInfo::default().with_code(request_code.clone()),
+            );
+        }
+        db
+    }
+
+    fn transfer(nonce: u64, gas_limit: u64) -> TxEnv {
+        TxEnv::builder()
+            .caller(CALLER)
+            .call(RECEIVER)
+            .nonce(nonce)
+            .value(U256::from(1))
+            .gas_limit(gas_limit)
+            .build_fill()
+    }
+
+    #[test]
+    fn build_block_skips_invalid_and_oversized_transactions() {
+        let mut db = db();
+        db.insert_account_info(
+            CALLER,
+            AccountInfo {
+                balance: U256::from(1_000),
+                ..Default::default()
+            },
+        );
+        let state = State::builder()
+            .with_database(db)
+            .with_bundle_update()
+            .build();
+        let mut evm = Context::mainnet().with_db(state).build_mainnet();
+
+        let block = BlockEnv {
+            number: U256::from(1),
+            gas_limit: 50_000,
+            ..Default::default()
+        };
+        let mut builder = BlockBuilder::new(&mut evm, block.into()).unwrap();
+        builder
+            .add_transactions([
+                transfer(0, 21_000),
+                // nonce is too high
+                transfer(5, 21_000),
+                // does not fit in the remaining gas
+                transfer(1, 30_000),
+                transfer(1, 21_000),
+            ])
+            .unwrap();
+        let built = builder.finish().unwrap();
+
+        assert_eq!(built.transactions.len(), 2);
+        assert_eq!(built.gas_used, 42_000);
+        assert_eq!(built.receipts[1].cumulative_gas_used, 42_000);
+        assert_eq!(built.skipped.len(), 2);
+        assert!(matches!(built.skipped[0].1, SkipReason::Invalid(_)));
+        assert_eq!(built.skipped[1].1, SkipReason::BlockGasLimitReached);
+
+        let receiver = built.bundle_state.account(&RECEIVER).unwrap();
+        assert_eq!(
+            receiver.info.as_ref().map(|info| info.balance),
+            Some(U256::from(2))
+        );
+    }
+
+    #[test]
+    fn build_block_skips_transactions_over_blob_gas() {
+        let blob_tx = |nonce: u64, blobs: usize| {
+            TxEnv::builder()
+                .caller(CALLER)
+                .call(RECEIVER)
+                .nonce(nonce)
+                .gas_limit(21_000)
+                .blob_hashes(vec![BLOB_HASH; blobs])
+                .max_fee_per_blob_gas(1)
+                .build_fill()
+        };
+        let mut db = db();
+        db.insert_account_info(
+            CALLER,
+            AccountInfo {
+                balance: U256::from(100 * GAS_PER_BLOB),
+                ..Default::default()
+            },
+        );
+        let state = State::builder()
+            .with_database(db)
+            .with_bundle_update()
+            .build();
+        let mut evm = Context::mainnet().with_db(state).build_mainnet();
+
+        // Prague allows 9 blobs per block.
+        let mut builder = BlockBuilder::new(&mut evm, BlockEnv::default().into()).unwrap();
+        builder
+            .add_transactions([blob_tx(0, 6), blob_tx(1, 4), blob_tx(1, 3)])
+            .unwrap();
+        let built = builder.finish().unwrap();
+
+        assert_eq!(built.transactions.len(), 2);
+        assert_eq!(built.blob_gas_used, 9 * GAS_PER_BLOB);
+        assert_eq!(built.skipped.len(), 1);
+        assert_eq!(built.skipped[0].1, SkipReason::BlobGasLimitReached);
+        assert_eq!(built.skipped[0].0.blob_hashes.len(), 4);
+
+        // Blob parameters of a blob parameter only fork raise the limit.
+        let mut builder = BlockBuilder::new(&mut evm, BlockEnv::default().into())
+            .unwrap()
+            .with_blob_params(BlobParams::bpo1());
+        builder.add_transaction(blob_tx(2, 6)).unwrap();
+        builder.add_transaction(blob_tx(3, 6)).unwrap();
+        builder.add_transaction(blob_tx(4, 6)).unwrap();
+        let built = builder.finish().unwrap();
+        assert_eq!(built.blob_gas_used, 12 * GAS_PER_BLOB);
+        assert_eq!(built.skipped[0].1, SkipReason::BlobGasLimitReached);
+    }
+
+    #[test]
+    fn build_block_applies_system_calls() {
+        const PARENT_HASH: B256 =
+            b256!("0x1111111111111111111111111111111111111111111111111111111111111111");
+        let mut db = db();
+        // Stores the calldata in slot zero.
+        db.insert_account_info(
+            HISTORY_STORAGE_ADDRESS,
+            AccountInfo::default().with_code(Bytecode::new_legacy(Bytes::from_static(&[
+                opcode::PUSH0,
+                opcode::CALLDATALOAD,
+                opcode::PUSH0,
+                opcode::SSTORE,
+            ]))),
+        );
+        let state = State::builder()
+            .with_database(db)
+            .with_bundle_update()
+            .build();
+        let mut evm = Context::mainnet().with_db(state).build_mainnet();
+
+        let template = BlockTemplate {
+            env: BlockEnv {
+                number: U256::from(1),
+                ..Default::default()
+            },
+            parent_hash: Some(PARENT_HASH),
+            // The beacon roots contract has no code and is skipped.
+            parent_beacon_block_root: Some(B256::ZERO),
+        };
+        let built = BlockBuilder::new(&mut evm, template)
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let history = built
+            .bundle_state
+            .account(&HISTORY_STORAGE_ADDRESS)
+            .unwrap();
+        assert_eq!(history.storage_slot(U256::ZERO), Some(PARENT_HASH.into()));
+        assert!(built.bundle_state.account(&BEACON_ROOTS_ADDRESS).is_none());
+        assert_eq!(built.withdrawal_requests, Bytes::from_static(&[0xab]));
+        assert_eq!(built.consolidation_requests, Bytes::from_static(&[0xab]));
+
+        // The block is invalid without the request contracts.
+        let state = State::builder()
+            .with_database(InMemoryDB::default())
+            .with_bundle_update()
+            .build();
+        let mut evm = Context::mainnet().with_db(state).build_mainnet();
+        let builder = BlockBuilder::new(&mut evm, BlockEnv::default().into()).unwrap();
+        assert!(matches!(builder.finish(), Err(EVMError::Custom(_))));
+    }
+}
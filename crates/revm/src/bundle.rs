@@ -0,0 +1,331 @@
+//! Atomic execution of transaction bundles.
+//!
+//! A bundle is an ordered list of transactions that is either applied as a whole or not at all.
+//! Transactions marked as [`BundleTx::revertible`] are allowed to revert or halt, any other
+//! transaction that does not succeed fails the bundle. Failed bundles are discarded from the
+//! journal so the database stays untouched.
+use crate::{
+    context::ContextTr,
+    context_interface::{
+        result::{ExecResultAndState, ExecutionResult, HaltReasonTr, TransactionIndexedError},
+        Block,
+    },
+    database_interface::Database,
+    handler::{ContextTrDbError, EvmTr},
+    primitives::{Address, I256, U256},
+    state::EvmState,
+    ExecuteCommitEvm, ExecuteEvm,
+};
+use core::fmt;
+use std::vec::Vec;
+
+/// Transaction inside a bundle.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BundleTx<TX> {
+    /// Transaction to execute.
+    pub tx: TX,
+    /// If `true` the transaction is allowed to revert or halt without failing the bundle.
+    pub revertible: bool,
+}
+
+impl<TX> BundleTx<TX> {
+    /// Creates a transaction that must succeed.
+    pub fn new(tx: TX) -> Self {
+        Self {
+            tx,
+            revertible: false,
+        }
+    }
+
+    /// Creates a transaction that is allowed to revert.
+    pub fn revertible(tx: TX) -> Self {
+        Self {
+            tx,
+            revertible: true,
+        }
+    }
+}
+
+/// Output of a successfully executed bundle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleOutput<R> {
+    /// Per-transaction execution results.
+    pub results: Vec<R>,
+    /// Beneficiary of the block the bundle was executed in.
+    pub coinbase: Address,
+    /// Coinbase balance before the bundle.
+    pub coinbase_balance_before: U256,
+    /// Coinbase balance after the bundle.
+    pub coinbase_balance_after: U256,
+}
+
+impl<R> BundleOutput<R> {
+    /// Returns the signed change of the coinbase balance.
+    ///
+    /// It is negative if the bundle moved funds out of the coinbase account.
+    pub fn coinbase_delta(&self) -> I256 {
+        I256::from_raw(
+            self.coinbase_balance_after
+                .wrapping_sub(self.coinbase_balance_before),
+        )
+    }
+
+    /// Returns the coinbase profit, saturating to zero if the balance decreased.
+    pub fn coinbase_profit(&self) -> U256 {
+        self.coinbase_balance_after
+            .saturating_sub(self.coinbase_balance_before)
+    }
+}
+
+/// Reason a bundle failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BundleError<Error, R> {
+    /// Database error while reading state before the first transaction was executed.
+    Database(Error),
+    /// Transaction was invalid or execution returned a fatal error.
+    Transaction(TransactionIndexedError<Error>),
+    /// Transaction that is not revertible reverted or halted.
+    Reverted {
+        /// Index of the transaction inside the bundle.
+        transaction_index: usize,
+        /// Result of the reverted transaction.
+        result: R,
+    },
+}
+
+impl<Error: fmt::Display, R> fmt::Display for BundleError<Error, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(error) => write!(f, "database error before bundle execution: {error}"),
+            Self::Transaction(error) => error.fmt(f),
+            Self::Reverted {
+                transaction_index, ..
+            } => write!(
+                f,
+                "transaction {transaction_index} reverted and is not revertible"
+            ),
+        }
+    }
+}
+
+impl<Error: fmt::Debug + fmt::Display, R: fmt::Debug> core::error::Error for BundleError<Error, R> {}
+
+/// Type alias for the result of [`ExecuteBundleEvm::transact_bundle`].
+pub type BundleResult<R, S, Error> =
+    Result<ExecResultAndState<BundleOutput<R>, S>, BundleError<Error, R>>;
+
+/// Execute transaction bundles atomically.
+pub trait ExecuteBundleEvm: ExecuteEvm {
+    /// Executes the bundle and finalizes the journal.
+    ///
+    /// Execution stops at the first transaction that fails. In that case the journal is
+    /// finalized and its state is dropped, so no changes leave the EVM.
+    ///
+    /// The journal is expected to be empty when the bundle starts.
+    ///
+    /// This does not use [`ExecuteEvm::transact_many`], which only stops at transaction errors
+    /// and would run the rest of the bundle after a reverted transaction. Journal checkpoints
+    /// are not used to undo a failed bundle either. Every transaction ends with
+    /// [`JournalTr::commit_tx`](crate::context_interface::JournalTr::commit_tx), which clears the
+    /// journal entries a checkpoint reverts. Finalizing the journal and dropping its state
+    /// discards the whole bundle instead.
+    fn transact_bundle(
+        &mut self,
+        txs: impl IntoIterator<Item = BundleTx<Self::Tx>>,
+    ) -> BundleResult<Self::ExecutionResult, Self::State, Self::Error>;
+}
+
+impl<EVM, H> ExecuteBundleEvm for EVM
+where
+    EVM: ExecuteEvm<ExecutionResult = ExecutionResult<H>, State = EvmState> + EvmTr,
+    EVM::Error: From<ContextTrDbError<EVM::Context>>,
+    H: HaltReasonTr,
+{
+    fn transact_bundle(
+        &mut self,
+        txs: impl IntoIterator<Item = BundleTx<Self::Tx>>,
+    ) -> BundleResult<Self::ExecutionResult, Self::State, Self::Error> {
+        let coinbase = self.ctx_ref().block().beneficiary();
+        let coinbase_balance_before = self
+            .ctx()
+            .db_mut()
+            .basic(coinbase)
+            .map_err(|error| BundleError::Database(error.into()))?
+            .map(|info| info.balance)
+            .unwrap_or_default();
+
+        let txs = txs.into_iter();
+        let mut results = Vec::with_capacity(txs.size_hint().0);
+        for (index, BundleTx { tx, revertible }) in txs.enumerate() {
+            let result = match self.transact_one(tx) {
+                Ok(result) => result,
+                Err(error) => {
+                    let _ = self.finalize();
+                    return Err(BundleError::Transaction(TransactionIndexedError::new(
+                        error, index,
+                    )));
+                }
+            };
+            if !revertible && !result.is_success() {
+                let _ = self.finalize();
+                return Err(BundleError::Reverted {
+                    transaction_index: index,
+                    result,
+                });
+            }
+            results.push(result);
+        }
+
+        let state = self.finalize();
+        let coinbase_balance_after = state
+            .get(&coinbase)
+            .map(|account| account.info.balance)
+            .unwrap_or(coinbase_balance_before);
+
+        Ok(ExecResultAndState::new(
+            BundleOutput {
+                results,
+                coinbase,
+                coinbase_balance_before,
+                coinbase_balance_after,
+            },
+            state,
+        ))
+    }
+}
+
+/// Extension of [`ExecuteBundleEvm`] that commits successful bundles to the database.
+pub trait ExecuteBundleCommitEvm: ExecuteBundleEvm + ExecuteCommitEvm {
+    /// Executes the bundle and commits it to the database if it succeeded.
+    ///
+    /// If the bundle fails the database is left untouched.
+    #[inline]
+    fn transact_bundle_commit(
+        &mut self,
+        txs: impl IntoIterator<Item = BundleTx<Self::Tx>>,
+    ) -> Result<BundleOutput<Self::ExecutionResult>, BundleError<Self::Error, Self::ExecutionResult>>
+    {
+        let output = self.transact_bundle(txs)?;
+        self.commit(output.state);
+        Ok(output.result)
+    }
+}
+
+impl<EVM> ExecuteBundleCommitEvm for EVM where EVM: ExecuteBundleEvm + ExecuteCommitEvm {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bytecode::opcode,
+        context::TxEnv,
+        database::InMemoryDB,
+        primitives::{address, Bytes},
+        state::{AccountInfo, Bytecode},
+        Context, MainBuilder, MainContext,
+    };
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const RECEIVER: Address = address!("0x2000000000000000000000000000000000000002");
+    const REVERTER: Address = address!("0x3000000000000000000000000000000000000003");
+    const COINBASE: Address = address!("0x4000000000000000000000000000000000000004");
+
+    fn db() -> InMemoryDB {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            CALLER,
+            AccountInfo {
+                balance: U256::from(10_000_000),
+                ..Default::default()
+            },
+        );
+        db.insert_account_info(
+            REVERTER,
+            AccountInfo::default().with_code(Bytecode::new_legacy(Bytes::from_static(&[
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::REVERT,
+            ]))),
+        );
+        db
+    }
+
+    fn tx(nonce: u64, to: Address) -> TxEnv {
+        TxEnv::builder()
+            .caller(CALLER)
+            .call(to)
+            .nonce(nonce)
+            .gas_price(10)
+            .gas_limit(50_000)
+            .build_fill()
+    }
+
+    #[test]
+    fn bundle_commits_with_revertible_tx() {
+        let mut evm = Context::mainnet()
+            .with_db(db())
+            .modify_block_chained(|block| block.beneficiary = COINBASE)
+            .build_mainnet();
+
+        let output = evm
+            .transact_bundle_commit([
+                BundleTx::new(tx(0, RECEIVER)),
+                BundleTx::revertible(tx(1, REVERTER)),
+            ])
+            .unwrap();
+
+        assert_eq!(output.results.len(), 2);
+        assert!(output.results[0].is_success());
+        assert!(!output.results[1].is_success());
+
+        let gas_used: u64 = output.results.iter().map(|r| r.gas_used()).sum();
+        assert_eq!(output.coinbase_profit(), U256::from(gas_used * 10));
+        assert_eq!(
+            output.coinbase_delta(),
+            I256::from_raw(U256::from(gas_used * 10))
+        );
+        assert_eq!(evm.db_mut().basic(CALLER).unwrap().unwrap().nonce, 2);
+    }
+
+    #[test]
+    fn failed_bundle_leaves_db_untouched() {
+        let mut evm = Context::mainnet()
+            .with_db(db())
+            .modify_block_chained(|block| block.beneficiary = COINBASE)
+            .build_mainnet();
+
+        let error = evm
+            .transact_bundle_commit([
+                BundleTx::new(tx(0, RECEIVER)),
+                BundleTx::new(tx(1, REVERTER)),
+            ])
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            BundleError::Reverted {
+                transaction_index: 1,
+                ..
+            }
+        ));
+
+        let error = evm
+            .transact_bundle_commit([
+                BundleTx::new(tx(0, RECEIVER)),
+                BundleTx::new(tx(5, RECEIVER)),
+            ])
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            BundleError::Transaction(TransactionIndexedError {
+                transaction_index: 1,
+                ..
+            })
+        ));
+
+        let db = evm.db_mut();
+        assert_eq!(db.basic(CALLER).unwrap().unwrap().nonce, 0);
+        assert_eq!(db.basic(COINBASE).unwrap(), None);
+    }
+}
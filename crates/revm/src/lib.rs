@@ -2,6 +2,9 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(not(feature = "std"))]
+extern crate alloc as std;
+
 // reexport dependencies
 #[doc(inline)]
 pub use bytecode;
@@ -26,8 +29,14 @@ pub use primitives;
 #[doc(inline)]
 pub use state;
 
+pub mod builder;
+pub mod bundle;
+pub mod simulate;
+
 // Export items.
 
+pub use builder::{BlockBuilder, BlockTemplate};
+pub use bundle::{ExecuteBundleCommitEvm, ExecuteBundleEvm};
 pub use context::{
     journal::{Journal, JournalEntry},
     Context,
@@ -39,3 +48,4 @@ pub use handler::{
 };
 pub use inspector::{InspectCommitEvm, InspectEvm, InspectSystemCallEvm, Inspector};
 pub use precompile::install_crypto;
+pub use simulate::SimulateEvm;
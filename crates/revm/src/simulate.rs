@@ -0,0 +1,601 @@
+//! Multi-block call simulation following `eth_simulateV1` semantics.
+//!
+//! [`SimulateEvm::simulate`] executes a sequence of [`SimBlock`]s on top of the block currently set
+//! in the EVM. Every simulated block can override block fields ([`BlockOverrides`]) and account
+//! state ([`AccountOverride`]) before its calls are executed and committed to the [`CacheDB`].
+//!
+//! Gaps between block numbers are filled with empty "phantom" blocks. Each simulated and phantom
+//! block gets a synthetic hash (see [`synthetic_block_hash`]) that is stored in the
+//! [`CacheDB`] block hash cache so the `BLOCKHASH` opcode can observe it in later blocks.
+use crate::{
+    context::{BlockEnv, CfgEnv, Context, Journal, TxEnv},
+    context_interface::{
+        block::BlobExcessGasAndPrice,
+        result::{ExecutionResult, HaltReasonTr},
+    },
+    database::CacheDB,
+    database_interface::DatabaseRef,
+    primitives::{
+        hardfork::SpecId, keccak256, Address, HashMap, StorageKey, StorageValue, B256, U256,
+    },
+    state::Bytecode,
+    ExecuteCommitEvm,
+};
+use core::{fmt, ops::DerefMut};
+use std::vec::Vec;
+
+/// Maximum number of blocks (including phantom blocks) a single simulation can span.
+pub const MAX_SIMULATED_BLOCKS: u64 = 256;
+
+/// Timestamp increment used when a simulated block does not override its timestamp.
+pub const SIMULATED_BLOCK_TIME_INCREMENT: u64 = 12;
+
+/// Block fields that can be overridden for a simulated block.
+///
+/// Fields that are not set are inherited from the parent block, with the exception of
+/// `number` and `time` that are incremented.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockOverrides {
+    /// Block number.
+    pub number: Option<U256>,
+    /// Block timestamp.
+    pub time: Option<U256>,
+    /// Block gas limit.
+    pub gas_limit: Option<u64>,
+    /// Block beneficiary.
+    pub fee_recipient: Option<Address>,
+    /// Block prevrandao.
+    pub prev_randao: Option<B256>,
+    /// Block base fee.
+    pub base_fee: Option<u64>,
+    /// Blob base fee. Excess blob gas is kept from the parent block.
+    pub blob_base_fee: Option<u128>,
+}
+
+/// Account fields that can be overridden before a simulated block is executed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountOverride {
+    /// Account balance.
+    pub balance: Option<U256>,
+    /// Account nonce.
+    pub nonce: Option<u64>,
+    /// Account code.
+    pub code: Option<Bytecode>,
+    /// Replaces the whole account storage. Mutually exclusive with `state_diff`.
+    pub state: Option<HashMap<StorageKey, StorageValue>>,
+    /// Overrides the given storage slots. Mutually exclusive with `state`.
+    pub state_diff: Option<HashMap<StorageKey, StorageValue>>,
+}
+
+/// One block of calls to simulate.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimBlock {
+    /// Overrides applied to the block environment.
+    pub block_overrides: BlockOverrides,
+    /// Overrides applied to the state before the calls are executed.
+    pub state_overrides: HashMap<Address, AccountOverride>,
+    /// Calls executed in order.
+    pub calls: Vec<TxEnv>,
+}
+
+/// Result of a simulated block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulatedBlock<R> {
+    /// Block environment the calls were executed in.
+    pub block: BlockEnv,
+    /// Synthetic hash of the block.
+    pub hash: B256,
+    /// Sum of gas used by all calls.
+    pub gas_used: u64,
+    /// Per-call execution results, including logs.
+    pub calls: Vec<R>,
+}
+
+/// Errors that can happen during simulation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulateError<EvmError, DbError> {
+    /// More than [`MAX_SIMULATED_BLOCKS`] blocks would be simulated.
+    TooManyBlocks,
+    /// Block number is not greater than the number of the parent block.
+    BlockNumberNotIncreasing {
+        /// Index of the simulated block.
+        block_index: usize,
+    },
+    /// Block timestamp is not greater than the timestamp of the parent block.
+    TimestampNotIncreasing {
+        /// Index of the simulated block.
+        block_index: usize,
+    },
+    /// Both `state` and `state_diff` were set for an account override.
+    StateAndStateDiff {
+        /// Index of the simulated block.
+        block_index: usize,
+        /// Overridden account.
+        address: Address,
+    },
+    /// Call gas limit exceeds the gas left in the block.
+    BlockGasLimitReached {
+        /// Index of the simulated block.
+        block_index: usize,
+        /// Index of the call inside the block.
+        call_index: usize,
+    },
+    /// Error while applying state overrides.
+    Database(DbError),
+    /// Call failed validation or execution.
+    Call {
+        /// Index of the simulated block.
+        block_index: usize,
+        /// Index of the call inside the block.
+        call_index: usize,
+        /// Error returned by the EVM.
+        error: EvmError,
+    },
+}
+
+impl<EvmError: fmt::Display, DbError: fmt::Display> fmt::Display
+    for SimulateError<EvmError, DbError>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyBlocks => write!(f, "too many blocks, max is {MAX_SIMULATED_BLOCKS}"),
+            Self::BlockNumberNotIncreasing { block_index } => {
+                write!(f, "block {block_index}: block number is not increasing")
+            }
+            Self::TimestampNotIncreasing { block_index } => {
+                write!(f, "block {block_index}: timestamp is not increasing")
+            }
+            Self::StateAndStateDiff {
+                block_index,
+                address,
+            } => write!(
+                f,
+                "block {block_index}: both state and stateDiff set for {address}"
+            ),
+            Self::BlockGasLimitReached {
+                block_index,
+                call_index,
+            } => write!(
+                f,
+                "block {block_index} call {call_index}: block gas limit reached"
+            ),
+            Self::Database(error) => write!(f, "database error: {error}"),
+            Self::Call {
+                block_index,
+                call_index,
+                error,
+            } => write!(f, "block {block_index} call {call_index}: {error}"),
+        }
+    }
+}
+
+impl<EvmError, DbError> core::error::Error for SimulateError<EvmError, DbError>
+where
+    EvmError: fmt::Debug + fmt::Display,
+    DbError: fmt::Debug + fmt::Display,
+{
+}
+
+/// Returns the synthetic hash of a simulated block with the given number.
+///
+/// Simulated blocks are never sealed, so their hash is `keccak256(rlp(number))`,
+/// the same value `eth_simulateV1` specifies for phantom blocks.
+pub fn synthetic_block_hash(number: u64) -> B256 {
+    let bytes = number.to_be_bytes();
+    let trimmed = &bytes[bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len())..];
+    let mut rlp = [0u8; 9];
+    let len = match trimmed {
+        [] => {
+            rlp[0] = 0x80;
+            1
+        }
+        [byte] if *byte < 0x80 => {
+            rlp[0] = *byte;
+            1
+        }
+        _ => {
+            rlp[0] = 0x80 + trimmed.len() as u8;
+            rlp[1..=trimmed.len()].copy_from_slice(trimmed);
+            trimmed.len() + 1
+        }
+    };
+    keccak256(&rlp[..len])
+}
+
+/// Simulation of multiple blocks of calls over a [`CacheDB`].
+pub trait SimulateEvm: ExecuteCommitEvm {
+    /// Database error returned while applying state overrides.
+    type DbError;
+
+    /// Simulates `blocks` on top of the currently set block.
+    ///
+    /// If `validation` is `true` calls are fully validated, including nonces and fees.
+    /// Otherwise nonce, base fee and EIP-3607 checks are disabled through the [`CfgEnv`]
+    /// flags that are enabled at compile time, and the base fee defaults to zero.
+    ///
+    /// All changes are committed to the [`CacheDB`]; wrap it with [`CacheDB::nest`] to be able to
+    /// discard them. Block and configuration are restored after the simulation.
+    fn simulate(
+        &mut self,
+        blocks: Vec<SimBlock>,
+        validation: bool,
+    ) -> Result<Vec<SimulatedBlock<Self::ExecutionResult>>, SimulateError<Self::Error, Self::DbError>>;
+}
+
+impl<EVM, DB, SPEC, CHAIN, H> SimulateEvm for EVM
+where
+    EVM: ExecuteCommitEvm<Tx = TxEnv, Block = BlockEnv, ExecutionResult = ExecutionResult<H>>
+        + DerefMut<
+            Target = Context<
+                BlockEnv,
+                TxEnv,
+                CfgEnv<SPEC>,
+                CacheDB<DB>,
+                Journal<CacheDB<DB>>,
+                CHAIN,
+            >,
+        >,
+    DB: DatabaseRef,
+    SPEC: Into<SpecId> + Copy,
+    H: HaltReasonTr,
+{
+    type DbError = DB::Error;
+
+    fn simulate(
+        &mut self,
+        blocks: Vec<SimBlock>,
+        validation: bool,
+    ) -> Result<Vec<SimulatedBlock<Self::ExecutionResult>>, SimulateError<Self::Error, DB::Error>>
+    {
+        let parent = self.block.clone();
+        let cfg = self.cfg.clone();
+
+        set_validation(&mut self.cfg, validation);
+        let result = simulate_blocks(self, parent.clone(), blocks, validation);
+
+        self.cfg = cfg;
+        self.set_block(parent);
+        result
+    }
+}
+
+/// Relaxes the configuration for non-validating simulation.
+fn set_validation<SPEC>(cfg: &mut CfgEnv<SPEC>, validation: bool) {
+    if validation {
+        return;
+    }
+    cfg.disable_nonce_check = true;
+    #[cfg(feature = "optional_no_base_fee")]
+    {
+        cfg.disable_base_fee = true;
+    }
+    #[cfg(feature = "optional_eip3607")]
+    {
+        cfg.disable_eip3607 = true;
+    }
+}
+
+fn simulate_blocks<EVM, DB, SPEC, CHAIN, H>(
+    evm: &mut EVM,
+    mut parent: BlockEnv,
+    blocks: Vec<SimBlock>,
+    validation: bool,
+) -> Result<Vec<SimulatedBlock<ExecutionResult<H>>>, SimulateError<EVM::Error, DB::Error>>
+where
+    EVM: ExecuteCommitEvm<Tx = TxEnv, Block = BlockEnv, ExecutionResult = ExecutionResult<H>>
+        + DerefMut<
+            Target = Context<
+                BlockEnv,
+                TxEnv,
+                CfgEnv<SPEC>,
+                CacheDB<DB>,
+                Journal<CacheDB<DB>>,
+                CHAIN,
+            >,
+        >,
+    DB: DatabaseRef,
+    H: HaltReasonTr,
+{
+    let first_number = parent.number;
+    let mut output = Vec::with_capacity(blocks.len());
+
+    for (block_index, sim_block) in blocks.into_iter().enumerate() {
+        let SimBlock {
+            block_overrides,
+            state_overrides,
+            calls,
+        } = sim_block;
+
+        let number = block_overrides
+            .number
+            .unwrap_or(parent.number + U256::from(1));
+        if number <= parent.number {
+            return Err(SimulateError::BlockNumberNotIncreasing { block_index });
+        }
+        if number - first_number > U256::from(MAX_SIMULATED_BLOCKS) {
+            return Err(SimulateError::TooManyBlocks);
+        }
+
+        // Fill the gap with empty phantom blocks.
+        while parent.number + U256::from(1) < number {
+            parent.number += U256::from(1);
+            parent.timestamp += U256::from(SIMULATED_BLOCK_TIME_INCREMENT);
+            let hash = synthetic_block_hash(parent.number.saturating_to());
+            evm.journaled_state
+                .database
+                .cache
+                .block_hashes
+                .insert(parent.number, hash);
+        }
+
+        let block = sim_block_env(&parent, number, block_overrides, validation)
+            .ok_or(SimulateError::TimestampNotIncreasing { block_index })?;
+
+        apply_state_overrides(&mut evm.journaled_state.database, state_overrides)
+            .map_err(|error| error.into_simulate_error(block_index))?;
+
+        evm.set_block(block.clone());
+        let mut gas_used = 0u64;
+        let mut results = Vec::with_capacity(calls.len());
+        for (call_index, tx) in calls.into_iter().enumerate() {
+            if tx.gas_limit > block.gas_limit.saturating_sub(gas_used) {
+                return Err(SimulateError::BlockGasLimitReached {
+                    block_index,
+                    call_index,
+                });
+            }
+            let result = evm
+                .transact_commit(tx)
+                .map_err(|error| SimulateError::Call {
+                    block_index,
+                    call_index,
+                    error,
+                })?;
+            gas_used += result.gas_used();
+            results.push(result);
+        }
+
+        let hash = synthetic_block_hash(block.number.saturating_to());
+        evm.journaled_state
+            .database
+            .cache
+            .block_hashes
+            .insert(block.number, hash);
+
+        parent = block.clone();
+        output.push(SimulatedBlock {
+            block,
+            hash,
+            gas_used,
+            calls: results,
+        });
+    }
+
+    Ok(output)
+}
+
+/// Builds the environment of a simulated block from its parent and overrides.
+///
+/// Returns `None` if the timestamp is not increasing.
+fn sim_block_env(
+    parent: &BlockEnv,
+    number: U256,
+    overrides: BlockOverrides,
+    validation: bool,
+) -> Option<BlockEnv> {
+    let timestamp = overrides
+        .time
+        .unwrap_or(parent.timestamp + U256::from(SIMULATED_BLOCK_TIME_INCREMENT));
+    if timestamp <= parent.timestamp {
+        return None;
+    }
+
+    let mut block = parent.clone();
+    block.number = number;
+    block.timestamp = timestamp;
+    if let Some(gas_limit) = overrides.gas_limit {
+        block.gas_limit = gas_limit;
+    }
+    if let Some(beneficiary) = overrides.fee_recipient {
+        block.beneficiary = beneficiary;
+    }
+    if let Some(prevrandao) = overrides.prev_randao {
+        block.prevrandao = Some(prevrandao);
+    }
+    block.basefee = match overrides.base_fee {
+        Some(basefee) => basefee,
+        None if validation => parent.basefee,
+        None => 0,
+    };
+    if let Some(blob_gasprice) = overrides.blob_base_fee {
+        let excess_blob_gas = parent
+            .blob_excess_gas_and_price
+            .map(|blob| blob.excess_blob_gas)
+            .unwrap_or_default();
+        block.blob_excess_gas_and_price = Some(BlobExcessGasAndPrice {
+            excess_blob_gas,
+            blob_gasprice,
+        });
+    }
+    Some(block)
+}
+
+/// Error of [`apply_state_overrides`].
+enum OverrideError<DbError> {
+    StateAndStateDiff(Address),
+    Database(DbError),
+}
+
+impl<DbError> OverrideError<DbError> {
+    fn into_simulate_error<EvmError>(self, block_index: usize) -> SimulateError<EvmError, DbError> {
+        match self {
+            Self::StateAndStateDiff(address) => SimulateError::StateAndStateDiff {
+                block_index,
+                address,
+            },
+            Self::Database(error) => SimulateError::Database(error),
+        }
+    }
+}
+
+/// Applies account overrides to the [`CacheDB`].
+fn apply_state_overrides<DB: DatabaseRef>(
+    db: &mut CacheDB<DB>,
+    overrides: HashMap<Address, AccountOverride>,
+) -> Result<(), OverrideError<DB::Error>> {
+    for (address, account) in overrides {
+        if account.state.is_some() && account.state_diff.is_some() {
+            return Err(OverrideError::StateAndStateDiff(address));
+        }
+
+        let mut info = db
+            .basic_ref(address)
+            .map_err(OverrideError::Database)?
+            .unwrap_or_default();
+        if let Some(balance) = account.balance {
+            info.balance = balance;
+        }
+        if let Some(nonce) = account.nonce {
+            info.nonce = nonce;
+        }
+        if let Some(code) = account.code {
+            info.code_hash = code.hash_slow();
+            info.code = Some(code);
+        }
+        db.insert_account_info(address, info);
+
+        if let Some(state) = account.state {
+            db.replace_account_storage(address, state)
+                .map_err(OverrideError::Database)?;
+        }
+        for (slot, value) in account.state_diff.into_iter().flatten() {
+            db.insert_account_storage(address, slot, value)
+                .map_err(OverrideError::Database)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::ContextTr, database::InMemoryDB, database_interface::Database,
+        primitives::address, MainBuilder, MainContext,
+    };
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const TARGET: Address = address!("0x2000000000000000000000000000000000000002");
+
+    fn transfer(nonce: u64, value: u64) -> TxEnv {
+        TxEnv::builder()
+            .caller(CALLER)
+            .call(TARGET)
+            .nonce(nonce)
+            .value(U256::from(value))
+            .gas_limit(21_000)
+            .build_fill()
+    }
+
+    #[test]
+    fn synthetic_hash_uses_rlp_encoding() {
+        assert_eq!(synthetic_block_hash(0), keccak256([0x80]));
+        assert_eq!(synthetic_block_hash(1), keccak256([0x01]));
+        assert_eq!(synthetic_block_hash(0x80), keccak256([0x81, 0x80]));
+        assert_eq!(synthetic_block_hash(0x0400), keccak256([0x82, 0x04, 0x00]));
+    }
+
+    #[test]
+    fn simulate_blocks_with_gap_and_overrides() {
+        let mut evm = Context::mainnet()
+            .with_db(InMemoryDB::default())
+            .modify_block_chained(|block| block.number = U256::from(10))
+            .build_mainnet();
+
+        let blocks = vec![
+            SimBlock {
+                state_overrides: HashMap::from_iter([(
+                    CALLER,
+                    AccountOverride {
+                        balance: Some(U256::from(1_000)),
+                        ..Default::default()
+                    },
+                )]),
+                calls: vec![transfer(0, 100)],
+                ..Default::default()
+            },
+            SimBlock {
+                block_overrides: BlockOverrides {
+                    number: Some(U256::from(14)),
+                    ..Default::default()
+                },
+                calls: vec![transfer(1, 200)],
+                ..Default::default()
+            },
+        ];
+
+        let output = evm.simulate(blocks, true).unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].block.number, U256::from(11));
+        assert_eq!(output[1].block.number, U256::from(14));
+        assert_eq!(output[1].gas_used, 21_000);
+        assert!(output[1].calls[0].is_success());
+
+        // simulated and phantom blocks are visible to `BLOCKHASH`.
+        let db = evm.db_mut();
+        for number in 11..=14 {
+            assert_eq!(db.block_hash(number), Ok(synthetic_block_hash(number)));
+        }
+        assert_eq!(db.basic(TARGET).unwrap().unwrap().balance, U256::from(300));
+
+        // block is restored after simulation.
+        assert_eq!(evm.block.number, U256::from(10));
+    }
+
+    #[test]
+    fn simulate_validation_enforces_nonce() {
+        let mut evm = Context::mainnet()
+            .with_db(InMemoryDB::default())
+            .build_mainnet();
+
+        let blocks = vec![SimBlock {
+            calls: vec![transfer(5, 0)],
+            ..Default::default()
+        }];
+
+        assert!(matches!(
+            evm.simulate(blocks.clone(), true),
+            Err(SimulateError::Call {
+                block_index: 0,
+                call_index: 0,
+                ..
+            })
+        ));
+        assert!(!evm.cfg.disable_nonce_check);
+
+        let output = evm.simulate(blocks, false).unwrap();
+        assert!(output[0].calls[0].is_success());
+    }
+
+    #[test]
+    fn simulate_rejects_decreasing_block_number() {
+        let mut evm = Context::mainnet()
+            .with_db(InMemoryDB::default())
+            .modify_block_chained(|block| block.number = U256::from(10))
+            .build_mainnet();
+
+        let blocks = vec![SimBlock {
+            block_overrides: BlockOverrides {
+                number: Some(U256::from(10)),
+                ..Default::default()
+            },
+            calls: vec![transfer(0, 0)],
+            ..Default::default()
+        }];
+
+        assert!(matches!(
+            evm.simulate(blocks, false),
+            Err(SimulateError::BlockNumberNotIncreasing { block_index: 0 })
+        ));
+    }
+}
@@ -0,0 +1,7 @@
+//! Rollup API types.
+
+pub mod builder;
+pub mod exec;
+
+pub use builder::RollupBuilder;
+pub use exec::{RollupContextTr, RollupError};
@@ -0,0 +1,53 @@
+//! Rollup builder trait [`RollupBuilder`] used to build [`RollupEvm`].
+use crate::{evm::RollupEvm, FeeVaults, L1DataFee, RollupTxTr};
+use revm::{
+    context::Cfg,
+    context_interface::{Block, JournalTr},
+    handler::{instructions::EthInstructions, EthPrecompiles},
+    interpreter::interpreter::EthInterpreter,
+    state::EvmState,
+    Context, Database,
+};
+
+/// Type alias for default RollupEvm
+pub type DefaultRollupEvm<CTX, INSP = ()> =
+    RollupEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, EthPrecompiles>;
+
+/// Trait that allows for a [`RollupEvm`] to be built.
+pub trait RollupBuilder: Sized {
+    /// Type of the context.
+    type Context;
+
+    /// Build the rollup EVM.
+    fn build_rollup(self) -> DefaultRollupEvm<Self::Context>;
+
+    /// Build the rollup EVM with an inspector.
+    fn build_rollup_with_inspector<INSP>(
+        self,
+        inspector: INSP,
+    ) -> DefaultRollupEvm<Self::Context, INSP>;
+}
+
+impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN> RollupBuilder
+    for Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN>
+where
+    BLOCK: Block,
+    TX: RollupTxTr,
+    CFG: Cfg,
+    DB: Database,
+    JOURNAL: JournalTr<Database = DB, State = EvmState>,
+    CHAIN: L1DataFee + FeeVaults,
+{
+    type Context = Self;
+
+    fn build_rollup(self) -> DefaultRollupEvm<Self::Context> {
+        RollupEvm::new(self, ())
+    }
+
+    fn build_rollup_with_inspector<INSP>(
+        self,
+        inspector: INSP,
+    ) -> DefaultRollupEvm<Self::Context, INSP> {
+        RollupEvm::new(self, inspector)
+    }
+}
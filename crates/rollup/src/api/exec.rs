@@ -0,0 +1,174 @@
+//! Implementation of the [`ExecuteEvm`] trait for the [`RollupEvm`].
+use crate::{
+    evm::RollupEvm, handler::RollupHandler, FeeVaults, L1DataFee, RollupHaltReason,
+    RollupTransactionError, RollupTxTr,
+};
+use revm::{
+    context::{result::ExecResultAndState, ContextSetters},
+    context_interface::{
+        result::{EVMError, ExecutionResult},
+        Cfg, ContextTr, Database, JournalTr,
+    },
+    handler::{
+        instructions::EthInstructions, system_call::SystemCallEvm, EthFrame, Handler,
+        PrecompileProvider, SystemCallTx,
+    },
+    inspector::{
+        InspectCommitEvm, InspectEvm, InspectSystemCallEvm, Inspector, InspectorHandler, JournalExt,
+    },
+    interpreter::{interpreter::EthInterpreter, InterpreterResult},
+    primitives::{Address, Bytes},
+    state::EvmState,
+    DatabaseCommit, ExecuteCommitEvm, ExecuteEvm,
+};
+
+/// Context of a rollup.
+///
+/// The chain context prices the L1 data of the transactions and distributes the fees. Its
+/// hardfork is derived from the spec of the configuration.
+pub trait RollupContextTr:
+    ContextTr<Journal: JournalTr<State = EvmState>, Tx: RollupTxTr, Chain: L1DataFee + FeeVaults>
+{
+    /// Returns the hardfork of the rollup.
+    fn rollup_spec(&self) -> <Self::Chain as L1DataFee>::Spec;
+}
+
+impl<T> RollupContextTr for T
+where
+    T: ContextTr<
+        Journal: JournalTr<State = EvmState>,
+        Tx: RollupTxTr,
+        Chain: L1DataFee + FeeVaults,
+    >,
+    <T::Cfg as Cfg>::Spec: Into<<T::Chain as L1DataFee>::Spec>,
+{
+    fn rollup_spec(&self) -> <Self::Chain as L1DataFee>::Spec {
+        self.cfg().spec().into()
+    }
+}
+
+/// Type alias for the error type of the RollupEvm.
+pub type RollupError<CTX> =
+    EVMError<<<CTX as ContextTr>::Db as Database>::Error, RollupTransactionError>;
+
+impl<CTX, INSP, PRECOMPILE> ExecuteEvm
+    for RollupEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: RollupContextTr + ContextSetters,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    type Tx = <CTX as ContextTr>::Tx;
+    type Block = <CTX as ContextTr>::Block;
+    type State = EvmState;
+    type Error = RollupError<CTX>;
+    type ExecutionResult = ExecutionResult<RollupHaltReason>;
+
+    fn set_block(&mut self, block: Self::Block) {
+        self.0.ctx.set_block(block);
+    }
+
+    fn transact_one(&mut self, tx: Self::Tx) -> Result<Self::ExecutionResult, Self::Error> {
+        self.0.ctx.set_tx(tx);
+        let mut h = RollupHandler::<_, _, EthFrame<EthInterpreter>>::new();
+        h.run(self)
+    }
+
+    fn finalize(&mut self) -> Self::State {
+        self.0.ctx.journal_mut().finalize()
+    }
+
+    fn replay(
+        &mut self,
+    ) -> Result<ExecResultAndState<Self::ExecutionResult, Self::State>, Self::Error> {
+        let mut h = RollupHandler::<_, _, EthFrame<EthInterpreter>>::new();
+        h.run(self).map(|result| {
+            let state = self.finalize();
+            ExecResultAndState::new(result, state)
+        })
+    }
+}
+
+impl<CTX, INSP, PRECOMPILE> ExecuteCommitEvm
+    for RollupEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: RollupContextTr<Db: DatabaseCommit> + ContextSetters,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    fn commit(&mut self, state: Self::State) {
+        self.0.ctx.db_mut().commit(state);
+    }
+}
+
+impl<CTX, INSP, PRECOMPILE> InspectEvm
+    for RollupEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: RollupContextTr<Journal: JournalExt> + ContextSetters,
+    INSP: Inspector<CTX, EthInterpreter>,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    type Inspector = INSP;
+
+    fn set_inspector(&mut self, inspector: Self::Inspector) {
+        self.0.inspector = inspector;
+    }
+
+    fn inspect_one_tx(&mut self, tx: Self::Tx) -> Result<Self::ExecutionResult, Self::Error> {
+        self.0.ctx.set_tx(tx);
+        let mut h = RollupHandler::<_, _, EthFrame<EthInterpreter>>::new();
+        h.inspect_run(self)
+    }
+}
+
+impl<CTX, INSP, PRECOMPILE> InspectCommitEvm
+    for RollupEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: RollupContextTr<Journal: JournalExt, Db: DatabaseCommit> + ContextSetters,
+    INSP: Inspector<CTX, EthInterpreter>,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+}
+
+impl<CTX, INSP, PRECOMPILE> SystemCallEvm
+    for RollupEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: RollupContextTr<Tx: SystemCallTx> + ContextSetters,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    fn system_call_one_with_caller(
+        &mut self,
+        caller: Address,
+        system_contract_address: Address,
+        data: Bytes,
+    ) -> Result<Self::ExecutionResult, Self::Error> {
+        self.0.ctx.set_tx(CTX::Tx::new_system_tx_with_caller(
+            caller,
+            system_contract_address,
+            data,
+        ));
+        let mut h = RollupHandler::<_, _, EthFrame<EthInterpreter>>::new();
+        h.run_system_call(self)
+    }
+}
+
+impl<CTX, INSP, PRECOMPILE> InspectSystemCallEvm
+    for RollupEvm<CTX, INSP, EthInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: RollupContextTr<Journal: JournalExt, Tx: SystemCallTx> + ContextSetters,
+    INSP: Inspector<CTX, EthInterpreter>,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    fn inspect_one_system_call_with_caller(
+        &mut self,
+        caller: Address,
+        system_contract_address: Address,
+        data: Bytes,
+    ) -> Result<Self::ExecutionResult, Self::Error> {
+        self.0.ctx.set_tx(CTX::Tx::new_system_tx_with_caller(
+            caller,
+            system_contract_address,
+            data,
+        ));
+        let mut h = RollupHandler::<_, _, EthFrame<EthInterpreter>>::new();
+        h.inspect_run_system_call(self)
+    }
+}
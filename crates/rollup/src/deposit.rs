@@ -0,0 +1,72 @@
+//! Helpers for the state changes of deposit transactions.
+//!
+//! Deposits are pre-paid on L1. They skip the fee checks, mint value to the caller and, if they
+//! fail, still bump the nonce of the caller and persist the mint.
+use crate::RollupTxTr;
+use revm::{
+    context::journaled_state::{account::JournaledAccountTr, JournalCheckpoint},
+    context_interface::{Block, Cfg, ContextTr, JournalTr, Transaction},
+    primitives::{Address, U256},
+    Database,
+};
+
+/// Mints the value of the deposit to the caller and deducts the effective balance spending.
+///
+/// The nonce of the caller is bumped if the deposit is a call.
+pub fn deduct_deposit_caller<CTX: ContextTr<Tx: RollupTxTr>>(
+    ctx: &mut CTX,
+) -> Result<(), <CTX::Db as Database>::Error> {
+    let (block, tx, cfg, journal, _, _) = ctx.all_mut();
+    let basefee = block.basefee() as u128;
+    let blob_price = block.blob_gasprice().unwrap_or_default();
+
+    let mut caller = journal.load_account_with_code_mut(tx.caller())?.data;
+
+    // deposit skips max fee check and just deducts the effective balance spending.
+    let effective_balance_spending = tx
+        .effective_balance_spending(basefee, blob_price)
+        .expect("Deposit transaction effective balance spending overflow")
+        - tx.value();
+
+    // Mint value should be added first before subtracting the effective balance spending.
+    let mut new_balance = caller
+        .balance()
+        .saturating_add(U256::from(tx.mint().unwrap_or_default()))
+        .saturating_sub(effective_balance_spending);
+
+    if cfg.is_balance_check_disabled() {
+        // Make sure the caller's balance is at least the value of the transaction.
+        // this is not consensus critical, and it is used in testing.
+        new_balance = new_balance.max(tx.value());
+    }
+
+    // set the new balance and bump the nonce if it is a call
+    caller.set_balance(new_balance);
+    if tx.kind().is_call() {
+        caller.bump_nonce();
+    }
+
+    Ok(())
+}
+
+/// Discards all changes of the failed deposit, then bumps the nonce of the caller and mints the
+/// value of the deposit.
+///
+/// The changes are committed, deposits always persist the mint, even if they fail.
+pub fn persist_failed_deposit<JOURNAL: JournalTr>(
+    journal: &mut JOURNAL,
+    caller: Address,
+    mint: Option<u128>,
+) -> Result<(), <JOURNAL::Database as Database>::Error> {
+    // Default JournalCheckpoint is the first checkpoint and will wipe all changes.
+    journal.checkpoint_revert(JournalCheckpoint::default());
+
+    let mut acc = journal.load_account_mut(caller)?;
+    acc.bump_nonce();
+    acc.incr_balance(U256::from(mint.unwrap_or_default()));
+
+    drop(acc); // Drop acc to avoid borrow checker issues.
+
+    journal.commit_tx();
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Contains the [`RollupTransactionError`] type and the [`IsTxError`] trait.
+use core::fmt::Display;
+use revm::context_interface::{
+    result::{EVMError, InvalidTransaction},
+    transaction::TransactionError,
+};
+
+/// Trait to check if the error is a transaction error.
+///
+/// Used in the `catch_error` handler to catch deposit transactions that were halted.
+pub trait IsTxError {
+    /// Check if the error is a transaction error.
+    fn is_tx_error(&self) -> bool;
+}
+
+impl<DB, TX> IsTxError for EVMError<DB, TX> {
+    fn is_tx_error(&self) -> bool {
+        matches!(self, EVMError::Transaction(_))
+    }
+}
+
+/// Rollup transaction validation error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RollupTransactionError {
+    /// Base transaction error.
+    Base(InvalidTransaction),
+    /// Deposits can't be system transactions.
+    DepositSystemTx,
+    /// The deposit halted.
+    ///
+    /// This error is internal, a deposit that fails must still be included in the block, so the
+    /// handler turns it into a [`RollupHaltReason::FailedDeposit`][crate::RollupHaltReason::FailedDeposit]
+    /// halt that only persists the mint and the nonce bump of the caller.
+    HaltedDeposit,
+    /// Missing enveloped transaction bytes for non-deposit transaction.
+    ///
+    /// The enveloped transaction is needed to compute the L1 data fee.
+    MissingEnvelopedTx,
+}
+
+impl TransactionError for RollupTransactionError {}
+
+impl Display for RollupTransactionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Base(error) => error.fmt(f),
+            Self::DepositSystemTx => {
+                write!(f, "deposit system transactions are not supported")
+            }
+            Self::HaltedDeposit => {
+                write!(
+                    f,
+                    "deposit transaction halted; error will be bubbled up to main return handler"
+                )
+            }
+            Self::MissingEnvelopedTx => {
+                write!(
+                    f,
+                    "missing enveloped transaction bytes for non-deposit transaction"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for RollupTransactionError {}
+
+impl From<InvalidTransaction> for RollupTransactionError {
+    fn from(value: InvalidTransaction) -> Self {
+        Self::Base(value)
+    }
+}
+
+impl<DBError, TXE: From<RollupTransactionError>> From<RollupTransactionError>
+    for EVMError<DBError, TXE>
+{
+    fn from(value: RollupTransactionError) -> Self {
+        Self::Transaction(TXE::from(value))
+    }
+}
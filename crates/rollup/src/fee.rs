@@ -0,0 +1,88 @@
+//! Contains the [`L1DataFee`] and [`FeeVaults`] traits.
+//!
+//! Rollup transactions pay for the L1 data they occupy on top of the L2 execution gas. The
+//! chain context of the EVM prices this data and decides where the collected fees go.
+use crate::RollupSpec;
+use revm::{
+    context_interface::{FeeKind, FeePolicy, FeeRecipient, FeeSplit},
+    primitives::{Address, U256},
+    Database,
+};
+
+pub use revm::context_interface::TxFees;
+
+/// L1 data fee of the rollup.
+///
+/// Implemented by the chain context of the EVM, which holds the L1 parameters of the block.
+pub trait L1DataFee {
+    /// Hardfork of the rollup.
+    type Spec: RollupSpec;
+
+    /// Loads the L1 parameters of the given L2 block.
+    ///
+    /// Called before every non-deposit transaction, implementations should only reload the
+    /// parameters if they are not for the given block.
+    fn load<DB: Database>(
+        &mut self,
+        db: &mut DB,
+        l2_block: U256,
+        spec: Self::Spec,
+    ) -> Result<(), DB::Error>;
+
+    /// Returns the L1 data fee of the enveloped transaction.
+    fn l1_data_fee(&mut self, enveloped_tx: &[u8], spec: Self::Spec) -> U256;
+
+    /// Returns the operator fee for the given gas.
+    ///
+    /// The operator fee is charged for the gas limit of the transaction and the part of the
+    /// unused gas is refunded.
+    fn operator_fee(&self, enveloped_tx: &[u8], gas: u64, spec: Self::Spec) -> U256 {
+        let _ = (enveloped_tx, gas, spec);
+        U256::ZERO
+    }
+
+    /// Clears the values cached for the current transaction.
+    fn clear_tx_cache(&mut self) {}
+}
+
+/// Addresses of the vaults collecting the fees of the rollup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeVaultAddresses {
+    /// Vault of the base fee.
+    pub base_fee_vault: Address,
+    /// Vault of the L1 data fee.
+    pub l1_fee_vault: Address,
+    /// Vault of the operator fee.
+    pub operator_fee_vault: Address,
+}
+
+/// Distribution of the [`TxFees`] of a transaction.
+///
+/// Used when the beneficiary is rewarded for non-deposit transactions, unless the configuration
+/// sets its own [`FeePolicy`].
+pub trait FeeVaults {
+    /// Returns the addresses of the fee vaults.
+    fn fee_vaults(&self) -> FeeVaultAddresses;
+
+    /// Returns the fee policy of the rollup.
+    ///
+    /// The base fee, the L1 data fee and the operator fee are paid into their vaults, the
+    /// priority fee goes to the beneficiary and the blob fee is burned.
+    fn fee_policy(&self) -> FeePolicy {
+        let vaults = self.fee_vaults();
+        FeePolicy::mainnet()
+            .with_split(
+                FeeKind::BaseFee,
+                FeeSplit::to(FeeRecipient::Address(vaults.base_fee_vault)),
+            )
+            .with_split(
+                FeeKind::L1DataFee,
+                FeeSplit::to(FeeRecipient::Address(vaults.l1_fee_vault)),
+            )
+            .with_split(
+                FeeKind::OperatorFee,
+                FeeSplit::to(FeeRecipient::Address(vaults.operator_fee_vault)),
+            )
+    }
+}
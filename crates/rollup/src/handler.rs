@@ -0,0 +1,423 @@
+//! Handler of rollup transactions.
+use crate::{
+    deposit::{deduct_deposit_caller, persist_failed_deposit},
+    FeeVaults, IsTxError, L1DataFee, RollupContextTr, RollupHaltReason, RollupTransactionError,
+    RollupTxTr, TxFees,
+};
+use revm::{
+    context::{
+        journaled_state::account::JournaledAccountTr, result::InvalidTransaction, LocalContextTr,
+    },
+    context_interface::{
+        context::ContextError,
+        result::{ExecutionResult, FromStringError},
+        Cfg, ContextTr, JournalTr, Transaction,
+    },
+    handler::{
+        evm::FrameTr,
+        handler::EvmTrError,
+        post_execution::{self, reimburse_caller},
+        pre_execution::{calculate_caller_fee, validate_account_nonce_and_code_with_components},
+        EthFrame, EvmTr, FrameResult, Handler, MainnetHandler,
+    },
+    inspector::{Inspector, InspectorEvmTr, InspectorHandler},
+    interpreter::{interpreter::EthInterpreter, interpreter_action::FrameInit},
+    primitives::U256,
+};
+use std::boxed::Box;
+
+/// Rollup handler extends the [`Handler`] with deposits, the L1 data fee, the operator fee and
+/// the fee vaults of a rollup.
+///
+/// Deposits are not validated, mint value to the caller and report their gas as normal. A
+/// deposit that fails is still included as a [`RollupHaltReason::FailedDeposit`] halt.
+#[derive(Debug, Clone)]
+pub struct RollupHandler<EVM, ERROR, FRAME> {
+    /// Mainnet handler, runs the hooks that rollup transactions share with mainnet.
+    pub mainnet: MainnetHandler<EVM, ERROR, FRAME>,
+}
+
+impl<EVM, ERROR, FRAME> RollupHandler<EVM, ERROR, FRAME> {
+    /// Create a new rollup handler.
+    pub fn new() -> Self {
+        Self {
+            mainnet: MainnetHandler::default(),
+        }
+    }
+}
+
+impl<EVM, ERROR, FRAME> Default for RollupHandler<EVM, ERROR, FRAME> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<EVM, ERROR, FRAME> Handler for RollupHandler<EVM, ERROR, FRAME>
+where
+    EVM: EvmTr<Context: RollupContextTr, Frame = FRAME>,
+    ERROR: EvmTrError<EVM> + From<RollupTransactionError> + FromStringError + IsTxError,
+    FRAME: FrameTr<FrameResult = FrameResult, FrameInit = FrameInit>,
+{
+    type Evm = EVM;
+    type Error = ERROR;
+    type HaltReason = RollupHaltReason;
+
+    fn validate_env(&self, evm: &mut Self::Evm) -> Result<(), Self::Error> {
+        let tx = evm.ctx().tx();
+        // Deposits are pre-verified on L1.
+        if tx.is_deposit() {
+            if tx.is_system_transaction() {
+                return Err(RollupTransactionError::DepositSystemTx.into());
+            }
+            return Ok(());
+        }
+
+        if tx.enveloped_tx().is_none() {
+            return Err(RollupTransactionError::MissingEnvelopedTx.into());
+        }
+
+        self.mainnet.validate_env(evm)
+    }
+
+    fn validate_against_state_and_deduct_caller(
+        &self,
+        evm: &mut Self::Evm,
+    ) -> Result<(), Self::Error> {
+        if evm.ctx().tx().is_deposit() {
+            return deduct_deposit_caller(evm.ctx()).map_err(From::from);
+        }
+
+        let spec = evm.ctx().rollup_spec();
+        let (block, tx, cfg, journal, chain, _) = evm.ctx().all_mut();
+
+        chain.load(journal.db_mut(), block.number(), spec)?;
+
+        let mut caller_account = journal.load_account_with_code_mut(tx.caller())?.data;
+
+        // validates account nonce and code
+        validate_account_nonce_and_code_with_components(&caller_account.account().info, tx, cfg)?;
+
+        // check additional cost and deduct it from the caller's balances
+        let mut balance = caller_account.account().info.balance;
+
+        if !cfg.is_fee_charge_disabled() {
+            let Some(enveloped_tx) = tx.enveloped_tx() else {
+                return Err(RollupTransactionError::MissingEnvelopedTx.into());
+            };
+            // The operator fee is charged for the whole gas limit, the unused part is refunded.
+            let additional_cost = chain
+                .l1_data_fee(enveloped_tx, spec)
+                .saturating_add(chain.operator_fee(enveloped_tx, tx.gas_limit(), spec));
+            let Some(new_balance) = balance.checked_sub(additional_cost) else {
+                return Err(InvalidTransaction::LackOfFundForMaxFee {
+                    fee: Box::new(additional_cost),
+                    balance: Box::new(balance),
+                }
+                .into());
+            };
+            balance = new_balance
+        }
+
+        let balance = calculate_caller_fee(balance, tx, block, cfg)?;
+
+        // make changes to the account
+        caller_account.set_balance(balance);
+        if tx.kind().is_call() {
+            caller_account.bump_nonce();
+        }
+
+        Ok(())
+    }
+
+    fn reimburse_caller(
+        &self,
+        evm: &mut Self::Evm,
+        frame_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
+    ) -> Result<(), Self::Error> {
+        let mut additional_refund = U256::ZERO;
+
+        let spec = evm.ctx().rollup_spec();
+        let ctx = evm.ctx_ref();
+        let tx = ctx.tx();
+        if !tx.is_deposit() && !ctx.cfg().is_fee_charge_disabled() {
+            if let Some(enveloped_tx) = tx.enveloped_tx() {
+                let gas = frame_result.gas();
+                let chain = ctx.chain();
+                additional_refund = chain
+                    .operator_fee(enveloped_tx, gas.limit(), spec)
+                    .saturating_sub(chain.operator_fee(enveloped_tx, gas.used(), spec));
+            }
+        }
+
+        reimburse_caller(evm.ctx(), frame_result.gas(), additional_refund).map_err(From::from)
+    }
+
+    fn reward_beneficiary(
+        &self,
+        evm: &mut Self::Evm,
+        frame_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
+    ) -> Result<(), Self::Error> {
+        // Deposits are paid on L1.
+        if evm.ctx().tx().is_deposit() {
+            return Ok(());
+        }
+
+        let spec = evm.ctx().rollup_spec();
+        let gas = frame_result.gas();
+        let ctx = evm.ctx();
+        let mainnet_fees = post_execution::tx_fees(ctx, gas);
+        let (_, tx, _, _, chain, _) = ctx.all_mut();
+        let Some(enveloped_tx) = tx.enveloped_tx() else {
+            return Err(RollupTransactionError::MissingEnvelopedTx.into());
+        };
+
+        let fees = TxFees {
+            l1_data_fee: chain.l1_data_fee(enveloped_tx, spec),
+            operator_fee: chain.operator_fee(enveloped_tx, gas.used(), spec),
+            ..mainnet_fees
+        };
+        post_execution::distribute_fees(ctx, &fees, |chain| chain.fee_policy())?;
+
+        Ok(())
+    }
+
+    fn execution_result(
+        &mut self,
+        evm: &mut Self::Evm,
+        frame_result: <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
+    ) -> Result<ExecutionResult<Self::HaltReason>, Self::Error> {
+        match core::mem::replace(evm.ctx().error(), Ok(())) {
+            Err(ContextError::Db(e)) => return Err(e.into()),
+            Err(ContextError::Custom(e)) => return Err(Self::Error::from_string(e)),
+            Ok(_) => (),
+        }
+
+        let exec_result =
+            post_execution::output(evm.ctx(), frame_result).map_haltreason(RollupHaltReason::Base);
+
+        // A halted deposit bubbles up to `catch_error`, where the mint and the nonce bump of
+        // the caller are persisted.
+        if exec_result.is_halt() && evm.ctx().tx().is_deposit() {
+            return Err(ERROR::from(RollupTransactionError::HaltedDeposit));
+        }
+        evm.ctx().journal_mut().commit_tx();
+        evm.ctx().chain_mut().clear_tx_cache();
+        evm.ctx().local_mut().clear();
+        evm.frame_stack().clear();
+
+        Ok(exec_result)
+    }
+
+    fn catch_error(
+        &self,
+        evm: &mut Self::Evm,
+        error: Self::Error,
+    ) -> Result<ExecutionResult<Self::HaltReason>, Self::Error> {
+        let is_deposit = evm.ctx().tx().is_deposit();
+        let is_tx_error = error.is_tx_error();
+        let mut output = Err(error);
+
+        // Deposit transaction can't fail so we manually handle it here.
+        if is_tx_error && is_deposit {
+            let ctx = evm.ctx();
+            let caller = ctx.tx().caller();
+            let mint = ctx.tx().mint();
+            let gas_used = ctx.tx().gas_limit();
+            persist_failed_deposit(ctx.journal_mut(), caller, mint)?;
+
+            output = Ok(ExecutionResult::Halt {
+                reason: RollupHaltReason::FailedDeposit,
+                gas_used,
+            })
+        }
+
+        // do the cleanup
+        evm.ctx().chain_mut().clear_tx_cache();
+        evm.ctx().local_mut().clear();
+        evm.frame_stack().clear();
+
+        output
+    }
+}
+
+impl<EVM, ERROR> InspectorHandler for RollupHandler<EVM, ERROR, EthFrame<EthInterpreter>>
+where
+    EVM: InspectorEvmTr<
+        Context: RollupContextTr,
+        Frame = EthFrame<EthInterpreter>,
+        Inspector: Inspector<<<Self as Handler>::Evm as EvmTr>::Context, EthInterpreter>,
+    >,
+    ERROR: EvmTrError<EVM> + From<RollupTransactionError> + FromStringError + IsTxError,
+{
+    type IT = EthInterpreter;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::builder::RollupBuilder, Deposit, FeeVaultAddresses, FeeVaults, L1DataFee,
+        RollupHaltReason, RollupSpec, RollupTransaction,
+    };
+    use revm::{
+        bytecode::Bytecode,
+        context::{CfgEnv, Context, TxEnv},
+        context_interface::{
+            result::{ExecutionResult, ResultAndState},
+            FeeKind, FeeTransfer, LocalContextTr,
+        },
+        database::InMemoryDB,
+        primitives::{address, bytes, hardfork::SpecId, Address, Bytes, U256},
+        state::AccountInfo,
+        Database, ExecuteEvm, MainContext,
+    };
+
+    const CALLER: Address = address!("0x1000000000000000000000000000000000000001");
+    const TARGET: Address = address!("0x2000000000000000000000000000000000000002");
+    const L1_FEE_VAULT: Address = address!("0x3000000000000000000000000000000000000003");
+
+    /// Hardforks of the test rollup.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    enum TestSpec {
+        #[default]
+        Genesis,
+    }
+
+    impl From<TestSpec> for SpecId {
+        fn from(_: TestSpec) -> Self {
+            SpecId::PRAGUE
+        }
+    }
+
+    impl RollupSpec for TestSpec {}
+
+    /// Chain context of the test rollup, the L1 data is priced per byte.
+    #[derive(Clone, Debug, Default)]
+    struct TestChain {
+        fee_per_byte: U256,
+    }
+
+    impl L1DataFee for TestChain {
+        type Spec = TestSpec;
+
+        fn load<DB: Database>(
+            &mut self,
+            _db: &mut DB,
+            _l2_block: U256,
+            _spec: TestSpec,
+        ) -> Result<(), DB::Error> {
+            Ok(())
+        }
+
+        fn l1_data_fee(&mut self, enveloped_tx: &[u8], _spec: TestSpec) -> U256 {
+            self.fee_per_byte * U256::from(enveloped_tx.len())
+        }
+    }
+
+    impl FeeVaults for TestChain {
+        fn fee_vaults(&self) -> FeeVaultAddresses {
+            FeeVaultAddresses {
+                l1_fee_vault: L1_FEE_VAULT,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Runs the transaction, returns its output and fee transfers.
+    fn run(tx: RollupTransaction<TxEnv>) -> (ResultAndState<RollupHaltReason>, Vec<FeeTransfer>) {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            CALLER,
+            AccountInfo {
+                balance: U256::from(1_000),
+                ..Default::default()
+            },
+        );
+        db.insert_account_info(
+            TARGET,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("fe"))),
+        );
+
+        let mut evm = Context::mainnet()
+            .with_db(db)
+            .with_cfg(CfgEnv::new_with_spec(TestSpec::Genesis))
+            .with_chain(TestChain {
+                fee_per_byte: U256::from(10),
+            })
+            .with_tx(RollupTransaction::default())
+            .build_rollup();
+        let output = evm.transact(tx).unwrap();
+        (output, evm.0.ctx.local.fee_transfers().to_vec())
+    }
+
+    #[test]
+    fn test_l1_data_fee_paid_to_vault() {
+        let tx = RollupTransaction::new(
+            TxEnv::builder()
+                .caller(CALLER)
+                .call(Address::ZERO)
+                .gas_limit(21_000)
+                .build_fill(),
+        )
+        .with_enveloped_tx(Bytes::from(vec![0u8; 10]));
+
+        let (output, fee_transfers) = run(tx);
+        assert!(output.result.is_success());
+        assert_eq!(output.state[&CALLER].info.balance, U256::from(900));
+        assert_eq!(output.state[&L1_FEE_VAULT].info.balance, U256::from(100));
+        assert_eq!(
+            fee_transfers,
+            [FeeTransfer {
+                kind: FeeKind::L1DataFee,
+                to: Some(L1_FEE_VAULT),
+                amount: U256::from(100),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_missing_enveloped_tx() {
+        let tx = RollupTransaction::new(
+            TxEnv::builder()
+                .caller(CALLER)
+                .call(Address::ZERO)
+                .gas_limit(21_000)
+                .build_fill(),
+        );
+
+        let mut evm = Context::mainnet()
+            .with_cfg(CfgEnv::new_with_spec(TestSpec::Genesis))
+            .with_chain(TestChain::default())
+            .with_tx(RollupTransaction::default())
+            .build_rollup();
+        assert!(evm.transact(tx).is_err());
+    }
+
+    #[test]
+    fn test_failed_deposit_persists_mint() {
+        let depositor = address!("0x4000000000000000000000000000000000000004");
+        let tx = RollupTransaction::new_deposit(
+            TxEnv::builder()
+                .caller(depositor)
+                .call(TARGET)
+                .gas_limit(30_000)
+                .build_fill(),
+            Deposit {
+                mint: Some(500),
+                is_system_transaction: false,
+            },
+        );
+
+        let (output, fee_transfers) = run(tx);
+        assert_eq!(
+            output.result,
+            ExecutionResult::Halt {
+                reason: RollupHaltReason::FailedDeposit,
+                gas_used: 30_000,
+            }
+        );
+        let depositor = &output.state[&depositor].info;
+        assert_eq!(depositor.balance, U256::from(500));
+        assert_eq!(depositor.nonce, 1);
+        assert!(fee_transfers.is_empty());
+    }
+}
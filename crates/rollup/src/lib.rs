@@ -0,0 +1,30 @@
+//! Building blocks for rollup variants of Revm.
+//!
+//! A rollup is described by a spec enum ([`RollupSpec`]), a transaction type that can carry
+//! deposits ([`RollupTxTr`]) and a chain context that prices the L1 data of a transaction
+//! ([`L1DataFee`]) and pays the collected fees into vaults ([`FeeVaults`]). [`RollupHandler`]
+//! and [`RollupEvm`] run transactions of any rollup made of these parts.
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc as std;
+
+pub mod api;
+pub mod deposit;
+pub mod error;
+pub mod evm;
+pub mod fee;
+pub mod handler;
+pub mod result;
+pub mod spec;
+pub mod transaction;
+
+pub use api::{RollupBuilder, RollupContextTr};
+pub use error::{IsTxError, RollupTransactionError};
+pub use evm::RollupEvm;
+pub use fee::{FeeVaultAddresses, FeeVaults, L1DataFee, TxFees};
+pub use handler::RollupHandler;
+pub use result::RollupHaltReason;
+pub use spec::RollupSpec;
+pub use transaction::{Deposit, RollupTransaction, RollupTxTr};
@@ -0,0 +1,29 @@
+//! Contains the [`RollupHaltReason`] type.
+use revm::context_interface::result::HaltReason;
+
+/// Halt reason of a rollup transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RollupHaltReason {
+    /// Base halt reason.
+    Base(HaltReason),
+    /// The deposit failed, only its mint and the nonce bump of the caller are persisted.
+    FailedDeposit,
+}
+
+impl From<HaltReason> for RollupHaltReason {
+    fn from(value: HaltReason) -> Self {
+        Self::Base(value)
+    }
+}
+
+impl TryFrom<RollupHaltReason> for HaltReason {
+    type Error = RollupHaltReason;
+
+    fn try_from(value: RollupHaltReason) -> Result<HaltReason, RollupHaltReason> {
+        match value {
+            RollupHaltReason::Base(reason) => Ok(reason),
+            RollupHaltReason::FailedDeposit => Err(value),
+        }
+    }
+}
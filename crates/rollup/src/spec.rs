@@ -0,0 +1,18 @@
+//! Contains the [`RollupSpec`] trait.
+use revm::primitives::hardfork::SpecId;
+
+/// Hardfork of a rollup.
+///
+/// Every hardfork of a rollup maps to the Ethereum [`SpecId`] whose execution rules it
+/// follows. Hardforks are ordered by activation.
+pub trait RollupSpec: Copy + Ord + Into<SpecId> {
+    /// Returns the Ethereum [`SpecId`] of the hardfork.
+    fn into_eth_spec(self) -> SpecId {
+        self.into()
+    }
+
+    /// Checks if the hardfork is enabled in the other hardfork.
+    fn is_enabled_in(self, other: Self) -> bool {
+        other <= self
+    }
+}
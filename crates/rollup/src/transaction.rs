@@ -0,0 +1,210 @@
+//! Contains the [`RollupTxTr`] trait and the [`RollupTransaction`] type.
+use auto_impl::auto_impl;
+use revm::{
+    context::TxEnv,
+    context_interface::transaction::Transaction,
+    handler::SystemCallTx,
+    primitives::{Address, Bytes, TxKind, B256, U256},
+};
+use std::vec;
+
+/// Transaction of a rollup.
+///
+/// Besides the transactions signed on L2, a rollup includes deposits that are initiated on L1.
+/// Deposits are pre-paid on L1, they are not validated and can mint value to the caller.
+#[auto_impl(&, &mut, Box, Arc)]
+pub trait RollupTxTr: Transaction {
+    /// Enveloped transaction bytes, used to compute the L1 data fee.
+    fn enveloped_tx(&self) -> Option<&Bytes>;
+
+    /// Mint of the deposit transaction.
+    fn mint(&self) -> Option<u128>;
+
+    /// Whether the transaction is a system transaction.
+    fn is_system_transaction(&self) -> bool;
+
+    /// Returns `true` if the transaction is a deposit.
+    fn is_deposit(&self) -> bool;
+}
+
+/// Deposit parts of a [`RollupTransaction`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deposit {
+    /// Value minted to the caller.
+    pub mint: Option<u128>,
+    /// Whether the transaction is a system transaction.
+    pub is_system_transaction: bool,
+}
+
+/// Rollup transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RollupTransaction<T: Transaction> {
+    /// Base transaction fields.
+    pub base: T,
+    /// An enveloped EIP-2718 typed transaction, used to compute the L1 data fee.
+    pub enveloped_tx: Option<Bytes>,
+    /// Deposit parts, set if the transaction is a deposit.
+    pub deposit: Option<Deposit>,
+}
+
+impl<T: Transaction> AsRef<T> for RollupTransaction<T> {
+    fn as_ref(&self) -> &T {
+        &self.base
+    }
+}
+
+impl<T: Transaction> RollupTransaction<T> {
+    /// Create a new rollup transaction.
+    pub fn new(base: T) -> Self {
+        Self {
+            base,
+            enveloped_tx: None,
+            deposit: None,
+        }
+    }
+
+    /// Create a new deposit.
+    pub fn new_deposit(base: T, deposit: Deposit) -> Self {
+        Self {
+            base,
+            enveloped_tx: None,
+            deposit: Some(deposit),
+        }
+    }
+
+    /// Sets the enveloped transaction bytes.
+    pub fn with_enveloped_tx(mut self, enveloped_tx: Bytes) -> Self {
+        self.enveloped_tx = Some(enveloped_tx);
+        self
+    }
+}
+
+impl Default for RollupTransaction<TxEnv> {
+    fn default() -> Self {
+        Self {
+            base: TxEnv::default(),
+            enveloped_tx: Some(vec![0x00].into()),
+            deposit: None,
+        }
+    }
+}
+
+impl<TX: Transaction + SystemCallTx> SystemCallTx for RollupTransaction<TX> {
+    fn new_system_tx_with_caller(
+        caller: Address,
+        system_contract_address: Address,
+        data: Bytes,
+    ) -> Self {
+        Self::new(TX::new_system_tx_with_caller(
+            caller,
+            system_contract_address,
+            data,
+        ))
+        .with_enveloped_tx(Bytes::default())
+    }
+}
+
+impl<T: Transaction> Transaction for RollupTransaction<T> {
+    type AccessListItem<'a>
+        = T::AccessListItem<'a>
+    where
+        T: 'a;
+    type Authorization<'a>
+        = T::Authorization<'a>
+    where
+        T: 'a;
+
+    fn tx_type(&self) -> u8 {
+        self.base.tx_type()
+    }
+
+    fn caller(&self) -> Address {
+        self.base.caller()
+    }
+
+    fn gas_limit(&self) -> u64 {
+        self.base.gas_limit()
+    }
+
+    fn value(&self) -> U256 {
+        self.base.value()
+    }
+
+    fn input(&self) -> &Bytes {
+        self.base.input()
+    }
+
+    fn nonce(&self) -> u64 {
+        self.base.nonce()
+    }
+
+    fn kind(&self) -> TxKind {
+        self.base.kind()
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        self.base.chain_id()
+    }
+
+    fn access_list(&self) -> Option<impl Iterator<Item = Self::AccessListItem<'_>>> {
+        self.base.access_list()
+    }
+
+    fn max_priority_fee_per_gas(&self) -> Option<u128> {
+        self.base.max_priority_fee_per_gas()
+    }
+
+    fn max_fee_per_gas(&self) -> u128 {
+        self.base.max_fee_per_gas()
+    }
+
+    fn gas_price(&self) -> u128 {
+        self.base.gas_price()
+    }
+
+    fn blob_versioned_hashes(&self) -> &[B256] {
+        self.base.blob_versioned_hashes()
+    }
+
+    fn max_fee_per_blob_gas(&self) -> u128 {
+        self.base.max_fee_per_blob_gas()
+    }
+
+    fn effective_gas_price(&self, base_fee: u128) -> u128 {
+        // Deposits use the gas price directly
+        if self.deposit.is_some() {
+            return self.gas_price();
+        }
+        self.base.effective_gas_price(base_fee)
+    }
+
+    fn authorization_list_len(&self) -> usize {
+        self.base.authorization_list_len()
+    }
+
+    fn authorization_list(&self) -> impl Iterator<Item = Self::Authorization<'_>> {
+        self.base.authorization_list()
+    }
+}
+
+impl<T: Transaction> RollupTxTr for RollupTransaction<T> {
+    fn enveloped_tx(&self) -> Option<&Bytes> {
+        self.enveloped_tx.as_ref()
+    }
+
+    fn mint(&self) -> Option<u128> {
+        self.deposit.as_ref().and_then(|deposit| deposit.mint)
+    }
+
+    fn is_system_transaction(&self) -> bool {
+        self.deposit
+            .as_ref()
+            .is_some_and(|deposit| deposit.is_system_transaction)
+    }
+
+    fn is_deposit(&self) -> bool {
+        self.deposit.is_some()
+    }
+}
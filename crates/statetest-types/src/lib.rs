@@ -12,6 +12,8 @@ pub mod blockchain;
 mod deserializer;
 mod env;
 mod error;
+/// OP Stack test types
+pub mod op;
 mod spec;
 mod test;
 mod test_authorization;
@@ -0,0 +1,214 @@
+//! OP Stack test types.
+//!
+//! OP Stack fixtures extend the Ethereum formats with the deposit fields of transactions and the
+//! L1 block info used for the L1 fee. Post states and networks are keyed by the OP Stack hardfork
+//! names, like `Ecotone` or `Isthmus`.
+
+use crate::{
+    blockchain::{Account, BlockHeader, State, Transaction},
+    TestUnit,
+};
+use revm::primitives::{Address, Bytes, B256, U256};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// OP Stack state test suite containing multiple test units.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct OpTestSuite(pub BTreeMap<String, OpTestUnit>);
+
+/// OP Stack state test unit.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpTestUnit {
+    /// Test unit with post states keyed by the OP Stack hardfork names.
+    #[serde(flatten)]
+    pub unit: TestUnit<String>,
+    /// Deposit fields, set if the transaction is a deposit.
+    #[serde(default)]
+    pub deposit: Option<DepositParts>,
+    /// L1 block info used for the L1 fee.
+    ///
+    /// If not set, it is loaded from the storage of the L1 block contract in the pre-state.
+    #[serde(default)]
+    pub l1_block_info: Option<OpL1BlockInfo>,
+}
+
+/// Deposit fields of a transaction.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositParts {
+    /// Hash that uniquely identifies the source of the deposit.
+    pub source_hash: B256,
+    /// Amount minted on L2.
+    #[serde(default)]
+    pub mint: Option<U256>,
+    /// Whether the deposit is a system transaction.
+    #[serde(default)]
+    pub is_system_tx: bool,
+}
+
+/// L1 block info of the L1 fee.
+///
+/// Fields that are not set are not activated in the hardfork of the test.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpL1BlockInfo {
+    /// Base fee of the L1 origin block.
+    pub l1_base_fee: U256,
+    /// L1 fee overhead, before Ecotone.
+    #[serde(default)]
+    pub l1_fee_overhead: Option<U256>,
+    /// L1 base fee scalar.
+    pub l1_base_fee_scalar: U256,
+    /// L1 blob base fee, from Ecotone.
+    #[serde(default)]
+    pub l1_blob_base_fee: Option<U256>,
+    /// L1 blob base fee scalar, from Ecotone.
+    #[serde(default)]
+    pub l1_blob_base_fee_scalar: Option<U256>,
+    /// Operator fee scalar, from Isthmus.
+    #[serde(default)]
+    pub operator_fee_scalar: Option<U256>,
+    /// Operator fee constant, from Isthmus.
+    #[serde(default)]
+    pub operator_fee_constant: Option<U256>,
+    /// DA footprint gas scalar, from Jovian.
+    #[serde(default)]
+    pub da_footprint_gas_scalar: Option<U256>,
+}
+
+/// OP Stack blockchain test suite containing multiple test cases.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct OpBlockchainTest(pub BTreeMap<String, OpBlockchainTestCase>);
+
+/// OP Stack blockchain test case.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpBlockchainTestCase {
+    /// Header of the parent of the first block.
+    pub genesis_block_header: BlockHeader,
+    /// Blocks of the test, built from payload attributes.
+    pub blocks: Vec<OpBlock>,
+    /// Post-state accounts (optional)
+    pub post_state: Option<BTreeMap<Address, Account>>,
+    /// Pre-state accounts
+    pub pre: State,
+    /// OP Stack hardfork name.
+    pub network: String,
+    /// Chain id, OP Mainnet if not set.
+    #[serde(default)]
+    pub chain_id: Option<U256>,
+}
+
+/// OP Stack block built from payload attributes.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpBlock {
+    /// Attributes of the block.
+    pub attributes: OpBlockAttributes,
+    /// Block transactions, starting with the L1 attributes deposit.
+    pub transactions: Vec<OpBlockTransaction>,
+    /// Block hash, used as the parent hash of the next block.
+    #[serde(default)]
+    pub hash: Option<B256>,
+    /// Expected exception if the block should fail.
+    pub expect_exception: Option<String>,
+}
+
+/// Payload attributes of an OP Stack block.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpBlockAttributes {
+    /// Block timestamp.
+    pub timestamp: U256,
+    /// Block prevrandao.
+    pub prev_randao: B256,
+    /// Block beneficiary.
+    pub suggested_fee_recipient: Address,
+    /// Parent beacon block root, from Ecotone.
+    #[serde(default)]
+    pub parent_beacon_block_root: Option<B256>,
+    /// Block gas limit.
+    pub gas_limit: U256,
+    /// Block base fee.
+    pub base_fee_per_gas: U256,
+}
+
+/// Transaction of an OP Stack block.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpBlockTransaction {
+    /// Fields shared with Ethereum transactions.
+    #[serde(flatten)]
+    pub tx: Transaction,
+    /// Deposit fields, set if the transaction is a deposit.
+    #[serde(flatten)]
+    pub deposit: Option<DepositParts>,
+    /// EIP-2718 encoding of the transaction, used for the L1 fee.
+    #[serde(default)]
+    pub enveloped_tx: Option<Bytes>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SpecName;
+
+    #[test]
+    fn deserialize_op_test_unit() {
+        let unit: OpTestUnit = serde_json::from_str(
+            r#"{
+                "env": {
+                    "currentCoinbase": "0x4200000000000000000000000000000000000011",
+                    "currentDifficulty": "0x00",
+                    "currentGasLimit": "0x01c9c380",
+                    "currentNumber": "0x01",
+                    "currentTimestamp": "0x03e8",
+                    "currentBaseFee": "0x07"
+                },
+                "pre": {},
+                "post": {
+                    "Ecotone": [],
+                    "Isthmus": []
+                },
+                "transaction": {
+                    "data": ["0x"],
+                    "gasLimit": ["0x5208"],
+                    "gasPrice": "0x0a",
+                    "nonce": "0x00",
+                    "secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+                    "to": "0x1000000000000000000000000000000000000000",
+                    "value": ["0x01"]
+                },
+                "deposit": {
+                    "sourceHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                    "mint": "0x02"
+                },
+                "l1BlockInfo": {
+                    "l1BaseFee": "0x03",
+                    "l1BaseFeeScalar": "0x04",
+                    "operatorFeeConstant": "0x05"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            unit.unit.post.keys().collect::<Vec<_>>(),
+            ["Ecotone", "Isthmus"]
+        );
+        let deposit = unit.deposit.unwrap();
+        assert_eq!(deposit.mint, Some(U256::from(2)));
+        assert!(!deposit.is_system_tx);
+        let l1_block_info = unit.l1_block_info.unwrap();
+        assert_eq!(l1_block_info.l1_base_fee, U256::from(3));
+        assert_eq!(l1_block_info.operator_fee_constant, Some(U256::from(5)));
+        assert_eq!(l1_block_info.l1_blob_base_fee, None);
+
+        // OP Stack hardfork names are unknown to Ethereum tests.
+        assert_eq!(
+            serde_json::from_str::<SpecName>(r#""Isthmus""#).unwrap(),
+            SpecName::Unknown
+        );
+    }
+}
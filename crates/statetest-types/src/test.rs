@@ -60,7 +60,7 @@ impl Test {
     /// Returns an error if:
     /// - The private key cannot be used to recover the sender address
     /// - The transaction type is invalid and no exception is expected
-    pub fn tx_env(&self, unit: &TestUnit) -> Result<TxEnv, TestError> {
+    pub fn tx_env<S>(&self, unit: &TestUnit<S>) -> Result<TxEnv, TestError> {
         // Setup sender
         let caller = if let Some(address) = unit.transaction.sender {
             address
@@ -9,10 +9,13 @@ use serde::Deserialize;
 use std::collections::BTreeMap;
 
 /// Single test unit struct
+///
+/// Post-execution expectations are keyed by `S`, which is [`SpecName`] for Ethereum tests.
 #[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(bound(deserialize = "S: Deserialize<'de> + Ord"))]
 //#[serde(deny_unknown_fields)]
 // field config
-pub struct TestUnit {
+pub struct TestUnit<S = SpecName> {
     /// Test info is optional.
     #[serde(default, rename = "_info")]
     pub info: Option<serde_json::Value>,
@@ -37,7 +40,7 @@ pub struct TestUnit {
     /// test results. This allows a single test to define different expected outcomes
     /// for different protocol versions, enabling comprehensive testing across
     /// multiple Ethereum upgrades.
-    pub post: BTreeMap<SpecName, Vec<Test>>,
+    pub post: BTreeMap<S, Vec<Test>>,
 
     /// Transaction details to be executed.
     ///
@@ -56,7 +59,7 @@ pub struct TestUnit {
     //pub config
 }
 
-impl TestUnit {
+impl<S> TestUnit<S> {
     /// Prepare the state from the test unit.
     ///
     /// This function uses [`TestUnit::pre`] to prepare the pre-state from the test unit.
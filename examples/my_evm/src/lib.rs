@@ -14,5 +14,10 @@ pub mod evm;
 /// including how transactions are processed and how the EVM interacts with inspectors.
 pub mod handler;
 
+/// Custom rollup built on the rollup kit.
+/// This module contains the hardforks and the chain context of MyRollup, the rollup kit
+/// provides the handler and the EVM that run its transactions.
+pub mod my_rollup;
+
 pub use evm::*;
 pub use handler::*;
@@ -2,14 +2,16 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
-use example_my_evm::{evm::MyEvm, handler::MyHandler};
+use example_my_evm::{evm::MyEvm, handler::MyHandler, my_rollup::my_rollup_context};
 use revm::{
     context::TxEnv,
     database::InMemoryDB,
     handler::{ExecuteCommitEvm, ExecuteEvm, Handler},
     inspector::InspectorHandler,
+    primitives::{Bytes, U256},
     Context, MainContext,
 };
+use rollup::{RollupBuilder, RollupTransaction};
 
 /// Example demonstrating various ways to use a custom EVM implementation.
 ///
@@ -18,6 +20,7 @@ use revm::{
 /// 2. Transaction execution with inspector support for debugging
 /// 3. Single transaction execution with state finalization
 /// 4. Transaction execution with automatic state commitment to the database
+/// 5. Transaction execution on a custom rollup built with the rollup kit
 ///
 /// Each example demonstrates a different aspect of EVM customization and usage,
 /// from simple execution to more complex patterns involving state management
@@ -40,4 +43,9 @@ pub fn main() {
     // Evm Execute Commit example
     let mut my_evm = MyEvm::new(Context::mainnet().with_db(InMemoryDB::default()), ());
     let _res = my_evm.transact_commit(TxEnv::default());
+
+    // Rollup example
+    let mut my_rollup = my_rollup_context(U256::from(16)).build_rollup();
+    let tx = RollupTransaction::new(TxEnv::default()).with_enveloped_tx(Bytes::from_static(&[0]));
+    let _res = my_rollup.transact(tx);
 }
@@ -0,0 +1,96 @@
+//! A custom rollup built on the rollup kit of `revm-rollup`.
+use revm::{
+    context::{BlockEnv, CfgEnv, TxEnv},
+    database_interface::EmptyDB,
+    primitives::{address, hardfork::SpecId, Address, U256},
+    Context, Database, Journal, MainContext,
+};
+use rollup::{FeeVaultAddresses, FeeVaults, L1DataFee, RollupSpec, RollupTransaction};
+
+/// Vault collecting the base fee.
+pub const BASE_FEE_VAULT: Address = address!("0x5300000000000000000000000000000000000001");
+/// Vault collecting the L1 data fee.
+pub const L1_FEE_VAULT: Address = address!("0x5300000000000000000000000000000000000002");
+
+/// Hardforks of MyRollup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MyRollupSpec {
+    /// First hardfork, follows the Cancun rules.
+    Genesis,
+    /// Second hardfork, follows the Prague rules.
+    #[default]
+    Upgrade,
+}
+
+impl From<MyRollupSpec> for SpecId {
+    fn from(spec: MyRollupSpec) -> Self {
+        match spec {
+            MyRollupSpec::Genesis => SpecId::CANCUN,
+            MyRollupSpec::Upgrade => SpecId::PRAGUE,
+        }
+    }
+}
+
+impl RollupSpec for MyRollupSpec {}
+
+/// Chain context of MyRollup.
+///
+/// L1 data is priced with a flat fee per byte of the enveloped transaction, which is halved by
+/// the [`MyRollupSpec::Upgrade`] hardfork.
+#[derive(Clone, Debug, Default)]
+pub struct MyRollupChain {
+    /// Fee per byte of L1 data.
+    pub l1_fee_per_byte: U256,
+}
+
+impl L1DataFee for MyRollupChain {
+    type Spec = MyRollupSpec;
+
+    fn load<DB: Database>(
+        &mut self,
+        _db: &mut DB,
+        _l2_block: U256,
+        _spec: MyRollupSpec,
+    ) -> Result<(), DB::Error> {
+        // The fee is configured by the node and not read from the state.
+        Ok(())
+    }
+
+    fn l1_data_fee(&mut self, enveloped_tx: &[u8], spec: MyRollupSpec) -> U256 {
+        let fee = self.l1_fee_per_byte * U256::from(enveloped_tx.len());
+        if spec.is_enabled_in(MyRollupSpec::Upgrade) {
+            fee / U256::from(2)
+        } else {
+            fee
+        }
+    }
+}
+
+impl FeeVaults for MyRollupChain {
+    fn fee_vaults(&self) -> FeeVaultAddresses {
+        FeeVaultAddresses {
+            base_fee_vault: BASE_FEE_VAULT,
+            l1_fee_vault: L1_FEE_VAULT,
+            // MyRollup does not charge an operator fee.
+            operator_fee_vault: Address::ZERO,
+        }
+    }
+}
+
+/// Type alias for the context of MyRollup.
+pub type MyRollupContext<DB> = Context<
+    BlockEnv,
+    RollupTransaction<TxEnv>,
+    CfgEnv<MyRollupSpec>,
+    DB,
+    Journal<DB>,
+    MyRollupChain,
+>;
+
+/// Creates the default context of MyRollup.
+pub fn my_rollup_context(l1_fee_per_byte: U256) -> MyRollupContext<EmptyDB> {
+    Context::mainnet()
+        .with_tx(RollupTransaction::default())
+        .with_cfg(CfgEnv::new_with_spec(MyRollupSpec::default()))
+        .with_chain(MyRollupChain { l1_fee_per_byte })
+}
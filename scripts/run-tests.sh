@@ -19,6 +19,9 @@ DEVNET_DEVELOP_DIR="$DEVNET_DIR/develop"
 
 LEGACY_DIR="$FIXTURES_DIR/legacytests" 
 
+# OP Stack fixtures are checked into the repository.
+OP_DIR="bins/revme/tests/op"
+
 ### URL and filenames ###
 FIXTURES_URL="https://github.com/ethereum/execution-spec-tests/releases/download"
 
@@ -153,6 +156,9 @@ run_tests() {
     echo "Running main stable statetests..."
     $RUST_RUNNER run $CARGO_OPTS -p revme -- statetest $KEEP_GOING_FLAG "$MAIN_STABLE_DIR/state_tests"
 
+    echo "Running main stable statetests with basic block execution..."
+    $RUST_RUNNER run $CARGO_OPTS -p revme -- statetest $KEEP_GOING_FLAG --basic-blocks "$MAIN_STABLE_DIR/state_tests"
+
     echo "Running main develop statetests..."
     $RUST_RUNNER run $CARGO_OPTS -p revme -- statetest $KEEP_GOING_FLAG "$MAIN_DEVELOP_DIR/state_tests"
 
@@ -162,6 +168,9 @@ run_tests() {
     echo "Running legacy Cancun tests..."
     $RUST_RUNNER run $CARGO_OPTS -p revme -- statetest $KEEP_GOING_FLAG "$LEGACY_DIR/Cancun/GeneralStateTests"
 
+    echo "Running legacy Cancun tests with basic block execution..."
+    $RUST_RUNNER run $CARGO_OPTS -p revme -- statetest $KEEP_GOING_FLAG --basic-blocks "$LEGACY_DIR/Cancun/GeneralStateTests"
+
     echo "Running legacy Constantinople tests..."
     $RUST_RUNNER run $CARGO_OPTS -p revme -- statetest $KEEP_GOING_FLAG "$LEGACY_DIR/Constantinople/GeneralStateTests"
 
@@ -170,6 +179,12 @@ run_tests() {
 
     echo "Running main stable blockchain tests..."
     $RUST_RUNNER run $CARGO_OPTS -p revme -- btest $KEEP_GOING_FLAG "$MAIN_STABLE_DIR/blockchain_tests"
+
+    echo "Running OP Stack statetests..."
+    $RUST_RUNNER run $CARGO_OPTS -p revme -- statetest $KEEP_GOING_FLAG --chain op "$OP_DIR/statetest"
+
+    echo "Running OP Stack blockchain tests..."
+    $RUST_RUNNER run $CARGO_OPTS -p revme -- btest $KEEP_GOING_FLAG --chain op "$OP_DIR/blockchaintest"
 }
 
 ##############################